@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+
+/// Adds the edges one by one, checking the memory on every addition
+fn bulk_insert(edges: &[Edge]) -> Graphs {
+    let mut graphs = Graphs::init("bulk");
+    for edge in edges.iter() {
+        graphs.add_edge(edge, None).unwrap();
+    }
+    graphs
+}
+
+fn prepare_edges(len: usize) -> Vec<Edge> {
+    let shire = Vertex::new("The Shire");
+    (0..len)
+        .map(|i| {
+            let mut hobbit = Vertex::new("Hobbit");
+            hobbit.set_attr("number", i as i64);
+            Edge::create(&hobbit, "lives at", &shire)
+        })
+        .collect()
+}
+
+// with a constant time per element the insertion is linear
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_insert");
+    group.sample_size(10);
+    for len in [1_000, 2_000, 4_000, 8_000] {
+        let edges = prepare_edges(len);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &edges, |b, edges| {
+            b.iter(|| bulk_insert(edges))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
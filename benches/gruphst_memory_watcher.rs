@@ -13,16 +13,24 @@ pub fn prepare_graphs_bench() -> Graphs {
 
     let fred = Vertex::new("Fred");
 
-    graphs.add_edge(&Edge::create(&alice, "friend of", &bob), None);
-    graphs.add_edge(&Edge::create(&bob, "friend of", &alice), None);
-    graphs.add_edge(&Edge::create(&fred, "relative of", &alice), None);
-    graphs.add_edge(&Edge::create(&fred, "friend of", &bob), None);
+    graphs
+        .add_edge(&Edge::create(&alice, "friend of", &bob), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&bob, "friend of", &alice), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&fred, "relative of", &alice), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&fred, "friend of", &bob), None)
+        .unwrap();
 
     graphs
 }
 
 fn add_edge(graphs: &mut Graphs, edge: &Edge) {
-    graphs.add_edge(edge, None);
+    graphs.add_edge(edge, None).unwrap();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -1,4 +1,5 @@
 use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+use gruphst::graphs::traversal::{Direction, TraversalOptions};
 use std::error::Error;
 
 // The idea it's to create some graph related with 
@@ -30,11 +31,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut graphs = Graphs::init("middle-earth");
     
     // Now we add the edge or relation between Gandalf and Frodo
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
 
     // We can add another relation or Edge to the graphs
     // for these two friends, e.g.
-    graphs.add_edge(&Edge::create(&frodo, "has best friend", &gandalf), None);
+    graphs.add_edge(&Edge::create(&frodo, "has best friend", &gandalf), None).unwrap();
 
     // Lets create more vertices for places and characters and edges
     // for the relation between them
@@ -45,24 +46,24 @@ fn main() -> Result<(), Box<dyn Error>> {
             &sam,
             "has best friend",
             &frodo),
-        None);
+        None).unwrap();
 
     let mut vertex = Vertex::new("The Shire");
 
     // Vertices and Edges has a uuid generated on creation
     let id_vertex_the_shire = vertex.get_id();
 
-    graphs.add_edge(&Edge::create(&frodo, "lives at", &vertex), None); 
+    graphs.add_edge(&Edge::create(&frodo, "lives at", &vertex), None).unwrap(); 
 
     vertex = Vertex::new("Isengard");
     vertex.set_attr("type", "tower");
 
-    graphs.add_edge(&Edge::create(&Vertex::new("Saruman"), "lives at", &vertex), None); 
+    graphs.add_edge(&Edge::create(&Vertex::new("Saruman"), "lives at", &vertex), None).unwrap(); 
 
     // we can use the id or the label to retrieve a Vertex that we have on Graph
     let the_shire = graphs.find_vertex_by_id(id_vertex_the_shire.as_str(), None)?;
 
-    graphs.add_edge(&Edge::create(&sam, "lives at", &the_shire), None); 
+    graphs.add_edge(&Edge::create(&sam, "lives at", &the_shire), None).unwrap(); 
 
     // Now we can do things like get stats of the Graphs
     let stats = graphs.get_stats();
@@ -100,6 +101,15 @@ fn main() -> Result<(), Box<dyn Error>> {
     let found = graphs.find_edges_with_vertex_attr_str_equals_to("years old", 24000, None)?;
     assert_eq!(found[0].get_from_vertex().get_label(), "Gandalf");
 
+    // We can walk the graph further than one hop, e.g. breadth-first
+    // from Samwise, following the outgoing edges up to a depth of 2
+    let options = TraversalOptions::bfs().direction(Direction::Out).max_depth(2);
+    let steps = graphs.traverse(&sam.get_id(), &options, None)?;
+    for (depth, vertex, edge) in steps.iter() {
+        println!("{} {} -> {}", depth, edge.get_relation(), vertex.get_label());
+    }
+    assert_eq!(steps[2].1.get_label(), "Gandalf");
+
     // Since we have a humble middle-earth network
     // we can persists it for another day
     // a file called "middle-earth.grphst" will be created, 
@@ -27,18 +27,18 @@ fn create_game_rules() -> Graphs {
     // Now we create the first relation between two edges
     // and we'll add to the vault's default graph
     let vertex = Edge::create(&rock, "crushes", &lizard);
-    graphs.add_edge(&vertex, None);
+    graphs.add_edge(&vertex, None).unwrap();
 
     // More relations will be added
-    graphs.add_edge(&Edge::create(&rock, "crushes", &scissors), None);
-    graphs.add_edge(&Edge::create(&lizard, "poisons", &spock), None);
-    graphs.add_edge(&Edge::create(&lizard, "eats", &paper), None);
-    graphs.add_edge(&Edge::create(&spock, "smashes", &scissors), None);
-    graphs.add_edge(&Edge::create(&spock, "vaporizes", &rock), None);
-    graphs.add_edge(&Edge::create(&scissors, "cuts", &paper), None);
-    graphs.add_edge(&Edge::create(&scissors, "decapites", &lizard), None);
-    graphs.add_edge(&Edge::create(&paper, "covers", &rock), None);
-    graphs.add_edge(&Edge::create(&paper, "disproves", &spock), None);
+    graphs.add_edge(&Edge::create(&rock, "crushes", &scissors), None).unwrap();
+    graphs.add_edge(&Edge::create(&lizard, "poisons", &spock), None).unwrap();
+    graphs.add_edge(&Edge::create(&lizard, "eats", &paper), None).unwrap();
+    graphs.add_edge(&Edge::create(&spock, "smashes", &scissors), None).unwrap();
+    graphs.add_edge(&Edge::create(&spock, "vaporizes", &rock), None).unwrap();
+    graphs.add_edge(&Edge::create(&scissors, "cuts", &paper), None).unwrap();
+    graphs.add_edge(&Edge::create(&scissors, "decapites", &lizard), None).unwrap();
+    graphs.add_edge(&Edge::create(&paper, "covers", &rock), None).unwrap();
+    graphs.add_edge(&Edge::create(&paper, "disproves", &spock), None).unwrap();
 
     graphs
 }
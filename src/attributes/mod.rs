@@ -0,0 +1,386 @@
+//! Attributes module
+//!
+//! Typed values for the attributes of vertices and edges
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Bound, RangeBounds},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::GruPHstError;
+
+/// Value of an attribute of a vertex or an edge
+///
+/// # Examples
+/// ```rust
+/// use gruphst::{attributes::AttrValue, vertex::Vertex};
+///
+/// let mut gandalf = Vertex::new("Gandalf");
+/// gandalf.set_attr("years old", 24000);
+/// gandalf.set_attr("wizard", true);
+/// gandalf.set_attr("colors", AttrValue::List(vec!["gray".into(), "white".into()]));
+///
+/// assert_eq!(gandalf.get_attr_i64("years old").unwrap(), 24000);
+/// assert_eq!(gandalf.get_attr_value("wizard").unwrap(), AttrValue::Bool(true));
+/// assert_eq!(gandalf.get_attr("colors").unwrap(), "[gray, white]");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AttrValue {
+    /// Integer number
+    Int(i64),
+    /// Floating point number
+    Float(f64),
+    /// Boolean
+    Bool(bool),
+    /// Text
+    Str(String),
+    /// Raw bytes
+    Bytes(Vec<u8>),
+    /// Milliseconds since the Unix epoch
+    Timestamp(i64),
+    /// Collection of values
+    List(Vec<AttrValue>),
+}
+
+/// Type of an attribute value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AttrType {
+    Int,
+    Float,
+    Bool,
+    Str,
+    Bytes,
+    Timestamp,
+    List,
+}
+
+impl AttrType {
+    /// Name of the type
+    pub fn name(&self) -> &'static str {
+        match self {
+            AttrType::Int => "integer",
+            AttrType::Float => "float",
+            AttrType::Bool => "bool",
+            AttrType::Str => "string",
+            AttrType::Bytes => "bytes",
+            AttrType::Timestamp => "timestamp",
+            AttrType::List => "list",
+        }
+    }
+}
+
+impl AttrValue {
+    /// Creates a timestamp value for the current time
+    pub fn now() -> Self {
+        AttrValue::from(SystemTime::now())
+    }
+
+    /// Name of the type of the value
+    pub fn type_name(&self) -> &'static str {
+        self.get_type().name()
+    }
+
+    /// Type of the value
+    pub fn get_type(&self) -> AttrType {
+        match self {
+            AttrValue::Int(_) => AttrType::Int,
+            AttrValue::Float(_) => AttrType::Float,
+            AttrValue::Bool(_) => AttrType::Bool,
+            AttrValue::Str(_) => AttrType::Str,
+            AttrValue::Bytes(_) => AttrType::Bytes,
+            AttrValue::Timestamp(_) => AttrType::Timestamp,
+            AttrValue::List(_) => AttrType::List,
+        }
+    }
+
+    /// Retrieves the value as integer, only for integers
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            AttrValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value as float, for integers and floats
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AttrValue::Int(v) => Some(*v as f64),
+            AttrValue::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value as boolean, only for booleans
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttrValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value as text, only for strings
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value as bytes, only for bytes
+    pub fn as_bytes(&self) -> Option<&Vec<u8>> {
+        match self {
+            AttrValue::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the milliseconds since the Unix epoch, only for timestamps
+    pub fn as_timestamp(&self) -> Option<i64> {
+        match self {
+            AttrValue::Timestamp(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the collection of values, only for lists
+    pub fn as_list(&self) -> Option<&Vec<AttrValue>> {
+        match self {
+            AttrValue::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Checks if the value is raw bytes
+    pub fn is_bytes(&self) -> bool {
+        matches!(self, AttrValue::Bytes(_))
+    }
+
+    /// Compares two values, integers and floats are compared as numbers,
+    /// other types only with values of the same type;
+    /// None when the values are not comparable
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::cmp::Ordering;
+    /// use gruphst::attributes::AttrValue;
+    ///
+    /// assert_eq!(AttrValue::Int(2).compare(&AttrValue::Float(1.5)), Some(Ordering::Greater));
+    /// assert_eq!(AttrValue::from("a").compare(&AttrValue::from("b")), Some(Ordering::Less));
+    /// assert_eq!(AttrValue::from("2").compare(&AttrValue::Int(2)), None);
+    /// ```
+    pub fn compare(&self, other: &AttrValue) -> Option<Ordering> {
+        match (self, other) {
+            (AttrValue::Int(a), AttrValue::Int(b)) => Some(a.cmp(b)),
+            (AttrValue::Int(_) | AttrValue::Float(_), AttrValue::Int(_) | AttrValue::Float(_)) => {
+                self.as_f64()?.partial_cmp(&other.as_f64()?)
+            }
+            (AttrValue::Bool(a), AttrValue::Bool(b)) => Some(a.cmp(b)),
+            (AttrValue::Str(a), AttrValue::Str(b)) => Some(a.cmp(b)),
+            (AttrValue::Bytes(a), AttrValue::Bytes(b)) => Some(a.cmp(b)),
+            (AttrValue::Timestamp(a), AttrValue::Timestamp(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// Checks if the value is inside of the range,
+    /// values not comparable with the bounds are not inside
+    pub(crate) fn is_within(&self, range: &AttrRange) -> bool {
+        let after_start = match &range.0 {
+            Bound::Included(start) => matches!(
+                self.compare(start),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Bound::Excluded(start) => self.compare(start) == Some(Ordering::Greater),
+            Bound::Unbounded => true,
+        };
+        let before_end = match &range.1 {
+            Bound::Included(end) => {
+                matches!(self.compare(end), Some(Ordering::Less | Ordering::Equal))
+            }
+            Bound::Excluded(end) => self.compare(end) == Some(Ordering::Less),
+            Bound::Unbounded => true,
+        };
+        after_start && before_end
+    }
+
+    /// Checks if both values are equal, integers and floats
+    /// are compared as numbers, other types by their value
+    pub(crate) fn equals(&self, other: &AttrValue) -> bool {
+        match self.compare(other) {
+            Some(ordering) => ordering == Ordering::Equal,
+            None => self == other,
+        }
+    }
+}
+
+/// Range of attribute values
+pub(crate) type AttrRange = (Bound<AttrValue>, Bound<AttrValue>);
+
+/// Converts any range, like `10..=50` or `1000..`, to a range of attribute values
+pub(crate) fn attr_range<R, T>(range: &R) -> AttrRange
+where
+    R: RangeBounds<T>,
+    T: Into<AttrValue> + Clone,
+{
+    let convert = |bound: Bound<&T>| match bound {
+        Bound::Included(v) => Bound::Included(v.clone().into()),
+        Bound::Excluded(v) => Bound::Excluded(v.clone().into()),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (convert(range.start_bound()), convert(range.end_bound()))
+}
+
+impl fmt::Display for AttrValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttrValue::Int(v) => write!(f, "{}", v),
+            AttrValue::Float(v) => write!(f, "{}", v),
+            AttrValue::Bool(v) => write!(f, "{}", v),
+            AttrValue::Str(v) => write!(f, "{}", v),
+            AttrValue::Bytes(v) => write!(f, "{:?}", v),
+            AttrValue::Timestamp(v) => write!(f, "{}", v),
+            AttrValue::List(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+macro_rules! attr_value_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for AttrValue {
+                fn from(v: $t) -> Self {
+                    AttrValue::Int(i64::from(v))
+                }
+            }
+        )*
+    };
+}
+
+attr_value_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! attr_value_from_uint {
+    ($($t:ty),*) => {
+        $(
+            /// Stored as integer when it fits, otherwise as float
+            impl From<$t> for AttrValue {
+                fn from(v: $t) -> Self {
+                    match i64::try_from(v) {
+                        Ok(v) => AttrValue::Int(v),
+                        Err(_) => AttrValue::Float(v as f64),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+attr_value_from_uint!(u64, usize);
+
+impl From<f32> for AttrValue {
+    fn from(v: f32) -> Self {
+        AttrValue::Float(f64::from(v))
+    }
+}
+
+impl From<f64> for AttrValue {
+    fn from(v: f64) -> Self {
+        AttrValue::Float(v)
+    }
+}
+
+impl From<bool> for AttrValue {
+    fn from(v: bool) -> Self {
+        AttrValue::Bool(v)
+    }
+}
+
+impl From<&str> for AttrValue {
+    fn from(v: &str) -> Self {
+        AttrValue::Str(v.to_string())
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(v: String) -> Self {
+        AttrValue::Str(v)
+    }
+}
+
+impl From<&String> for AttrValue {
+    fn from(v: &String) -> Self {
+        AttrValue::Str(v.clone())
+    }
+}
+
+impl From<&[u8]> for AttrValue {
+    fn from(v: &[u8]) -> Self {
+        AttrValue::Bytes(v.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for AttrValue {
+    fn from(v: Vec<u8>) -> Self {
+        AttrValue::Bytes(v)
+    }
+}
+
+impl From<SystemTime> for AttrValue {
+    fn from(v: SystemTime) -> Self {
+        let millis = match v.duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_millis() as i64,
+            Err(before) => -(before.duration().as_millis() as i64),
+        };
+        AttrValue::Timestamp(millis)
+    }
+}
+
+impl From<Vec<AttrValue>> for AttrValue {
+    fn from(v: Vec<AttrValue>) -> Self {
+        AttrValue::List(v)
+    }
+}
+
+/// Retrieves a typed value from an attribute,
+/// failing when the attribute does not exists or has another type
+pub(crate) fn typed_attr<T>(
+    attr_k: &str,
+    value: Option<&AttrValue>,
+    expected: &str,
+    convert: impl FnOnce(&AttrValue) -> Option<T>,
+) -> Result<T, GruPHstError> {
+    match value {
+        Some(value) => match convert(value) {
+            Some(v) => Ok(v),
+            None => {
+                warn!(
+                    "attribute '{}' is {} not {}",
+                    attr_k,
+                    value.type_name(),
+                    expected
+                );
+                Err(GruPHstError::AttributeTypeMismatch(
+                    attr_k.to_string(),
+                    expected.to_string(),
+                ))
+            }
+        },
+        None => {
+            warn!("attribute '{}' not found", attr_k);
+            Err(GruPHstError::AttributeNotFound)
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Configuration module
 
+use std::time::Duration;
+
 use dotenvy::dotenv;
 use log::warn;
 
-// TODO: add something to auto-persists, like a deamon? or something on every CUD?
+use crate::graphs::{AutoPersist, Compression, MemoryPolicy};
 
 const GRUPHST_MAX_MEM_USAGE: &str = "GRUPHST_MAX_MEM_USAGE";
 const DEFAULT_GRUPHST_MAX_MEM_USAGE: usize = 25 * 1024 * 1024;
@@ -102,3 +104,227 @@ pub fn get_csv_delimiter() -> u8 {
         }
     }
 }
+
+const GRUPHST_WAL_COMPACT_AFTER: &str = "GRUPHST_WAL_COMPACT_AFTER";
+const DEFAULT_GRUPHST_WAL_COMPACT_AFTER: usize = 1000;
+
+/// Read the number of records on the write-ahead log
+/// before compacting it into a fresh snapshot, from .env file
+/// or setted environmental variable
+/// if not exists returns default value that is 1000
+///
+/// # Example
+/// ```rust
+/// use gruphst::config::get_wal_compact_after;
+///
+/// let compact_after = get_wal_compact_after();
+/// ```
+pub fn get_wal_compact_after() -> usize {
+    dotenv().ok();
+    match dotenvy::var(GRUPHST_WAL_COMPACT_AFTER) {
+        Ok(value) => match value.trim().parse() {
+            Ok(records) => records,
+            Err(_) => {
+                warn!(
+                    "Not valid config for {}, using default value: {}",
+                    GRUPHST_WAL_COMPACT_AFTER, DEFAULT_GRUPHST_WAL_COMPACT_AFTER
+                );
+                DEFAULT_GRUPHST_WAL_COMPACT_AFTER
+            }
+        },
+        Err(_) => {
+            warn!(
+                "No config for {}, using default value: {}",
+                GRUPHST_WAL_COMPACT_AFTER, DEFAULT_GRUPHST_WAL_COMPACT_AFTER
+            );
+            DEFAULT_GRUPHST_WAL_COMPACT_AFTER
+        }
+    }
+}
+
+const GRUPHST_COMPRESSION: &str = "GRUPHST_COMPRESSION";
+const DEFAULT_GRUPHST_COMPRESSION: Compression = Compression::None;
+
+/// Read the compression codec for the saved Graphs from .env file
+/// or setted environmental variable, case insensitive, possible values:
+/// "none" and "lz4", that needs the lz4 feature.
+/// If not exists or not valid returns default value that is no compression
+///
+/// # Example
+/// ```rust
+/// use gruphst::config::get_compression;
+///
+/// let compression = get_compression();
+/// ```
+pub fn get_compression() -> Compression {
+    dotenv().ok();
+    match dotenvy::var(GRUPHST_COMPRESSION) {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "none" => Compression::None,
+            #[cfg(feature = "lz4")]
+            "lz4" => Compression::Lz4,
+            _ => {
+                warn!(
+                    "Not valid config for {}, using default value: {:?}",
+                    GRUPHST_COMPRESSION, DEFAULT_GRUPHST_COMPRESSION
+                );
+                DEFAULT_GRUPHST_COMPRESSION
+            }
+        },
+        Err(_) => {
+            warn!(
+                "No config for {}, using default value: {:?}",
+                GRUPHST_COMPRESSION, DEFAULT_GRUPHST_COMPRESSION
+            );
+            DEFAULT_GRUPHST_COMPRESSION
+        }
+    }
+}
+
+const GRUPHST_AUTO_PERSIST: &str = "GRUPHST_AUTO_PERSIST";
+const DEFAULT_GRUPHST_AUTO_PERSIST: AutoPersist = AutoPersist::Off;
+const GRUPHST_AUTO_PERSIST_MUTATIONS: &str = "GRUPHST_AUTO_PERSIST_MUTATIONS";
+const DEFAULT_GRUPHST_AUTO_PERSIST_MUTATIONS: usize = 100;
+const GRUPHST_AUTO_PERSIST_INTERVAL: &str = "GRUPHST_AUTO_PERSIST_INTERVAL";
+const DEFAULT_GRUPHST_AUTO_PERSIST_INTERVAL: u64 = 60;
+
+/// Read the auto-persist policy from .env file
+/// or setted environmental variable, case insensitive, possible values:
+/// "off", "write" to save after every mutation,
+/// "mutations" to save every GRUPHST_AUTO_PERSIST_MUTATIONS mutations, 100 by default,
+/// and "interval" to save every GRUPHST_AUTO_PERSIST_INTERVAL seconds, 60 by default,
+/// on a timer only for a SharedGraphs, a Graphs is saved on its first mutation after it.
+/// If not exists or not valid returns default value that is "off"
+///
+/// # Example
+/// ```rust
+/// use gruphst::config::get_auto_persist;
+///
+/// let auto_persist = get_auto_persist();
+/// ```
+pub fn get_auto_persist() -> AutoPersist {
+    dotenv().ok();
+    match dotenvy::var(GRUPHST_AUTO_PERSIST) {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "off" => AutoPersist::Off,
+            "write" => AutoPersist::OnWrite,
+            "mutations" => AutoPersist::EveryMutations(get_number(
+                GRUPHST_AUTO_PERSIST_MUTATIONS,
+                DEFAULT_GRUPHST_AUTO_PERSIST_MUTATIONS,
+            )),
+            "interval" => AutoPersist::Interval(Duration::from_secs(get_number(
+                GRUPHST_AUTO_PERSIST_INTERVAL,
+                DEFAULT_GRUPHST_AUTO_PERSIST_INTERVAL,
+            ))),
+            _ => {
+                warn!(
+                    "Not valid config for {}, using default value: {:?}",
+                    GRUPHST_AUTO_PERSIST, DEFAULT_GRUPHST_AUTO_PERSIST
+                );
+                DEFAULT_GRUPHST_AUTO_PERSIST
+            }
+        },
+        Err(_) => {
+            warn!(
+                "No config for {}, using default value: {:?}",
+                GRUPHST_AUTO_PERSIST, DEFAULT_GRUPHST_AUTO_PERSIST
+            );
+            DEFAULT_GRUPHST_AUTO_PERSIST
+        }
+    }
+}
+
+const GRUPHST_AUTO_PERSIST_PATH: &str = "GRUPHST_AUTO_PERSIST_PATH";
+
+/// Read the path where the auto-persisted Graphs are saved
+/// from .env file or setted environmental variable,
+/// if not exists returns None, for the current directory
+///
+/// # Example
+/// ```rust
+/// use gruphst::config::get_auto_persist_path;
+///
+/// let auto_persist_path = get_auto_persist_path();
+/// ```
+pub fn get_auto_persist_path() -> Option<String> {
+    dotenv().ok();
+    dotenvy::var(GRUPHST_AUTO_PERSIST_PATH)
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+}
+
+const GRUPHST_MEMORY_POLICY: &str = "GRUPHST_MEMORY_POLICY";
+const DEFAULT_GRUPHST_MEMORY_POLICY: MemoryPolicy = MemoryPolicy::Panic;
+
+/// Read what to do when a write would exceed GRUPHST_MAX_MEM_USAGE
+/// from .env file or setted environmental variable, case insensitive, possible values:
+/// "reject" to fail the write, "evict" to evict the least recently used vaults to disk
+/// and "panic" to save the Graphs and panic.
+/// If not exists or not valid returns default value that is "panic"
+///
+/// # Example
+/// ```rust
+/// use gruphst::config::get_memory_policy;
+///
+/// let memory_policy = get_memory_policy();
+/// ```
+pub fn get_memory_policy() -> MemoryPolicy {
+    dotenv().ok();
+    match dotenvy::var(GRUPHST_MEMORY_POLICY) {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "reject" => MemoryPolicy::Reject,
+            "evict" => MemoryPolicy::Evict,
+            "panic" => MemoryPolicy::Panic,
+            _ => {
+                warn!(
+                    "Not valid config for {}, using default value: {:?}",
+                    GRUPHST_MEMORY_POLICY, DEFAULT_GRUPHST_MEMORY_POLICY
+                );
+                DEFAULT_GRUPHST_MEMORY_POLICY
+            }
+        },
+        Err(_) => {
+            warn!(
+                "No config for {}, using default value: {:?}",
+                GRUPHST_MEMORY_POLICY, DEFAULT_GRUPHST_MEMORY_POLICY
+            );
+            DEFAULT_GRUPHST_MEMORY_POLICY
+        }
+    }
+}
+
+const GRUPHST_EVICTION_PATH: &str = "GRUPHST_EVICTION_PATH";
+
+/// Read the path where the evicted vaults are written
+/// from .env file or setted environmental variable,
+/// if not exists returns None, for the temporary directory
+///
+/// # Example
+/// ```rust
+/// use gruphst::config::get_eviction_path;
+///
+/// let eviction_path = get_eviction_path();
+/// ```
+pub fn get_eviction_path() -> Option<String> {
+    dotenv().ok();
+    dotenvy::var(GRUPHST_EVICTION_PATH)
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+}
+
+/// Reads a number, or the default one when not exists or not valid
+fn get_number<T>(var: &str, default: T) -> T
+where
+    T: std::str::FromStr + std::fmt::Display,
+{
+    match dotenvy::var(var).map(|value| value.trim().parse()) {
+        Ok(Ok(number)) => number,
+        _ => {
+            warn!(
+                "No valid config for {}, using default value: {}",
+                var, default
+            );
+            default
+        }
+    }
+}
@@ -1,31 +1,42 @@
 //! Edge modules
 
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::collections::HashMap;
 
 use log::warn;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    attributes::{typed_attr, AttrValue},
     errors::GruPHstError,
-    vertex::{Vertex, Vertex_},
+    vertex::{Vertex, VertexRef},
 };
 
 mod queries;
 
 /// Representation of a Edge, that consists on the relation of two vertices
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     /// A Edge has an uuid
-    id: String,
+    pub(crate) id: String,
     /// A label for the relation
-    relation: String,
+    pub(crate) relation: String,
     /// Origin vertex
-    from: Rc<RefCell<Vertex_>>,
+    pub(crate) from: VertexRef,
     /// Target vertex
-    to: Rc<RefCell<Vertex_>>,
+    pub(crate) to: VertexRef,
     /// Attributes for the Edge
-    attr: HashMap<String, String>,
+    pub(crate) attr: HashMap<String, AttrValue>,
+}
+
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.relation == other.relation
+            && self.attr == other.attr
+            && self.get_from_vertex() == other.get_from_vertex()
+            && self.get_to_vertex() == other.get_to_vertex()
+    }
 }
 
 impl Edge {
@@ -78,9 +89,9 @@ impl Edge {
     ///     &Vertex::new("Sam"));
     /// ```
     pub fn add_relation(&mut self, from: &Vertex, relation: &str, to: &Vertex) {
-        self.from = Rc::clone(&from.vrtx);
+        self.from = from.vrtx.clone();
         self.relation = String::from(relation);
-        self.to = Rc::clone(&to.vrtx);
+        self.to = to.vrtx.clone();
     }
 
     /// Creates an Edge,
@@ -97,8 +108,8 @@ impl Edge {
     /// ```
     pub fn create(from: &Vertex, relation: &str, to: &Vertex) -> Self {
         let mut v = Edge::new(relation);
-        v.from = Rc::clone(&from.vrtx);
-        v.to = Rc::clone(&to.vrtx);
+        v.from = from.vrtx.clone();
+        v.to = to.vrtx.clone();
         v
     }
 
@@ -109,12 +120,12 @@ impl Edge {
 
     /// Updates the "from" or source edge in Edge
     pub fn update_from(&mut self, from_vertex: &Vertex) {
-        self.from = Rc::clone(&from_vertex.vrtx);
+        self.from = from_vertex.vrtx.clone();
     }
 
     /// Updates the "to" or target edge in Edge
     pub fn update_to(&mut self, to_vertex: &Vertex) {
-        self.to = Rc::clone(&to_vertex.vrtx);
+        self.to = to_vertex.vrtx.clone();
     }
 
     /// Retrieves the "From" or source vertex of edge or the relation
@@ -141,33 +152,88 @@ impl Edge {
         self.relation = relation_label.to_string();
     }
 
-    /// Set an attribute for a edge
+    /// Set an attribute for a edge, the value keeps its type
     pub fn set_attr<T>(&mut self, attr_k: &str, attr_v: T)
     where
-        T: std::fmt::Display,
+        T: Into<AttrValue>,
     {
-        self.attr.insert(attr_k.to_string(), attr_v.to_string());
+        self.attr.insert(attr_k.to_string(), attr_v.into());
     }
 
-    /// Get attribute for a edge
-    pub fn get_attr(&self, attr_k: &str) -> Result<&String, GruPHstError> {
-        let res = self.attr.get(attr_k);
-        match res {
-            Some(res) => Ok(res),
-            None => {
-                warn!("attribute '{}' not found", attr_k);
-                Err(GruPHstError::AttributeNotFound)
-            }
-        }
+    /// Set attribute of type Vec<u8> for a edge
+    pub fn set_attr_vec_u8(&mut self, attr_k: &str, attr_v: &[u8]) {
+        self.set_attr(attr_k, attr_v);
+    }
+
+    /// Get attribute for a edge, as text,
+    /// fails for Vec<u8> attributes
+    pub fn get_attr(&self, attr_k: &str) -> Result<String, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "string", |v| {
+            (!v.is_bytes()).then(|| v.to_string())
+        })
+    }
+
+    /// Get the typed value of an attribute for a edge
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{attributes::AttrValue, edge::Edge, vertex::Vertex};
+    ///
+    /// let mut edge = Edge::create(&Vertex::new("Bree"), "road to", &Vertex::new("Rivendell"));
+    /// edge.set_attr("km", 300.5);
+    ///
+    /// assert_eq!(edge.get_attr_value("km").unwrap(), AttrValue::Float(300.5));
+    /// assert_eq!(edge.get_attr_f64("km").unwrap(), 300.5);
+    /// ```
+    pub fn get_attr_value(&self, attr_k: &str) -> Result<AttrValue, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "any", |v| Some(v.clone()))
+    }
+
+    /// Get attribute of type integer
+    pub fn get_attr_i64(&self, attr_k: &str) -> Result<i64, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "integer", AttrValue::as_i64)
+    }
+
+    /// Get attribute of type float, integers are converted
+    pub fn get_attr_f64(&self, attr_k: &str) -> Result<f64, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "float", AttrValue::as_f64)
+    }
+
+    /// Get attribute of type bool
+    pub fn get_attr_bool(&self, attr_k: &str) -> Result<bool, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "bool", AttrValue::as_bool)
+    }
+
+    /// Get attribute of type timestamp, as milliseconds since the Unix epoch
+    pub fn get_attr_timestamp(&self, attr_k: &str) -> Result<i64, GruPHstError> {
+        typed_attr(
+            attr_k,
+            self.attr.get(attr_k),
+            "timestamp",
+            AttrValue::as_timestamp,
+        )
+    }
+
+    /// Get attribute of type list
+    pub fn get_attr_list(&self, attr_k: &str) -> Result<Vec<AttrValue>, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "list", |v| {
+            v.as_list().cloned()
+        })
+    }
+
+    /// Get attribute of type Vec<u8>
+    pub fn get_attr_vec_u8(&self, attr_k: &str) -> Result<Vec<u8>, GruPHstError> {
+        typed_attr(attr_k, self.attr.get(attr_k), "bytes", |v| {
+            v.as_bytes().cloned()
+        })
     }
 
     /// Updates the value of an attribute
     pub fn update_attr<T>(&mut self, attr_k: &str, attr_v: T) -> Result<(), GruPHstError>
     where
-        T: std::fmt::Display,
+        T: Into<AttrValue>,
     {
         if let Some(attr) = self.attr.get_mut(attr_k) {
-            *attr = attr_v.to_string();
+            *attr = attr_v.into();
             return Ok(());
         }
         warn!("attribute {} not found for update", attr_k);
@@ -177,13 +243,9 @@ impl Edge {
     /// Updates the value of an attribute or creates a new one if attribute key does not exists
     pub fn upsert_attr<T>(&mut self, attr_k: &str, attr_v: T)
     where
-        T: std::fmt::Display,
+        T: Into<AttrValue>,
     {
-        if let Some(attr) = self.attr.get_mut(attr_k) {
-            *attr = attr_v.to_string();
-        } else {
-            self.attr.insert(attr_k.to_string(), attr_v.to_string());
-        }
+        self.set_attr(attr_k, attr_v);
     }
 
     /// Deletes an attribute
@@ -1,3 +1,6 @@
+use std::ops::RangeBounds;
+
+use crate::attributes::{attr_range, AttrRange, AttrValue};
 use crate::edge::Edge;
 
 mod vertices;
@@ -25,10 +28,32 @@ impl Edge {
         T: std::fmt::Display + std::clone::Clone,
     {
         match self.attr.get(attr_k) {
-            Some(val) => {
-                let v = attr_v.clone();
-                *val == v.to_string()
-            }
+            Some(val) if !val.is_bytes() => val.to_string() == attr_v.to_string(),
+            _ => false,
+        }
+    }
+
+    /// Checks if an attribute value is inside of a range,
+    /// integers and floats are compared as numbers,
+    /// other types only with bounds of the same type
+    pub fn has_attr_in_range<R, T>(&self, attr_k: &str, range: R) -> bool
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        self.has_attr_within(attr_k, &attr_range(&range))
+    }
+
+    pub(crate) fn has_attr_within(&self, attr_k: &str, range: &AttrRange) -> bool {
+        match self.attr.get(attr_k) {
+            Some(val) => val.is_within(range),
+            None => false,
+        }
+    }
+
+    pub(crate) fn has_attr_equal(&self, attr_k: &str, attr_v: &AttrValue) -> bool {
+        match self.attr.get(attr_k) {
+            Some(val) => val.equals(attr_v),
             None => false,
         }
     }
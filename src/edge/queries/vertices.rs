@@ -1,5 +1,11 @@
-use crate::{edge::Edge, errors::GruPHstError, vertex::Vertex};
+use crate::{
+    attributes::{attr_range, AttrRange, AttrValue},
+    edge::Edge,
+    errors::GruPHstError,
+    vertex::Vertex,
+};
 use log::warn;
+use std::ops::RangeBounds;
 
 impl Edge {
     /// Checks if "from" or "to" vertices has any attribute key
@@ -60,6 +66,20 @@ impl Edge {
                 .has_attr_vec_u8_equals_to(attr_k, attr_v)
     }
 
+    /// Checks if "from" or "to" vertex has an attribute inside of a range
+    pub fn has_vertex_with_attr_in_range<R, T>(&self, attr_k: &str, range: R) -> bool
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        self.has_vertex_with_attr_within(attr_k, &attr_range(&range))
+    }
+
+    pub(crate) fn has_vertex_with_attr_within(&self, attr_k: &str, range: &AttrRange) -> bool {
+        self.get_from_vertex().has_attr_within(attr_k, range)
+            || self.get_to_vertex().has_attr_within(attr_k, range)
+    }
+
     /// finds a Vertex by id on an Edge on "from" and "to" vertices
     pub fn find_vertex_by_id(&self, id: &str) -> Result<Vertex, GruPHstError> {
         if self.get_from_vertex().get_id() == *id {
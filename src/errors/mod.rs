@@ -8,22 +8,64 @@ pub enum GruPHstError {
     AttributeNotFound,
     #[error("Attributes empty")]
     AttributesEmpty,
+    #[error("Attribute \"{0}\" is not of type {1}")]
+    AttributeTypeMismatch(String, String),
     #[error("Vertex not found")]
     VertexNotFound,
+    #[error("Vertex \"{0}\" still has Edges")]
+    VertexHasEdges(String),
     #[error("Edge not found")]
     EdgeNotFound,
     #[error("No relations: \"{0}\" on Edges")]
     EdgeNoRelations(String),
+    #[error("Edge weight \"{0}\" is not a valid number")]
+    EdgeWeightNotValid(String),
+    #[error("No path found from \"{0}\" to \"{1}\"")]
+    NoPathFound(String, String),
+    #[error("Query syntax error at position {0}: {1}")]
+    QuerySyntax(usize, String),
+    #[error("Query unknown variable \"{1}\" at position {0}")]
+    QueryUnknownVariable(usize, String),
     #[error("Vault is empy; no Edges")]
     VaultEmpty,
     #[error("Provided vault: \"{0}\" does not exists")]
     VaultNotExists(String),
+    #[error("Vault \"{0}\" is on disk and could not be loaded")]
+    VaultEvicted(String),
     #[error("No vaults on Grpahs")]
     NoVaultOnGraphs,
+    #[error("Memory usage limit exceeded, check GRUPHST_MAX_MEM_USAGE var")]
+    MemoryLimitExceeded,
     #[error("Persisted file excedes max memory usage, check GRUPHST_MAX_MEM_USAGE var")]
     PersistenceFile,
+    #[error("Not a GruPHst snapshot file")]
+    SnapshotBadMagic,
+    #[error("Unsupported snapshot format version {0}")]
+    SnapshotUnsupportedVersion(u32),
+    #[error("Snapshot checksum mismatch, the file is corrupted")]
+    SnapshotChecksumMismatch,
+    #[error("Unsupported snapshot compression codec {0}, check the enabled features")]
+    SnapshotUnsupportedCompression(u8),
+    #[error("Unsupported snapshot cipher {0}")]
+    SnapshotUnsupportedCipher(u8),
+    #[error("Snapshot is encrypted, a key is needed to load it")]
+    SnapshotEncrypted,
+    #[error("Wrong key to decrypt the snapshot")]
+    SnapshotWrongKey,
+    #[error("Snapshot could not be encrypted")]
+    SnapshotEncryptionFailed,
     #[error("Not valid file on path")]
     NotValidFileNameOnPath,
+    #[error("Mutation not written on the write-ahead log: {0}")]
+    WalWriteFailed(String),
+    #[error("Graphs not opened with a write-ahead log")]
+    NoWriteAheadLog,
+    #[error("Graphs not opened or saved on a vault store")]
+    NoVaultStore,
+    #[error("Index on attribute \"{0}\" not found")]
+    IndexNotFound(String),
+    #[error("Schema violation: {0}")]
+    SchemaViolation(String),
     #[error("CSV file empty")]
     CSVEmpty,
     #[error("CSV row missing relation value for edge")]
@@ -29,11 +29,11 @@ pub(super) fn generate_graphs_from_csv(
     csv_rows: &[CSVRow],
 ) -> Result<Graphs, GruPHstError> {
     let mut graphs = Graphs::init(graphs_name);
-    create_vaults_from_csv(&mut graphs, csv_rows);
+    create_vaults_from_csv(&mut graphs, csv_rows)?;
     for csv_row in csv_rows.iter() {
         let (vertex_from, vertex_to) = &csv_row.generate_vertices();
         let edge = Edge::create(vertex_from, &csv_row.relation, vertex_to);
-        graphs.add_edge(&edge, Some(&csv_row.graphs_vault));
+        graphs.add_edge(&edge, Some(&csv_row.graphs_vault))?;
     }
     if graphs.get_vaults() == Err(GruPHstError::NoVaultOnGraphs) {
         return Err(GruPHstError::CSVEmpty);
@@ -57,8 +57,9 @@ fn fill_vertex_attributes(vertex: &mut Vertex, attr_str: &str) {
     vertex.set_attr(attr.first().unwrap().trim(), attr.get(1).unwrap().trim());
 }
 
-fn create_vaults_from_csv(graphs: &mut Graphs, csv_rows: &[CSVRow]) {
+fn create_vaults_from_csv(graphs: &mut Graphs, csv_rows: &[CSVRow]) -> Result<(), GruPHstError> {
     for csv_row in csv_rows.iter() {
-        graphs.insert(&csv_row.graphs_vault);
+        graphs.insert(&csv_row.graphs_vault)?;
     }
+    Ok(())
 }
@@ -89,7 +89,7 @@ impl CSVRow {
 ///
 /// let edge = Edge::create(&gandalf_v, "friend of", &frodo_v);
 ///
-/// gru.add_edge(&edge, None);
+/// gru.add_edge(&edge, None).unwrap();
 ///
 /// export_to_csv_gruphst_format(
 ///     &gru,
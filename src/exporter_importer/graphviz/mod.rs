@@ -33,7 +33,7 @@ fn generate_graphviz_footer() -> String {
 ///
 /// let edge = Edge::create(&gandalf_v, "friend of", &frodo_v);
 ///
-/// gru.add_edge(&edge, None);
+/// gru.add_edge(&edge, None).unwrap();
 ///
 /// export_to_graphviz_format(
 ///     &gru,
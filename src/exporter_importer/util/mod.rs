@@ -4,6 +4,7 @@ use std::error::Error;
 const CSV_EXTENSION: &str = "csv";
 const GRAPHVIZ_EXTENSION: &str = "gv.txt";
 
+#[allow(clippy::upper_case_acronyms)]
 pub(super) enum ExportFileFormat {
     CSV,
     GraphViz,
@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+
+use log::warn;
+
+use crate::{edge::Edge, errors::GruPHstError, graphs::Graphs, vertex::Vertex};
+
+type RelationsIndex = HashMap<String, HashMap<String, Vec<usize>>>;
+
+/// Outgoing and incoming adjacency index for the edges of a vault.
+///
+/// For every vertex id and relation keeps the positions
+/// of the edges on the vault, so the neighbours of a vertex
+/// are retrieved without scanning the whole vault.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Adjacency {
+    /// positions of edges by "from" vertex id and relation
+    out: RelationsIndex,
+    /// positions of edges by "to" vertex id and relation
+    inc: RelationsIndex,
+}
+
+impl Adjacency {
+    /// Builds the index for a collection of edges
+    pub(crate) fn build(edges: &[Edge]) -> Self {
+        let mut adjacency = Adjacency::default();
+        for (position, edge) in edges.iter().enumerate() {
+            adjacency.add(edge, position);
+        }
+        adjacency
+    }
+
+    /// Indexes an edge placed at the provided position
+    pub(crate) fn add(&mut self, edge: &Edge, position: usize) {
+        let relation = edge.get_relation();
+        self.out
+            .entry(edge.get_from_vertex().get_id())
+            .or_default()
+            .entry(relation.clone())
+            .or_default()
+            .push(position);
+        self.inc
+            .entry(edge.get_to_vertex().get_id())
+            .or_default()
+            .entry(relation)
+            .or_default()
+            .push(position);
+    }
+
+    /// Removes an edge placed at the provided position,
+    /// the positions of the following edges are shifted
+    /// as it happens on the vault
+    pub(crate) fn remove(&mut self, edge: &Edge, position: usize) {
+        let relation = edge.get_relation();
+        Adjacency::remove_position(
+            &mut self.out,
+            &edge.get_from_vertex().get_id(),
+            &relation,
+            position,
+        );
+        Adjacency::remove_position(
+            &mut self.inc,
+            &edge.get_to_vertex().get_id(),
+            &relation,
+            position,
+        );
+        for index in [&mut self.out, &mut self.inc] {
+            for relations in index.values_mut() {
+                for positions in relations.values_mut() {
+                    for p in positions.iter_mut().filter(|p| **p > position) {
+                        *p -= 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_position(index: &mut RelationsIndex, id: &str, relation: &str, position: usize) {
+        if let Some(relations) = index.get_mut(id) {
+            if let Some(positions) = relations.get_mut(relation) {
+                positions.retain(|p| *p != position);
+                if positions.is_empty() {
+                    relations.remove(relation);
+                }
+            }
+            if relations.is_empty() {
+                index.remove(id);
+            }
+        }
+    }
+
+    /// Positions of the outgoing edges of a vertex,
+    /// for a relation or any of them when None
+    pub(crate) fn out_positions(&self, id: &str, relation: Option<&str>) -> Vec<usize> {
+        Adjacency::positions(&self.out, id, relation)
+    }
+
+    /// Positions of the incoming edges of a vertex,
+    /// for a relation or any of them when None
+    pub(crate) fn in_positions(&self, id: &str, relation: Option<&str>) -> Vec<usize> {
+        Adjacency::positions(&self.inc, id, relation)
+    }
+
+    /// Ids of the vertices with an outgoing edge of the relation,
+    /// in the order of their first edge on the vault
+    pub(crate) fn with_relation_out(&self, relation: &str) -> Vec<String> {
+        Adjacency::with_relation(&self.out, relation)
+    }
+
+    /// Ids of the vertices with an incoming edge of the relation,
+    /// in the order of their first edge on the vault
+    pub(crate) fn with_relation_in(&self, relation: &str) -> Vec<String> {
+        Adjacency::with_relation(&self.inc, relation)
+    }
+
+    fn with_relation(index: &RelationsIndex, relation: &str) -> Vec<String> {
+        let mut ids: Vec<(usize, &String)> = index
+            .iter()
+            .filter_map(|(id, relations)| {
+                let first = relations.get(relation)?.iter().min()?;
+                Some((*first, id))
+            })
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|(_, id)| id.clone()).collect()
+    }
+
+    fn positions(index: &RelationsIndex, id: &str, relation: Option<&str>) -> Vec<usize> {
+        let mut positions = match (index.get(id), relation) {
+            (Some(relations), Some(relation)) => {
+                relations.get(relation).cloned().unwrap_or_default()
+            }
+            (Some(relations), None) => relations.values().flatten().copied().collect(),
+            (None, _) => Vec::new(),
+        };
+        positions.sort_unstable();
+        positions
+    }
+}
+
+impl Graphs {
+    /// Retrieves the outgoing edges of a vertex on a vault
+    /// for a relation or any relation when None
+    pub(crate) fn edges_out(
+        &self,
+        id: &str,
+        relation: Option<&str>,
+        vault_name: &str,
+    ) -> Vec<&Edge> {
+        match self.vault_ref(vault_name) {
+            Ok(Some(vault)) => vault
+                .adjacency
+                .out_positions(id, relation)
+                .into_iter()
+                .map(|position| &vault.edges[position])
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Retrieves the incoming edges of a vertex on a vault
+    /// for a relation or any relation when None
+    pub(crate) fn edges_in(
+        &self,
+        id: &str,
+        relation: Option<&str>,
+        vault_name: &str,
+    ) -> Vec<&Edge> {
+        match self.vault_ref(vault_name) {
+            Ok(Some(vault)) => vault
+                .adjacency
+                .in_positions(id, relation)
+                .into_iter()
+                .map(|position| &vault.edges[position])
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Retrieves the vertices related by an outgoing edge
+    /// with the vertex that matches the provided id,
+    /// for a relation or any relation when None,
+    /// for some provided vault_name or default when None
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("The Shire")), None).unwrap();
+    ///
+    /// let friends = graphs.neighbors_out(&frodo.get_id(), Some("friend of"), None).unwrap();
+    /// assert_eq!(friends[0].get_label(), "Sam");
+    /// assert_eq!(graphs.neighbors_out(&frodo.get_id(), None, None).unwrap().len(), 2);
+    /// ```
+    pub fn neighbors_out(
+        &self,
+        id: &str,
+        relation: Option<&str>,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<Vertex>, GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.select_vault_vertices(vault_name)?;
+        let edges = self.edges_out(id, relation, &current_vault);
+        let vertices = Graphs::uniq_vertices(edges.iter().map(|edge| edge.get_to_vertex()));
+        if !vertices.is_empty() {
+            Ok(vertices)
+        } else {
+            warn!("Vertex with id: {} has no relations out", id);
+            Err(GruPHstError::EdgeNoRelations(String::from("out")))
+        }
+    }
+
+    /// Retrieves the vertices related by an incoming edge
+    /// with the vertex that matches the provided id,
+    /// for a relation or any relation when None,
+    /// for some provided vault_name or default when None
+    pub fn neighbors_in(
+        &self,
+        id: &str,
+        relation: Option<&str>,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<Vertex>, GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.select_vault_vertices(vault_name)?;
+        let edges = self.edges_in(id, relation, &current_vault);
+        let vertices = Graphs::uniq_vertices(edges.iter().map(|edge| edge.get_from_vertex()));
+        if !vertices.is_empty() {
+            Ok(vertices)
+        } else {
+            warn!("Vertex with id: {} has no relations in", id);
+            Err(GruPHstError::EdgeNoRelations(String::from("in")))
+        }
+    }
+
+    fn uniq_vertices(vertices: impl Iterator<Item = Vertex>) -> Vec<Vertex> {
+        let mut ids: HashSet<String> = HashSet::new();
+        vertices
+            .filter(|vertex| ids.insert(vertex.get_id()))
+            .collect()
+    }
+}
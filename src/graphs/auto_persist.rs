@@ -0,0 +1,213 @@
+//! Automatic persistence of a Graphs after its mutations
+//!
+//! The policy is read from GRUPHST_AUTO_PERSIST when a Graphs is initialized
+//! or loaded, or set with `set_auto_persist`. The Graphs is saved, like `save`,
+//! on the configured path and flushed when dropped if there are mutations
+//! not saved yet, encrypted with the key of `save_encrypted` or
+//! `load_encrypted` when it has one. A cloned Graphs is not auto-persisted.
+
+use std::{
+    error::Error,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use log::{error, info};
+
+use crate::{
+    config::{get_auto_persist, get_auto_persist_path, get_compression},
+    graphs::Graphs,
+};
+
+/// When a Graphs is saved after its mutations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoPersist {
+    /// only saved manually
+    #[default]
+    Off,
+    /// saved after every mutation
+    OnWrite,
+    /// saved after the provided number of mutations
+    EveryMutations(usize),
+    /// saved every interval, if there are mutations not saved yet.
+    /// Only a SharedGraphs saves it on a timer, on a background thread;
+    /// a Graphs has no timer and is saved on its first mutation
+    /// after the interval, or when dropped
+    Interval(Duration),
+}
+
+/// State of the auto-persistence of a Graphs
+pub(crate) struct AutoPersistSlot {
+    policy: AutoPersist,
+    file_path: Option<String>,
+    /// mutations since the last save
+    pending: usize,
+    last_save: Instant,
+    /// changes with every policy, so a background thread
+    /// of a previous policy stops
+    generation: u64,
+    /// held while a transaction is running
+    held: bool,
+}
+
+impl AutoPersistSlot {
+    fn new(policy: AutoPersist, file_path: Option<String>) -> Self {
+        AutoPersistSlot {
+            policy,
+            file_path,
+            pending: 0,
+            last_save: Instant::now(),
+            generation: 0,
+            held: false,
+        }
+    }
+
+    /// With the policy and path of GRUPHST_AUTO_PERSIST and GRUPHST_AUTO_PERSIST_PATH
+    pub(crate) fn from_config() -> Self {
+        AutoPersistSlot::new(get_auto_persist(), get_auto_persist_path())
+    }
+
+    fn is_due(&self) -> bool {
+        match self.policy {
+            AutoPersist::Off => false,
+            AutoPersist::OnWrite => self.pending > 0,
+            AutoPersist::EveryMutations(mutations) => self.pending >= mutations.max(1),
+            AutoPersist::Interval(interval) => {
+                self.pending > 0 && self.last_save.elapsed() >= interval
+            }
+        }
+    }
+}
+
+impl Default for AutoPersistSlot {
+    fn default() -> Self {
+        AutoPersistSlot::new(AutoPersist::Off, None)
+    }
+}
+
+/// A cloned Graphs is detached from the auto-persistence
+impl Clone for AutoPersistSlot {
+    fn clone(&self) -> Self {
+        AutoPersistSlot::default()
+    }
+}
+
+impl fmt::Debug for AutoPersistSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.policy)
+    }
+}
+
+impl Graphs {
+    /// Sets when the Graphs is saved after its mutations,
+    /// on the provided path or the current directory when None,
+    /// like `save`. Mutations not saved yet are flushed when the Graphs is dropped.
+    /// An Interval policy is only saved on a timer by a SharedGraphs
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::{AutoPersist, Graphs}, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("auto-persisted");
+    /// graphs.set_auto_persist(AutoPersist::EveryMutations(2), Some("./"));
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("The Shire")), None).unwrap();
+    ///
+    /// let saved = Graphs::load("./auto-persisted.grphst").unwrap();
+    /// assert_eq!(saved.len(), 2);
+    /// # std::fs::remove_file("./auto-persisted.grphst").unwrap();
+    /// ```
+    pub fn set_auto_persist(&mut self, policy: AutoPersist, file_path: Option<&str>) {
+        let generation = self.persist.generation + 1;
+        self.persist = AutoPersistSlot::new(policy, file_path.map(str::to_string));
+        self.persist.generation = generation;
+    }
+
+    /// Retrieves when the Graphs is saved after its mutations
+    pub fn get_auto_persist(&self) -> AutoPersist {
+        self.persist.policy
+    }
+
+    /// Saves the Graphs if there are mutations not saved yet
+    /// by the auto-persistence, encrypted when it has a key
+    pub fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.persist.policy == AutoPersist::Off || self.persist.pending == 0 {
+            return Ok(());
+        }
+        self.write_snapshot(
+            self.persist.file_path.as_deref(),
+            get_compression(),
+            self.key.as_ref(),
+        )?;
+        info!(
+            "Graphs {} auto-persisted after {} mutations",
+            self.label, self.persist.pending
+        );
+        self.persist.pending = 0;
+        self.persist.last_save = Instant::now();
+        Ok(())
+    }
+
+    /// Counts a mutation, saving the Graphs when the policy is due
+    pub(crate) fn persist_mutation(&mut self) {
+        if self.persist.policy == AutoPersist::Off {
+            return;
+        }
+        self.persist.pending += 1;
+        if !self.persist.held && self.persist.is_due() {
+            if let Err(err) = self.flush() {
+                error!("Graphs {} not auto-persisted: {}", self.label, err);
+            }
+        }
+    }
+
+    /// Interval of the policy and its generation,
+    /// for the background thread of a SharedGraphs
+    #[cfg(feature = "sync")]
+    pub(crate) fn auto_persist_interval(&self) -> Option<(Duration, u64)> {
+        match self.persist.policy {
+            AutoPersist::Interval(interval) => Some((interval, self.persist.generation)),
+            _ => None,
+        }
+    }
+
+    /// Holds the auto-persistence while a transaction is running,
+    /// returns the mutations pending and if it was already held
+    pub(crate) fn hold_auto_persist(&mut self) -> (usize, bool) {
+        let held = self.persist.held;
+        self.persist.held = true;
+        (self.persist.pending, held)
+    }
+
+    /// Releases the auto-persistence after a transaction,
+    /// the mutations of a rolled back one are not counted
+    pub(crate) fn release_auto_persist(&mut self, pending: Option<usize>, held: bool) {
+        if let Some(pending) = pending {
+            self.persist.pending = pending;
+        }
+        self.persist.held = held;
+        if !held && self.persist.is_due() {
+            if let Err(err) = self.flush() {
+                error!("Graphs {} not auto-persisted: {}", self.label, err);
+            }
+        }
+    }
+
+    /// Detaches the auto-persistence, while replaying a write-ahead log
+    pub(crate) fn take_auto_persist(&mut self) -> AutoPersistSlot {
+        std::mem::take(&mut self.persist)
+    }
+
+    pub(crate) fn restore_auto_persist(&mut self, persist: AutoPersistSlot) {
+        self.persist = persist;
+    }
+}
+
+impl Drop for Graphs {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            error!("Graphs {} not flushed on drop: {}", self.label, err);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Parsed representation of a query
+
+use crate::attributes::AttrValue;
+
+/// Direction of a relationship on a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RelDir {
+    /// `-[]->`
+    Out,
+    /// `<-[]-`
+    In,
+    /// `-[]-`
+    Both,
+}
+
+/// `(var:Label {key: value})`
+#[derive(Debug, Clone)]
+pub(super) struct NodePat {
+    pub(super) var: Option<String>,
+    pub(super) label: Option<String>,
+    pub(super) props: Vec<(String, AttrValue)>,
+}
+
+/// `-[var:relation|other*min..max {key: value}]->`
+#[derive(Debug, Clone)]
+pub(super) struct RelPat {
+    pub(super) var: Option<String>,
+    pub(super) relations: Vec<String>,
+    /// minimum and maximum number of edges for variable-length relationships,
+    /// None for a single edge
+    pub(super) length: Option<(usize, Option<usize>)>,
+    pub(super) props: Vec<(String, AttrValue)>,
+    pub(super) dir: RelDir,
+}
+
+/// A node followed by any number of relationship and node hops
+#[derive(Debug, Clone)]
+pub(super) struct Pattern {
+    pub(super) start: NodePat,
+    pub(super) hops: Vec<(RelPat, NodePat)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CmpOp {
+    Eq,
+    Neq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Func {
+    /// `id(x)`, the id of a vertex or an edge
+    Id,
+    /// `label(x)`, the label of a vertex
+    Label,
+    /// `type(r)`, the relation of an edge
+    Type,
+}
+
+/// A value on a WHERE condition or a RETURN item
+#[derive(Debug, Clone)]
+pub(super) enum Operand {
+    /// the whole vertex or edge bound to the variable
+    Var(String),
+    /// `var.key`
+    Prop(String, String),
+    /// `func(var)`
+    Func(Func, String),
+    Lit(AttrValue),
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Operand, CmpOp, Operand),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ReturnItem {
+    pub(super) operand: Operand,
+    /// the alias or the text of the item
+    pub(super) column: String,
+}
+
+/// `MATCH pattern [WHERE expr] RETURN [DISTINCT] items [LIMIT n]`
+#[derive(Debug, Clone)]
+pub(super) struct Query {
+    pub(super) pattern: Pattern,
+    pub(super) filter: Option<Expr>,
+    pub(super) distinct: bool,
+    pub(super) items: Vec<ReturnItem>,
+    pub(super) limit: Option<usize>,
+}
@@ -0,0 +1,229 @@
+//! Splits a query into tokens, keeping the position of every token
+
+use crate::errors::GruPHstError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Tok {
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    Colon,
+    Comma,
+    Dot,
+    DotDot,
+    Pipe,
+    Star,
+    Dash,
+    /// `->`
+    Arrow,
+    /// `<-`
+    LArrow,
+    Eq,
+    Neq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// a name, quoted ones with backticks are never keywords
+    Ident {
+        name: String,
+        quoted: bool,
+    },
+    Str(String),
+    Int(i64),
+    Float(f64),
+}
+
+/// A token and its byte position on the query
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Token {
+    pub(super) tok: Tok,
+    pub(super) pos: usize,
+}
+
+fn syntax_error(pos: usize, msg: &str) -> GruPHstError {
+    GruPHstError::QuerySyntax(pos, msg.to_string())
+}
+
+/// A value is expected after the token, so a dash is a negative sign
+fn expects_value(last: Option<&Token>) -> bool {
+    const KEYWORDS: [&str; 5] = ["WHERE", "AND", "OR", "NOT", "CONTAINS"];
+    matches!(
+        last.map(|token| &token.tok),
+        Some(Tok::Ident { name, quoted: false })
+            if KEYWORDS.iter().any(|keyword| name.eq_ignore_ascii_case(keyword))
+    ) || matches!(
+        last.map(|token| &token.tok),
+        None | Some(
+            Tok::LParen
+                | Tok::LBracket
+                | Tok::Colon
+                | Tok::Comma
+                | Tok::Eq
+                | Tok::Neq
+                | Tok::Lt
+                | Tok::Le
+                | Tok::Gt
+                | Tok::Ge
+        )
+    )
+}
+
+pub(super) fn tokenize(query: &str) -> Result<Vec<Token>, GruPHstError> {
+    let chars: Vec<(usize, char)> = query.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let peek = |i: usize| chars.get(i).map(|(_, c)| *c);
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        let simple = |tok: Tok| Token { tok, pos };
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => tokens.push(simple(Tok::LParen)),
+            ')' => tokens.push(simple(Tok::RParen)),
+            '[' => tokens.push(simple(Tok::LBracket)),
+            ']' => tokens.push(simple(Tok::RBracket)),
+            '{' => tokens.push(simple(Tok::LBrace)),
+            '}' => tokens.push(simple(Tok::RBrace)),
+            ':' => tokens.push(simple(Tok::Colon)),
+            ',' => tokens.push(simple(Tok::Comma)),
+            '|' => tokens.push(simple(Tok::Pipe)),
+            '*' => tokens.push(simple(Tok::Star)),
+            '=' => tokens.push(simple(Tok::Eq)),
+            '.' if peek(i + 1) == Some('.') => {
+                tokens.push(simple(Tok::DotDot));
+                i += 1;
+            }
+            '.' => tokens.push(simple(Tok::Dot)),
+            '-' if peek(i + 1).is_some_and(|c| c.is_ascii_digit())
+                && expects_value(tokens.last()) =>
+            {
+                i += 1;
+                let tok = number(&chars, &mut i, true);
+                match tok {
+                    Some(tok) => tokens.push(simple(tok)),
+                    None => return Err(syntax_error(pos, "number out of range")),
+                }
+            }
+            '-' if peek(i + 1) == Some('>') => {
+                tokens.push(simple(Tok::Arrow));
+                i += 1;
+            }
+            '-' => tokens.push(simple(Tok::Dash)),
+            '<' => match peek(i + 1) {
+                Some('-') => {
+                    tokens.push(simple(Tok::LArrow));
+                    i += 1;
+                }
+                Some('=') => {
+                    tokens.push(simple(Tok::Le));
+                    i += 1;
+                }
+                Some('>') => {
+                    tokens.push(simple(Tok::Neq));
+                    i += 1;
+                }
+                _ => tokens.push(simple(Tok::Lt)),
+            },
+            '>' if peek(i + 1) == Some('=') => {
+                tokens.push(simple(Tok::Ge));
+                i += 1;
+            }
+            '>' => tokens.push(simple(Tok::Gt)),
+            '!' if peek(i + 1) == Some('=') => {
+                tokens.push(simple(Tok::Neq));
+                i += 1;
+            }
+            '"' | '\'' | '`' => {
+                let quote = c;
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match peek(i) {
+                        None => return Err(syntax_error(pos, "unterminated quoted text")),
+                        Some('\\') if quote != '`' => {
+                            match peek(i + 1) {
+                                Some('n') => text.push('\n'),
+                                Some('t') => text.push('\t'),
+                                Some(escaped) => text.push(escaped),
+                                None => return Err(syntax_error(pos, "unterminated quoted text")),
+                            }
+                            i += 2;
+                        }
+                        Some(q) if q == quote => break,
+                        Some(other) => {
+                            text.push(other);
+                            i += 1;
+                        }
+                    }
+                }
+                let tok = match quote {
+                    '`' => Tok::Ident {
+                        name: text,
+                        quoted: true,
+                    },
+                    _ => Tok::Str(text),
+                };
+                tokens.push(simple(tok));
+            }
+            c if c.is_ascii_digit() => {
+                let tok = number(&chars, &mut i, false);
+                match tok {
+                    Some(tok) => tokens.push(simple(tok)),
+                    None => return Err(syntax_error(pos, "number out of range")),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while peek(i + 1).is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    i += 1;
+                }
+                let name: String = chars[start..=i].iter().map(|(_, c)| c).collect();
+                tokens.push(simple(Tok::Ident {
+                    name,
+                    quoted: false,
+                }));
+            }
+            other => {
+                return Err(syntax_error(
+                    pos,
+                    &format!("unexpected character '{}'", other),
+                ))
+            }
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Lexes the integer or float starting at the digit on i,
+/// leaving i on its last digit; None when out of range
+fn number(chars: &[(usize, char)], i: &mut usize, negative: bool) -> Option<Tok> {
+    let peek = |i: usize| chars.get(i).map(|(_, c)| *c);
+    let start = *i;
+    while peek(*i + 1).is_some_and(|c| c.is_ascii_digit()) {
+        *i += 1;
+    }
+    let is_float = peek(*i + 1) == Some('.') && peek(*i + 2).is_some_and(|c| c.is_ascii_digit());
+    if is_float {
+        *i += 1;
+        while peek(*i + 1).is_some_and(|c| c.is_ascii_digit()) {
+            *i += 1;
+        }
+    }
+    let digits: String = chars[start..=*i].iter().map(|(_, c)| c).collect();
+    let text = match negative {
+        true => format!("-{}", digits),
+        false => digits,
+    };
+    match is_float {
+        true => text.parse().map(Tok::Float).ok(),
+        false => text.parse().map(Tok::Int).ok(),
+    }
+}
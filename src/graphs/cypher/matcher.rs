@@ -0,0 +1,308 @@
+//! Matches the pattern of a query against a vault,
+//! binding the variables of the pattern to vertices and edges
+
+use std::{cmp::Ordering, collections::HashMap, ops::Bound::Included};
+
+use crate::{
+    attributes::{AttrRange, AttrValue},
+    edge::Edge,
+    graphs::{traversal::Direction, Graphs},
+    vertex::Vertex,
+};
+
+use super::{
+    ast::{CmpOp, Expr, Func, NodePat, Operand, Pattern, RelDir, RelPat},
+    Binding, QueryValue,
+};
+
+pub(super) type Bindings = HashMap<String, Binding>;
+
+/// Calls the visitor for every match of the pattern in the vault,
+/// in order of the label and id of the first vertex,
+/// stops when the visitor returns false.
+/// Like Cypher, an edge is not used twice on the same match
+pub(super) fn match_pattern<F>(
+    graphs: &Graphs,
+    pattern: &Pattern,
+    vertices: &HashMap<String, Vertex>,
+    vault: &str,
+    visitor: &mut F,
+) where
+    F: FnMut(&Bindings) -> bool,
+{
+    let mut starts: Vec<&Vertex> = vertices
+        .values()
+        .filter(|vertex| node_matches(&pattern.start, vertex))
+        .collect();
+    starts.sort_by_key(|vertex| (vertex.get_label(), vertex.get_id()));
+    let matcher = Matcher {
+        graphs,
+        pattern,
+        vaults: vec![vault.to_string()],
+    };
+    for start in starts {
+        let mut bindings = Bindings::new();
+        bind(
+            &mut bindings,
+            &pattern.start.var,
+            Binding::Vertex(start.clone()),
+        );
+        let mut used = Vec::new();
+        if !matcher.expand(0, start, &mut bindings, &mut used, visitor) {
+            return;
+        }
+    }
+}
+
+struct Matcher<'a> {
+    graphs: &'a Graphs,
+    pattern: &'a Pattern,
+    vaults: Vec<String>,
+}
+
+impl Matcher<'_> {
+    /// Matches the hop at the index from the current vertex,
+    /// returns false when the visitor asked to stop
+    fn expand<F>(
+        &self,
+        hop: usize,
+        current: &Vertex,
+        bindings: &mut Bindings,
+        used: &mut Vec<String>,
+        visitor: &mut F,
+    ) -> bool
+    where
+        F: FnMut(&Bindings) -> bool,
+    {
+        match self.pattern.hops.get(hop) {
+            Some((rel, _)) => {
+                let (min, max) = rel.length.unwrap_or((1, Some(1)));
+                let mut path = Vec::new();
+                self.walk(hop, current, min, max, &mut path, bindings, used, visitor)
+            }
+            None => visitor(bindings),
+        }
+    }
+
+    /// Follows the edges of the relationship at the index,
+    /// from `min` up to `max` edges, and matches the rest of the pattern
+    /// at the end of every path
+    #[allow(clippy::too_many_arguments)]
+    fn walk<F>(
+        &self,
+        hop: usize,
+        current: &Vertex,
+        min: usize,
+        max: Option<usize>,
+        path: &mut Vec<Edge>,
+        bindings: &mut Bindings,
+        used: &mut Vec<String>,
+        visitor: &mut F,
+    ) -> bool
+    where
+        F: FnMut(&Bindings) -> bool,
+    {
+        let (rel, node) = &self.pattern.hops[hop];
+        if path.len() >= min && node_matches(node, current) {
+            let bound = match rel.length {
+                None => Binding::Edge(path[0].clone()),
+                Some(_) => Binding::Edges(path.clone()),
+            };
+            let node_bound = Binding::Vertex(current.clone());
+            if is_consistent(bindings, &rel.var, &bound)
+                && is_consistent(bindings, &node.var, &node_bound)
+            {
+                let mut added = Vec::new();
+                if bind(bindings, &rel.var, bound) {
+                    added.push(rel.var.clone());
+                }
+                if bind(bindings, &node.var, node_bound) {
+                    added.push(node.var.clone());
+                }
+                let keep_going = self.expand(hop + 1, current, bindings, used, visitor);
+                for var in added.into_iter().flatten() {
+                    bindings.remove(&var);
+                }
+                if !keep_going {
+                    return false;
+                }
+            }
+        }
+        if max.is_some_and(|max| path.len() >= max) {
+            return true;
+        }
+        let direction = match rel.dir {
+            RelDir::Out => Direction::Out,
+            RelDir::In => Direction::In,
+            RelDir::Both => Direction::Both,
+        };
+        let mut seen: Vec<String> = Vec::new();
+        let adjacent =
+            self.graphs
+                .adjacent(&current.get_id(), direction, &rel.relations, &self.vaults);
+        for (edge, next) in adjacent {
+            let edge_id = edge.get_id();
+            // a loop is found twice when following both directions
+            if used.contains(&edge_id) || seen.contains(&edge_id) {
+                continue;
+            }
+            seen.push(edge_id.clone());
+            if !rel_matches(rel, &edge) {
+                continue;
+            }
+            used.push(edge_id);
+            path.push(edge);
+            let keep_going = self.walk(hop, &next, min, max, path, bindings, used, visitor);
+            path.pop();
+            used.pop();
+            if !keep_going {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Binds the variable when it is not bound yet,
+/// returns true when it has been bound
+fn bind(bindings: &mut Bindings, var: &Option<String>, bound: Binding) -> bool {
+    match var {
+        Some(var) if !bindings.contains_key(var) => {
+            bindings.insert(var.clone(), bound);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// A variable used twice on a pattern must be bound to the same elements
+fn is_consistent(bindings: &Bindings, var: &Option<String>, bound: &Binding) -> bool {
+    match var.as_ref().and_then(|var| bindings.get(var)) {
+        Some(existing) => existing.ids() == bound.ids(),
+        None => true,
+    }
+}
+
+/// A range with a single value, to check attributes
+/// with the queries of vertices and edges
+fn exactly(value: &AttrValue) -> AttrRange {
+    (Included(value.clone()), Included(value.clone()))
+}
+
+fn node_matches(node: &NodePat, vertex: &Vertex) -> bool {
+    if node
+        .label
+        .as_ref()
+        .is_some_and(|label| vertex.get_label() != *label)
+    {
+        return false;
+    }
+    node.props
+        .iter()
+        .all(|(k, v)| vertex.has_attr_within(k, &exactly(v)))
+}
+
+fn rel_matches(rel: &RelPat, edge: &Edge) -> bool {
+    rel.props
+        .iter()
+        .all(|(k, v)| edge.has_attr_within(k, &exactly(v)))
+}
+
+/// Integers and floats are compared as numbers
+fn equals(a: &AttrValue, b: &AttrValue) -> bool {
+    a == b || a.compare(b) == Some(Ordering::Equal)
+}
+
+/// Value of an operand for the bindings of a match,
+/// Null for missing attributes or functions that do not apply
+pub(super) fn evaluate(operand: &Operand, bindings: &Bindings) -> QueryValue {
+    let bound = |var: &str| bindings.get(var);
+    match operand {
+        Operand::Lit(value) => QueryValue::Value(value.clone()),
+        Operand::Var(var) => match bound(var) {
+            Some(Binding::Vertex(vertex)) => QueryValue::Vertex(vertex.clone()),
+            Some(Binding::Edge(edge)) => QueryValue::Edge(edge.clone()),
+            Some(Binding::Edges(edges)) => QueryValue::Edges(edges.clone()),
+            None => QueryValue::Null,
+        },
+        Operand::Prop(var, key) => {
+            let value = match bound(var) {
+                Some(Binding::Vertex(vertex)) => vertex.get_attr_value(key).ok(),
+                Some(Binding::Edge(edge)) => edge.get_attr_value(key).ok(),
+                _ => None,
+            };
+            value.map_or(QueryValue::Null, QueryValue::Value)
+        }
+        Operand::Func(func, var) => {
+            let value = match (func, bound(var)) {
+                (Func::Id, Some(Binding::Vertex(vertex))) => Some(vertex.get_id()),
+                (Func::Id, Some(Binding::Edge(edge))) => Some(edge.get_id()),
+                (Func::Label, Some(Binding::Vertex(vertex))) => Some(vertex.get_label()),
+                (Func::Type, Some(Binding::Edge(edge))) => Some(edge.get_relation()),
+                _ => None,
+            };
+            value.map_or(QueryValue::Null, |v| QueryValue::Value(AttrValue::Str(v)))
+        }
+    }
+}
+
+/// Checks the WHERE condition for the bindings of a match,
+/// with the three-valued logic of Cypher: None is null, like
+/// the comparisons with Null, and only Some(true) matches
+pub(super) fn check(expr: &Expr, bindings: &Bindings) -> Option<bool> {
+    match expr {
+        Expr::Or(a, b) => match (check(a, bindings), check(b, bindings)) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        },
+        Expr::And(a, b) => match (check(a, bindings), check(b, bindings)) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        },
+        Expr::Not(a) => check(a, bindings).map(|matched| !matched),
+        Expr::Cmp(left, op, right) => {
+            let left = evaluate(left, bindings);
+            let right = evaluate(right, bindings);
+            compare(&left, *op, &right)
+        }
+    }
+}
+
+/// Comparison of two values, None when any of them is Null
+/// or they can not be ordered
+fn compare(left: &QueryValue, op: CmpOp, right: &QueryValue) -> Option<bool> {
+    match (left, right) {
+        (QueryValue::Null, _) | (_, QueryValue::Null) => None,
+        (QueryValue::Value(a), QueryValue::Value(b)) => match op {
+            CmpOp::Eq => Some(equals(a, b)),
+            CmpOp::Neq => Some(!equals(a, b)),
+            CmpOp::Contains => match (a, b) {
+                (AttrValue::Str(a), AttrValue::Str(b)) => Some(a.contains(b.as_str())),
+                (AttrValue::List(list), b) => Some(list.iter().any(|v| equals(v, b))),
+                _ => None,
+            },
+            CmpOp::Lt => a.compare(b).map(|ord| ord == Ordering::Less),
+            CmpOp::Le => a.compare(b).map(|ord| ord != Ordering::Greater),
+            CmpOp::Gt => a.compare(b).map(|ord| ord == Ordering::Greater),
+            CmpOp::Ge => a.compare(b).map(|ord| ord != Ordering::Less),
+        },
+        (QueryValue::Vertex(a), QueryValue::Vertex(b)) => same(op, a.get_id() == b.get_id()),
+        (QueryValue::Edge(a), QueryValue::Edge(b)) => same(op, a.get_id() == b.get_id()),
+        (QueryValue::Edges(a), QueryValue::Edges(b)) => {
+            let ids = |edges: &Vec<Edge>| edges.iter().map(Edge::get_id).collect::<Vec<_>>();
+            same(op, ids(a) == ids(b))
+        }
+        _ => Some(false),
+    }
+}
+
+/// Vertices and edges are only compared for identity
+fn same(op: CmpOp, equal: bool) -> Option<bool> {
+    match op {
+        CmpOp::Eq => Some(equal),
+        CmpOp::Neq => Some(!equal),
+        _ => None,
+    }
+}
@@ -0,0 +1,265 @@
+//! Cypher-like textual queries over a vault
+//!
+//! Supports a subset of the Cypher language:
+//! `MATCH pattern [WHERE condition] RETURN [DISTINCT] items [LIMIT n]`
+//!
+//! - nodes: `(a)`, `(a:Gandalf)`, `(:"The Shire" {type: "region"})`
+//! - relationships: `-[:"friend of"]->`, `<-[r:knows|likes]-`, `-[]-`
+//! - variable-length relationships: `-[:knows*]->`, `-[:knows*2]->`,
+//!   `-[:knows*1..3]->`, `-[:knows*..3]->`
+//! - conditions: `=`, `<>`, `<`, `<=`, `>`, `>=`, `CONTAINS`,
+//!   combined with `AND`, `OR`, `NOT` and parentheses
+//! - values: `a.name`, `` a.`years old` ``, `id(a)`, `label(a)`, `type(r)`,
+//!   numbers, strings, `true` and `false`
+//!
+//! Labels of vertices and relations of edges with spaces can be written
+//! as strings or as words, like `-[:lives at]->`,
+//! keys of attributes with spaces with backticks.
+
+use std::collections::HashMap;
+
+use log::warn;
+
+use crate::{attributes::AttrValue, edge::Edge, errors::GruPHstError, vertex::Vertex};
+
+use super::Graphs;
+
+mod ast;
+mod lexer;
+mod matcher;
+mod parser;
+
+/// A vertex, an edge or the edges of a variable-length relationship,
+/// bound to a variable of a pattern
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    Vertex(Vertex),
+    Edge(Edge),
+    /// the ordered edges of a variable-length relationship
+    Edges(Vec<Edge>),
+}
+
+impl Binding {
+    /// Retrieves the vertex, only for vertices
+    pub fn as_vertex(&self) -> Option<&Vertex> {
+        match self {
+            Binding::Vertex(vertex) => Some(vertex),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the edge, only for single edges
+    pub fn as_edge(&self) -> Option<&Edge> {
+        match self {
+            Binding::Edge(edge) => Some(edge),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the edges, only for variable-length relationships
+    pub fn as_edges(&self) -> Option<&Vec<Edge>> {
+        match self {
+            Binding::Edges(edges) => Some(edges),
+            _ => None,
+        }
+    }
+
+    /// Ids of the bound elements
+    fn ids(&self) -> Vec<String> {
+        match self {
+            Binding::Vertex(vertex) => vec![vertex.get_id()],
+            Binding::Edge(edge) => vec![edge.get_id()],
+            Binding::Edges(edges) => edges.iter().map(Edge::get_id).collect(),
+        }
+    }
+}
+
+/// A value on a row of the result of a query
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryValue {
+    /// a vertex bound to a variable
+    Vertex(Vertex),
+    /// an edge bound to a variable
+    Edge(Edge),
+    /// the edges of a variable-length relationship bound to a variable
+    Edges(Vec<Edge>),
+    /// an attribute, id, label or relation
+    Value(AttrValue),
+    /// a missing attribute
+    Null,
+}
+
+impl QueryValue {
+    /// Retrieves the vertex, only for vertices
+    pub fn as_vertex(&self) -> Option<&Vertex> {
+        match self {
+            QueryValue::Vertex(vertex) => Some(vertex),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the edge, only for edges
+    pub fn as_edge(&self) -> Option<&Edge> {
+        match self {
+            QueryValue::Edge(edge) => Some(edge),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the edges, only for variable-length relationships
+    pub fn as_edges(&self) -> Option<&Vec<Edge>> {
+        match self {
+            QueryValue::Edges(edges) => Some(edges),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value, only for values
+    pub fn as_value(&self) -> Option<&AttrValue> {
+        match self {
+            QueryValue::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Checks if the value is missing
+    pub fn is_null(&self) -> bool {
+        matches!(self, QueryValue::Null)
+    }
+}
+
+/// Result of a query, a row for every match
+/// with a value for every column of the RETURN clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<QueryValue>>,
+}
+
+impl QueryResult {
+    /// Retrieves the names of the columns, the alias or the text of the items
+    pub fn get_columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+
+    /// Retrieves the rows
+    pub fn get_rows(&self) -> Vec<Vec<QueryValue>> {
+        self.rows.clone()
+    }
+
+    /// Retrieves all the values of a column, None when it does not exists
+    pub fn get_column(&self, column: &str) -> Option<Vec<QueryValue>> {
+        let idx = self.columns.iter().position(|c| c == column)?;
+        Some(self.rows.iter().map(|row| row[idx].clone()).collect())
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Checks if there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl Graphs {
+    /// Runs a Cypher-like query for some provided vault_name or default when None,
+    /// a query without matches returns a result without rows
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut gandalf = Vertex::new("Gandalf");
+    /// gandalf.set_attr("name", "Gandalf");
+    /// let mut frodo = Vertex::new("Frodo");
+    /// frodo.set_attr("years old", 50);
+    /// graphs.add_edge(&Edge::create(&gandalf, "friend of", &frodo), None).unwrap();
+    ///
+    /// let result = graphs.query(
+    ///     r#"MATCH (a)-[:"friend of"]->(b) WHERE a.name = "Gandalf" RETURN b, b.`years old`"#,
+    ///     None).unwrap();
+    /// assert_eq!(result.get_columns(), vec!["b", "b.years old"]);
+    /// assert_eq!(result.len(), 1);
+    /// assert_eq!(result.get_rows()[0][0].as_vertex().unwrap().get_label(), "Frodo");
+    /// assert_eq!(result.get_rows()[0][1].as_value().unwrap().as_i64(), Some(50));
+    /// ```
+    pub fn query(
+        &self,
+        query: &str,
+        vault_name: Option<&str>,
+    ) -> Result<QueryResult, GruPHstError> {
+        let query = parser::parse(query).inspect_err(|err| warn!("Query not valid: {}", err))?;
+        let vertices = self.select_vault_vertices(vault_name)?;
+        let vault = self.select_vault_label(vault_name);
+        let columns = query.items.iter().map(|item| item.column.clone()).collect();
+        let mut rows: Vec<Vec<QueryValue>> = Vec::new();
+        if query.limit != Some(0) {
+            matcher::match_pattern(self, &query.pattern, vertices, &vault, &mut |bindings| {
+                if let Some(filter) = &query.filter {
+                    if matcher::check(filter, bindings) != Some(true) {
+                        return true;
+                    }
+                }
+                let row: Vec<QueryValue> = query
+                    .items
+                    .iter()
+                    .map(|item| matcher::evaluate(&item.operand, bindings))
+                    .collect();
+                if !query.distinct || !rows.contains(&row) {
+                    rows.push(row);
+                }
+                query.limit.is_none_or(|limit| rows.len() < limit)
+            });
+        }
+        Ok(QueryResult { columns, rows })
+    }
+
+    /// Finds all the matches of a pattern, like
+    /// `(a)-[:knows*1..3]->(b)-[:lives at]->(c {type: "tower"})`,
+    /// for some provided vault_name or default when None.
+    /// Every match maps the named variables of the pattern
+    /// to the bound vertices and edges
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let gandalf = Vertex::new("Gandalf");
+    /// let frodo = Vertex::new("Frodo");
+    /// let sam = Vertex::new("Sam");
+    /// let mut orthanc = Vertex::new("Orthanc");
+    /// orthanc.set_attr("type", "tower");
+    /// graphs.add_edge(&Edge::create(&gandalf, "knows", &frodo), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&frodo, "knows", &sam), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&sam, "lives at", &orthanc), None).unwrap();
+    ///
+    /// let matches = graphs.match_pattern(
+    ///     r#"(a:Gandalf)-[k:knows*1..3]->(b)-[:lives at]->(c {type: "tower"})"#,
+    ///     None).unwrap();
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0]["b"].as_vertex().unwrap().get_label(), "Sam");
+    /// assert_eq!(matches[0]["k"].as_edges().unwrap().len(), 2);
+    /// assert_eq!(matches[0]["c"].as_vertex().unwrap().get_label(), "Orthanc");
+    /// ```
+    pub fn match_pattern(
+        &self,
+        pattern: &str,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<HashMap<String, Binding>>, GruPHstError> {
+        let pattern = parser::parse_pattern(pattern)
+            .inspect_err(|err| warn!("Pattern not valid: {}", err))?;
+        let vertices = self.select_vault_vertices(vault_name)?;
+        let vault = self.select_vault_label(vault_name);
+        let mut matches = Vec::new();
+        matcher::match_pattern(self, &pattern, vertices, &vault, &mut |bindings| {
+            matches.push(bindings.clone());
+            true
+        });
+        Ok(matches)
+    }
+}
@@ -0,0 +1,493 @@
+//! Recursive descent parser from tokens to a query
+
+use std::collections::HashSet;
+
+use crate::{attributes::AttrValue, errors::GruPHstError};
+
+use super::{
+    ast::{CmpOp, Expr, Func, NodePat, Operand, Pattern, Query, RelDir, RelPat, ReturnItem},
+    lexer::{tokenize, Tok, Token},
+};
+
+pub(super) fn parse(query: &str) -> Result<Query, GruPHstError> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        idx: 0,
+        end: query.len(),
+        vars: HashSet::new(),
+    };
+    parser.query()
+}
+
+/// Parses a pattern alone, like `(a)-[:knows*1..3]->(b)`
+pub(super) fn parse_pattern(pattern: &str) -> Result<Pattern, GruPHstError> {
+    let mut parser = Parser {
+        tokens: tokenize(pattern)?,
+        idx: 0,
+        end: pattern.len(),
+        vars: HashSet::new(),
+    };
+    let pattern = parser.pattern()?;
+    if parser.peek().is_some() {
+        return parser.error("expected end of pattern");
+    }
+    Ok(pattern)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    idx: usize,
+    /// position reported for errors at the end of the query
+    end: usize,
+    /// variables declared on the pattern
+    vars: HashSet<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.idx).map(|t| &t.tok)
+    }
+
+    fn pos(&self) -> usize {
+        self.tokens.get(self.idx).map_or(self.end, |t| t.pos)
+    }
+
+    fn next(&mut self) -> Option<Tok> {
+        let tok = self.tokens.get(self.idx).map(|t| t.tok.clone());
+        self.idx += 1;
+        tok
+    }
+
+    fn error<T>(&self, msg: &str) -> Result<T, GruPHstError> {
+        let found = match self.peek() {
+            Some(tok) => format!("{}, found {}", msg, describe(tok)),
+            None => format!("{}, found end of query", msg),
+        };
+        Err(GruPHstError::QuerySyntax(self.pos(), found))
+    }
+
+    fn eat(&mut self, tok: &Tok) -> bool {
+        if self.peek() == Some(tok) {
+            self.idx += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, tok: &Tok, msg: &str) -> Result<(), GruPHstError> {
+        match self.eat(tok) {
+            true => Ok(()),
+            false => self.error(msg),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.peek(),
+            Some(Tok::Ident { name, quoted: false }) if name.eq_ignore_ascii_case(keyword)
+        )
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.idx += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), GruPHstError> {
+        match self.eat_keyword(keyword) {
+            true => Ok(()),
+            false => self.error(&format!("expected {}", keyword)),
+        }
+    }
+
+    /// A name for a variable, a property or a label
+    fn name(&mut self, what: &str) -> Result<String, GruPHstError> {
+        match self.peek() {
+            Some(Tok::Ident { name, quoted }) if *quoted || !is_reserved(name) => {
+                let name = name.clone();
+                self.idx += 1;
+                Ok(name)
+            }
+            _ => self.error(&format!("expected {}", what)),
+        }
+    }
+
+    /// A name that can also be written as a string, like labels or relations
+    fn text(&mut self, what: &str) -> Result<String, GruPHstError> {
+        if let Some(Tok::Str(text)) = self.peek() {
+            let text = text.clone();
+            self.idx += 1;
+            return Ok(text);
+        }
+        self.name(what)
+    }
+
+    /// A label or a relation, as a string or as words
+    /// separated by spaces, like `lives at`
+    fn words(&mut self, what: &str) -> Result<String, GruPHstError> {
+        if let Some(Tok::Str(_)) = self.peek() {
+            return self.text(what);
+        }
+        let mut words = self.name(what)?;
+        while matches!(
+            self.peek(),
+            Some(Tok::Ident { name, quoted: false }) if !is_reserved(name)
+        ) {
+            words.push(' ');
+            words.push_str(&self.name(what)?);
+        }
+        Ok(words)
+    }
+
+    fn query(&mut self) -> Result<Query, GruPHstError> {
+        self.expect_keyword("MATCH")?;
+        let pattern = self.pattern()?;
+        let filter = match self.eat_keyword("WHERE") {
+            true => Some(self.or_expr()?),
+            false => None,
+        };
+        self.expect_keyword("RETURN")?;
+        let distinct = self.eat_keyword("DISTINCT");
+        let mut items = vec![self.return_item()?];
+        while self.eat(&Tok::Comma) {
+            items.push(self.return_item()?);
+        }
+        let limit = match self.eat_keyword("LIMIT") {
+            true => match self.next() {
+                Some(Tok::Int(n)) if n >= 0 => Some(n as usize),
+                _ => {
+                    self.idx -= 1;
+                    return self.error("expected a positive number for LIMIT");
+                }
+            },
+            false => None,
+        };
+        if self.peek().is_some() {
+            return self.error("expected end of query");
+        }
+        Ok(Query {
+            pattern,
+            filter,
+            distinct,
+            items,
+            limit,
+        })
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, GruPHstError> {
+        let start = self.node()?;
+        let mut hops = Vec::new();
+        while matches!(self.peek(), Some(Tok::Dash | Tok::LArrow)) {
+            let rel = self.relationship()?;
+            let node = self.node()?;
+            hops.push((rel, node));
+        }
+        Ok(Pattern { start, hops })
+    }
+
+    fn declare(&mut self, var: &Option<String>) {
+        if let Some(var) = var {
+            self.vars.insert(var.clone());
+        }
+    }
+
+    fn node(&mut self) -> Result<NodePat, GruPHstError> {
+        self.expect(&Tok::LParen, "expected '(' to start a node")?;
+        let var = self.optional_var();
+        let label = match self.eat(&Tok::Colon) {
+            true => Some(self.words("a label")?),
+            false => None,
+        };
+        let props = self.props()?;
+        self.expect(&Tok::RParen, "expected ')' to close a node")?;
+        self.declare(&var);
+        Ok(NodePat { var, label, props })
+    }
+
+    fn relationship(&mut self) -> Result<RelPat, GruPHstError> {
+        let incoming = match self.next() {
+            Some(Tok::LArrow) => true,
+            Some(Tok::Dash) => false,
+            _ => {
+                self.idx -= 1;
+                return self.error("expected a relationship");
+            }
+        };
+        let mut var = None;
+        let mut relations = Vec::new();
+        let mut length = None;
+        let mut props = Vec::new();
+        if self.eat(&Tok::LBracket) {
+            var = self.optional_var();
+            if self.eat(&Tok::Colon) {
+                relations.push(self.words("a relation")?);
+                while self.eat(&Tok::Pipe) {
+                    self.eat(&Tok::Colon);
+                    relations.push(self.words("a relation")?);
+                }
+            }
+            if self.eat(&Tok::Star) {
+                length = Some(self.length()?);
+            }
+            props = self.props()?;
+            self.expect(&Tok::RBracket, "expected ']' to close a relationship")?;
+        }
+        let dir = match (incoming, self.next()) {
+            (false, Some(Tok::Arrow)) => RelDir::Out,
+            (false, Some(Tok::Dash)) => RelDir::Both,
+            (true, Some(Tok::Dash)) => RelDir::In,
+            (true, Some(Tok::Arrow)) => {
+                self.idx -= 1;
+                return self.error("a relationship can not point to both sides");
+            }
+            _ => {
+                self.idx -= 1;
+                return self.error("expected '-' or '->' to close a relationship");
+            }
+        };
+        self.declare(&var);
+        Ok(RelPat {
+            var,
+            relations,
+            length,
+            props,
+            dir,
+        })
+    }
+
+    /// `*`, `*n`, `*min..max`, `*min..` or `*..max`, after the star;
+    /// at least one edge when the minimum is missing
+    fn length(&mut self) -> Result<(usize, Option<usize>), GruPHstError> {
+        let pos = self.pos();
+        let min = self.count()?;
+        let (min, max) = match self.eat(&Tok::DotDot) {
+            true => (min.unwrap_or(1), self.count()?),
+            false => match min {
+                Some(n) => (n, Some(n)),
+                None => (1, None),
+            },
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(GruPHstError::QuerySyntax(
+                pos,
+                "maximum length lower than the minimum".to_string(),
+            ));
+        }
+        Ok((min, max))
+    }
+
+    fn count(&mut self) -> Result<Option<usize>, GruPHstError> {
+        match self.peek() {
+            Some(Tok::Int(n)) => {
+                let n = *n as usize;
+                self.idx += 1;
+                Ok(Some(n))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn optional_var(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Tok::Ident { name, quoted }) if *quoted || !is_reserved(name) => {
+                let name = name.clone();
+                self.idx += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// `{key: literal, ...}`, empty when there are no braces
+    fn props(&mut self) -> Result<Vec<(String, AttrValue)>, GruPHstError> {
+        let mut props = Vec::new();
+        if !self.eat(&Tok::LBrace) {
+            return Ok(props);
+        }
+        if self.eat(&Tok::RBrace) {
+            return Ok(props);
+        }
+        loop {
+            let key = self.text("a property name")?;
+            self.expect(&Tok::Colon, "expected ':' after a property name")?;
+            props.push((key, self.literal()?));
+            if !self.eat(&Tok::Comma) {
+                break;
+            }
+        }
+        self.expect(&Tok::RBrace, "expected '}' to close the properties")?;
+        Ok(props)
+    }
+
+    fn literal(&mut self) -> Result<AttrValue, GruPHstError> {
+        let negative = self.eat(&Tok::Dash);
+        let value = match self.peek() {
+            Some(Tok::Int(n)) => AttrValue::Int(if negative { -n } else { *n }),
+            Some(Tok::Float(n)) => AttrValue::Float(if negative { -n } else { *n }),
+            Some(Tok::Str(text)) if !negative => AttrValue::Str(text.clone()),
+            _ if negative => return self.error("expected a number"),
+            _ if self.is_keyword("true") => AttrValue::Bool(true),
+            _ if self.is_keyword("false") => AttrValue::Bool(false),
+            _ => return self.error("expected a value"),
+        };
+        self.idx += 1;
+        Ok(value)
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, GruPHstError> {
+        let mut expr = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, GruPHstError> {
+        let mut expr = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, GruPHstError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not_expr()?)));
+        }
+        if self.eat(&Tok::LParen) {
+            let expr = self.or_expr()?;
+            self.expect(&Tok::RParen, "expected ')' to close a condition")?;
+            return Ok(expr);
+        }
+        let left = self.operand()?;
+        let op = match self.peek() {
+            Some(Tok::Eq) => CmpOp::Eq,
+            Some(Tok::Neq) => CmpOp::Neq,
+            Some(Tok::Lt) => CmpOp::Lt,
+            Some(Tok::Le) => CmpOp::Le,
+            Some(Tok::Gt) => CmpOp::Gt,
+            Some(Tok::Ge) => CmpOp::Ge,
+            _ if self.is_keyword("CONTAINS") => CmpOp::Contains,
+            _ => return self.error("expected a comparison operator"),
+        };
+        self.idx += 1;
+        let right = self.operand()?;
+        Ok(Expr::Cmp(left, op, right))
+    }
+
+    /// A variable, a property, a function over a variable or a literal
+    fn operand(&mut self) -> Result<Operand, GruPHstError> {
+        let is_name = matches!(
+            self.peek(),
+            Some(Tok::Ident { name, quoted }) if *quoted || !is_reserved(name)
+        );
+        if !is_name {
+            return Ok(Operand::Lit(self.literal()?));
+        }
+        let pos = self.pos();
+        let name = self.name("a variable")?;
+        if self.eat(&Tok::LParen) {
+            let func = match name.to_lowercase().as_str() {
+                "id" => Func::Id,
+                "label" => Func::Label,
+                "type" => Func::Type,
+                _ => {
+                    return Err(GruPHstError::QuerySyntax(
+                        pos,
+                        format!("unknown function {}", name),
+                    ))
+                }
+            };
+            let var_pos = self.pos();
+            let var = self.name("a variable")?;
+            self.check_var(var_pos, &var)?;
+            self.expect(&Tok::RParen, "expected ')' to close a function")?;
+            return Ok(Operand::Func(func, var));
+        }
+        self.check_var(pos, &name)?;
+        if self.eat(&Tok::Dot) {
+            let key = self.text("a property name")?;
+            return Ok(Operand::Prop(name, key));
+        }
+        Ok(Operand::Var(name))
+    }
+
+    fn check_var(&self, pos: usize, var: &str) -> Result<(), GruPHstError> {
+        match self.vars.contains(var) {
+            true => Ok(()),
+            false => Err(GruPHstError::QueryUnknownVariable(pos, var.to_string())),
+        }
+    }
+
+    fn return_item(&mut self) -> Result<ReturnItem, GruPHstError> {
+        let operand = self.operand()?;
+        let column = match self.eat_keyword("AS") {
+            true => self.name("an alias")?,
+            false => match &operand {
+                Operand::Var(var) => var.clone(),
+                Operand::Prop(var, key) => format!("{}.{}", var, key),
+                Operand::Func(func, var) => format!("{}({})", func.name(), var),
+                Operand::Lit(value) => value.to_string(),
+            },
+        };
+        Ok(ReturnItem { operand, column })
+    }
+}
+
+impl Func {
+    fn name(&self) -> &'static str {
+        match self {
+            Func::Id => "id",
+            Func::Label => "label",
+            Func::Type => "type",
+        }
+    }
+}
+
+/// Keywords that can not be used as names unless quoted with backticks
+fn is_reserved(name: &str) -> bool {
+    const RESERVED: [&str; 12] = [
+        "MATCH", "WHERE", "RETURN", "DISTINCT", "LIMIT", "AS", "AND", "OR", "NOT", "CONTAINS",
+        "TRUE", "FALSE",
+    ];
+    RESERVED.iter().any(|k| k.eq_ignore_ascii_case(name))
+}
+
+fn describe(tok: &Tok) -> String {
+    match tok {
+        Tok::Ident { name, .. } => format!("'{}'", name),
+        Tok::Str(text) => format!("\"{}\"", text),
+        Tok::Int(n) => n.to_string(),
+        Tok::Float(n) => n.to_string(),
+        other => {
+            let symbol = match other {
+                Tok::LParen => "(",
+                Tok::RParen => ")",
+                Tok::LBracket => "[",
+                Tok::RBracket => "]",
+                Tok::LBrace => "{",
+                Tok::RBrace => "}",
+                Tok::Colon => ":",
+                Tok::Comma => ",",
+                Tok::Dot => ".",
+                Tok::DotDot => "..",
+                Tok::Pipe => "|",
+                Tok::Star => "*",
+                Tok::Dash => "-",
+                Tok::Arrow => "->",
+                Tok::LArrow => "<-",
+                Tok::Eq => "=",
+                Tok::Neq => "<>",
+                Tok::Lt => "<",
+                Tok::Le => "<=",
+                Tok::Gt => ">",
+                _ => ">=",
+            };
+            format!("'{}'", symbol)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Deletion of vertices
+//!
+//! A vertex is deleted from a vault, or from every vault of the Graphs,
+//! along with the edges from or to it (detach), or only when there is
+//! none of them (restrict).
+//! The deletions are written on the write-ahead log as a single record.
+
+use log::{error, info, warn};
+
+use crate::{
+    errors::GruPHstError,
+    graphs::{memory::VaultRef, snapshot::size, wal::WalRecord, Graphs},
+};
+
+/// What to do with the edges from or to a vertex to delete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteMode {
+    /// Deletes the edges along with the vertex
+    Detach,
+    /// Fails with VertexHasEdges when there is any edge
+    Restrict,
+}
+
+/// Positions of the edges from or to a vertex on a vault,
+/// from the last one
+fn edge_positions(vault: &VaultRef, id: &str) -> Vec<usize> {
+    let mut positions = vault.adjacency.out_positions(id, None);
+    positions.extend(vault.adjacency.in_positions(id, None));
+    positions.sort_unstable_by(|a, b| b.cmp(a));
+    positions.dedup();
+    positions
+}
+
+/// Records for the write-ahead log of the deletion of a vertex,
+/// and its edges, from a vault
+fn deletion_records(vault: &VaultRef, id: &str, vault_name: &str) -> Vec<WalRecord> {
+    let mut records: Vec<WalRecord> = edge_positions(vault, id)
+        .into_iter()
+        .map(|position| {
+            WalRecord::DeleteEdge(vault_name.to_string(), vault.edges[position].get_id())
+        })
+        .collect();
+    records.push(WalRecord::RemoveVertex(
+        vault_name.to_string(),
+        id.to_string(),
+    ));
+    records
+}
+
+impl Graphs {
+    /// Deletes the Vertex that matches with the provided id from the vault,
+    /// with its edges for Detach mode or failing with VertexHasEdges
+    /// when it has any for Restrict mode,
+    /// for some provided vault_name or default when None
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::{DeleteMode, Graphs}, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&Vertex::new("Gandalf"), "guide of", &frodo), None).unwrap();
+    ///
+    /// assert!(graphs.delete_vertex(&frodo.get_id(), DeleteMode::Restrict, None).is_err());
+    /// graphs.delete_vertex(&frodo.get_id(), DeleteMode::Detach, None).unwrap();
+    /// assert_eq!(graphs.len(), 0);
+    /// assert_eq!(graphs.get_vertices(None).unwrap().len(), 2);
+    /// ```
+    pub fn delete_vertex(
+        &mut self,
+        id: &str,
+        mode: DeleteMode,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        let Some(vault) = self.vault_ref(&current_vault)? else {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        };
+        if !vault.vertices.contains_key(id) {
+            warn!("Vertex [{}] to delete not found", id);
+            return Err(GruPHstError::VertexNotFound);
+        }
+        self.check_delete_mode(&vault, id, mode, &current_vault)?;
+        self.log_deletion(deletion_records(&vault, id, &current_vault))?;
+        self.unlink_vertex(id, &current_vault);
+        self.mutated();
+        Ok(())
+    }
+
+    /// Deletes the Vertex that matches with the provided id
+    /// from every graphs' vault, with its edges for Detach mode
+    /// or failing with VertexHasEdges when it has any on some vault
+    /// for Restrict mode, without deleting it from any vault.
+    /// Every vault with the vertex is reloaded before deleting it,
+    /// failing without deleting it when some vault could not be reloaded
+    pub fn delete_vertex_in_graphs(
+        &mut self,
+        id: &str,
+        mode: DeleteMode,
+    ) -> Result<(), GruPHstError> {
+        let mut vault_names: Vec<String> = self.vault.keys().cloned().collect();
+        vault_names.extend(self.vaults_on_disk());
+        let mut found: Vec<String> = Vec::new();
+        let mut records = Vec::new();
+        for vault_name in vault_names {
+            let Some(vault) = self.vault_ref(&vault_name)? else {
+                continue;
+            };
+            if vault.vertices.contains_key(id) {
+                self.check_delete_mode(&vault, id, mode, &vault_name)?;
+                records.extend(deletion_records(&vault, id, &vault_name));
+                found.push(vault_name);
+            }
+        }
+        if found.is_empty() {
+            warn!("Vertex [{}] to delete not found in graphs", id);
+            return Err(GruPHstError::VertexNotFound);
+        }
+        for vault_name in found.iter() {
+            self.reload_evicted(vault_name)?;
+        }
+        // a reload never evicts another vault, but it is checked
+        // so the deletion is never applied on some vaults only
+        if let Some(evicted) = found.iter().find(|name| !self.vault.contains_key(*name)) {
+            error!("Vault {} evicted before deleting [{}]", evicted, id);
+            return Err(GruPHstError::VaultEvicted(evicted.clone()));
+        }
+        self.log_deletion(records)?;
+        for vault_name in found.iter() {
+            self.unlink_vertex(id, vault_name);
+        }
+        self.mutated();
+        Ok(())
+    }
+
+    fn check_delete_mode(
+        &self,
+        vault: &VaultRef,
+        id: &str,
+        mode: DeleteMode,
+        vault_name: &str,
+    ) -> Result<(), GruPHstError> {
+        if mode == DeleteMode::Restrict && !edge_positions(vault, id).is_empty() {
+            warn!(
+                "Vertex [{}] to delete has edges on vault {}",
+                id, vault_name
+            );
+            return Err(GruPHstError::VertexHasEdges(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Removes a vertex, in memory, and its edges from a vault
+    fn unlink_vertex(&mut self, id: &str, vault_name: &str) {
+        let positions = match self.vault_ref(vault_name) {
+            Ok(Some(vault)) => edge_positions(&vault, id),
+            _ => Vec::new(),
+        };
+        let detached = positions.len();
+        for position in positions {
+            self.remove_edge_at(position, vault_name);
+        }
+        let removed = self
+            .vertices
+            .get_mut(vault_name)
+            .and_then(|vertices| vertices.remove(id));
+        if let Some(vertex) = removed {
+            if let Some(indexes) = self.indexes.get_mut(vault_name) {
+                indexes.remove_vertex(&vertex);
+            }
+            self.account(vault_name, -(size::vertex(&vertex) as isize));
+        }
+        info!(
+            "Vertex [{}] deleted from vault {} with {} edges",
+            id, vault_name, detached
+        );
+    }
+
+    fn log_deletion(&mut self, mut records: Vec<WalRecord>) -> Result<(), GruPHstError> {
+        match records.len() {
+            0 => Ok(()),
+            1 => self.log_mutation(records.remove(0)),
+            _ => self.log_mutation(WalRecord::Transaction(records)),
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Composable filters to query the edges of the Graphs
+//!
+//! Filters over relations, vertex labels and ids, vertex and edge attributes
+//! can be combined with AND, OR and NOT, and evaluated on a vault,
+//! or on all of them, with a single query.
+
+use std::{cmp::Ordering, ops::Not, ops::RangeBounds};
+
+use log::warn;
+
+use crate::{
+    attributes::{attr_range, AttrRange, AttrValue},
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::Graphs,
+    vertex::Vertex,
+};
+
+/// Vertex of an edge to check on a filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// the "from" or source vertex
+    From,
+    /// the "to" or target vertex
+    To,
+    /// any of the vertices
+    Any,
+    /// both vertices
+    Both,
+}
+
+#[derive(Debug, Clone)]
+enum AttrCond {
+    Key(String),
+    KeyLike(String),
+    Equals(String, AttrValue),
+    Like(String, String),
+    InRange(String, AttrRange),
+}
+
+#[derive(Debug, Clone)]
+enum VertexCond {
+    Id(String),
+    Label(String),
+    LabelLike(String),
+    Attr(AttrCond),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Relation(String),
+    EdgeAttr(AttrCond),
+    Vertex(Side, VertexCond),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+}
+
+/// A filter for edges, built from simple predicates
+/// combined with `and`, `or` and `!`
+///
+/// # Examples
+/// ```rust
+/// use gruphst::graphs::filter::{Filter, Side};
+///
+/// // friends of Frodo, or anyone older than a thousand years,
+/// // but not the Gandalf ones
+/// let filter = Filter::relation("friend of")
+///     .and(Filter::label(Side::To, "Frodo"))
+///     .or(Filter::vertex_attr_in_range(Side::Any, "years old", 1000..))
+///     .and(!Filter::label(Side::Any, "Gandalf"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Filter {
+    node: Node,
+}
+
+impl Filter {
+    fn new(node: Node) -> Self {
+        Filter { node }
+    }
+
+    /// Edges with the relation
+    pub fn relation(relation: &str) -> Self {
+        Filter::new(Node::Relation(relation.to_string()))
+    }
+
+    /// Edges where the vertex on the side has the id
+    pub fn vertex_id(side: Side, id: &str) -> Self {
+        Filter::new(Node::Vertex(side, VertexCond::Id(id.to_string())))
+    }
+
+    /// Edges where the vertex on the side has the label
+    pub fn label(side: Side, label: &str) -> Self {
+        Filter::new(Node::Vertex(side, VertexCond::Label(label.to_string())))
+    }
+
+    /// Edges where the label of the vertex on the side
+    /// contains the text, case insensitive
+    pub fn label_like(side: Side, label: &str) -> Self {
+        Filter::new(Node::Vertex(
+            side,
+            VertexCond::LabelLike(label.to_lowercase()),
+        ))
+    }
+
+    /// Edges where the vertex on the side has the attribute key
+    pub fn vertex_attr_key(side: Side, attr_k: &str) -> Self {
+        Filter::vertex_attr(side, AttrCond::Key(attr_k.to_string()))
+    }
+
+    /// Edges where the vertex on the side has an attribute key
+    /// that contains the text, case insensitive
+    pub fn vertex_attr_key_like(side: Side, attr_k: &str) -> Self {
+        Filter::vertex_attr(side, AttrCond::KeyLike(attr_k.to_lowercase()))
+    }
+
+    /// Edges where the vertex on the side has the attribute
+    /// equals to the value, integers and floats are compared as numbers
+    pub fn vertex_attr_equals<T>(side: Side, attr_k: &str, attr_v: T) -> Self
+    where
+        T: Into<AttrValue>,
+    {
+        Filter::vertex_attr(side, AttrCond::Equals(attr_k.to_string(), attr_v.into()))
+    }
+
+    /// Edges where the vertex on the side has the attribute
+    /// with a value that contains the text, case insensitive
+    pub fn vertex_attr_like(side: Side, attr_k: &str, text: &str) -> Self {
+        Filter::vertex_attr(
+            side,
+            AttrCond::Like(attr_k.to_string(), text.to_lowercase()),
+        )
+    }
+
+    /// Edges where the vertex on the side has the attribute inside of the range
+    pub fn vertex_attr_in_range<R, T>(side: Side, attr_k: &str, range: R) -> Self
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        Filter::vertex_attr(
+            side,
+            AttrCond::InRange(attr_k.to_string(), attr_range(&range)),
+        )
+    }
+
+    fn vertex_attr(side: Side, cond: AttrCond) -> Self {
+        Filter::new(Node::Vertex(side, VertexCond::Attr(cond)))
+    }
+
+    /// Edges with the attribute key
+    pub fn attr_key(attr_k: &str) -> Self {
+        Filter::new(Node::EdgeAttr(AttrCond::Key(attr_k.to_string())))
+    }
+
+    /// Edges with an attribute key that contains the text, case insensitive
+    pub fn attr_key_like(attr_k: &str) -> Self {
+        Filter::new(Node::EdgeAttr(AttrCond::KeyLike(attr_k.to_lowercase())))
+    }
+
+    /// Edges with the attribute equals to the value,
+    /// integers and floats are compared as numbers
+    pub fn attr_equals<T>(attr_k: &str, attr_v: T) -> Self
+    where
+        T: Into<AttrValue>,
+    {
+        Filter::new(Node::EdgeAttr(AttrCond::Equals(
+            attr_k.to_string(),
+            attr_v.into(),
+        )))
+    }
+
+    /// Edges with the attribute with a value
+    /// that contains the text, case insensitive
+    pub fn attr_like(attr_k: &str, text: &str) -> Self {
+        Filter::new(Node::EdgeAttr(AttrCond::Like(
+            attr_k.to_string(),
+            text.to_lowercase(),
+        )))
+    }
+
+    /// Edges with the attribute inside of the range
+    pub fn attr_in_range<R, T>(attr_k: &str, range: R) -> Self
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        Filter::new(Node::EdgeAttr(AttrCond::InRange(
+            attr_k.to_string(),
+            attr_range(&range),
+        )))
+    }
+
+    /// Edges that matches this filter and the other one
+    pub fn and(self, other: Filter) -> Self {
+        match self.node {
+            Node::And(mut nodes) => {
+                nodes.push(other.node);
+                Filter::new(Node::And(nodes))
+            }
+            node => Filter::new(Node::And(vec![node, other.node])),
+        }
+    }
+
+    /// Edges that matches this filter or the other one
+    pub fn or(self, other: Filter) -> Self {
+        match self.node {
+            Node::Or(mut nodes) => {
+                nodes.push(other.node);
+                Filter::new(Node::Or(nodes))
+            }
+            node => Filter::new(Node::Or(vec![node, other.node])),
+        }
+    }
+
+    /// Edges that matches all the filters, any edge when empty
+    pub fn all(filters: Vec<Filter>) -> Self {
+        Filter::new(Node::And(filters.into_iter().map(|f| f.node).collect()))
+    }
+
+    /// Edges that matches any of the filters, none when empty
+    pub fn any(filters: Vec<Filter>) -> Self {
+        Filter::new(Node::Or(filters.into_iter().map(|f| f.node).collect()))
+    }
+
+    /// Checks if an edge matches the filter
+    pub fn matches(&self, edge: &Edge) -> bool {
+        self.node.matches(edge)
+    }
+}
+
+impl Not for Filter {
+    type Output = Filter;
+
+    /// Edges that does not match the filter
+    fn not(self) -> Self::Output {
+        match self.node {
+            Node::Not(node) => Filter::new(*node),
+            node => Filter::new(Node::Not(Box::new(node))),
+        }
+    }
+}
+
+impl Node {
+    fn matches(&self, edge: &Edge) -> bool {
+        match self {
+            Node::Relation(relation) => edge.relation == *relation,
+            Node::EdgeAttr(cond) => cond.matches(
+                |attr_k| edge.get_attr_value(attr_k).ok(),
+                || edge.attr.keys().cloned().collect(),
+            ),
+            Node::Vertex(side, cond) => {
+                let from = || cond.matches(&edge.get_from_vertex());
+                let to = || cond.matches(&edge.get_to_vertex());
+                match side {
+                    Side::From => from(),
+                    Side::To => to(),
+                    Side::Any => from() || to(),
+                    Side::Both => from() && to(),
+                }
+            }
+            Node::And(nodes) => nodes.iter().all(|node| node.matches(edge)),
+            Node::Or(nodes) => nodes.iter().any(|node| node.matches(edge)),
+            Node::Not(node) => !node.matches(edge),
+        }
+    }
+}
+
+impl VertexCond {
+    fn matches(&self, vertex: &Vertex) -> bool {
+        match self {
+            VertexCond::Id(id) => vertex.get_id() == *id,
+            VertexCond::Label(label) => vertex.get_label() == *label,
+            VertexCond::LabelLike(label) => vertex.get_label().to_lowercase().contains(label),
+            VertexCond::Attr(cond) => cond.matches(
+                |attr_k| vertex.get_attr_value(attr_k).ok(),
+                || vertex.get_attr_keys(),
+            ),
+        }
+    }
+}
+
+impl AttrCond {
+    /// Checks the condition with the value of an attribute by key,
+    /// and all the attribute keys
+    fn matches<V, K>(&self, value: V, keys: K) -> bool
+    where
+        V: Fn(&str) -> Option<AttrValue>,
+        K: Fn() -> Vec<String>,
+    {
+        match self {
+            AttrCond::Key(attr_k) => value(attr_k).is_some(),
+            AttrCond::KeyLike(attr_k) => {
+                keys().iter().any(|key| key.to_lowercase().contains(attr_k))
+            }
+            AttrCond::Equals(attr_k, attr_v) => match value(attr_k) {
+                Some(v) => v == *attr_v || v.compare(attr_v) == Some(Ordering::Equal),
+                None => false,
+            },
+            AttrCond::Like(attr_k, text) => match value(attr_k) {
+                Some(v) if !v.is_bytes() => v.to_string().to_lowercase().contains(text),
+                _ => false,
+            },
+            AttrCond::InRange(attr_k, range) => match value(attr_k) {
+                Some(v) => v.is_within(range),
+                None => false,
+            },
+        }
+    }
+}
+
+impl Graphs {
+    /// Returns a collection of edges that matches the filter
+    /// for some provided vault_name or default when None
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    /// use gruphst::graphs::filter::{Filter, Side};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut gandalf = Vertex::new("Gandalf");
+    /// gandalf.set_attr("years old", 24000);
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&gandalf, "friend of", &frodo), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("The Shire")), None).unwrap();
+    ///
+    /// let filter = Filter::relation("friend of")
+    ///     .and(!Filter::vertex_attr_in_range(Side::From, "years old", 1000..));
+    /// let edges = graphs.query_edges(&filter, None).unwrap();
+    /// assert_eq!(edges.len(), 1);
+    /// assert_eq!(edges[0].get_to_vertex().get_label(), "Sam");
+    /// ```
+    pub fn query_edges(
+        &self,
+        filter: &Filter,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<&Edge>, GruPHstError> {
+        let edges = self.select_vault_ref(vault_name)?;
+        let result: Vec<&Edge> = edges.iter().filter(|edge| filter.matches(edge)).collect();
+        if !result.is_empty() {
+            Ok(result)
+        } else {
+            warn!("Any edge found for filter");
+            Err(GruPHstError::EdgeNotFound)
+        }
+    }
+
+    /// Returns a collection of edges that matches the filter
+    /// on any graphs' vault
+    pub fn query_edges_in_graphs(&self, filter: &Filter) -> Result<Vec<&Edge>, GruPHstError> {
+        let mut result: Vec<&Edge> = Vec::new();
+        for vault in self.vaults_in_graphs() {
+            let (_, vault) = vault?;
+            result.extend(vault.edges.iter().filter(|edge| filter.matches(edge)));
+        }
+        if !result.is_empty() {
+            Ok(result)
+        } else {
+            warn!("Any edge found in graphs for filter");
+            Err(GruPHstError::EdgeNotFound)
+        }
+    }
+}
@@ -0,0 +1,633 @@
+//! Secondary indexes on the attributes of vertices and edges
+//!
+//! An index is created on a vault for an attribute key, of its vertices
+//! or its edges, and keeps the ids of the vertices, or the positions
+//! of the edges, by the value of the attribute. A hash index serves
+//! the lookups by equality, a BTree one the lookups by range too.
+//! Integers and floats are indexed as numbers; the lookups only retrieve
+//! candidates from the index, checked against the current value.
+//!
+//! The indexes are kept in sync with every mutation through the Graphs.
+//! The vertices changed out of the Graphs, through a shared Vertex,
+//! are marked as stale on the indexes watching them; a lookup always
+//! retrieves the stale vertices as candidates too, and they are indexed
+//! again on the next mutation through the Graphs.
+//! The definition of the indexes is saved with the vault,
+//! and the indexes built again when it is loaded.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    ops::Bound,
+};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attributes::{AttrRange, AttrValue},
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{memory::VaultRef, wal::WalRecord, Graphs},
+    util::serialized_size,
+    vertex::{mark_stale_ids, stale_ids, take_stale_ids, StaleIds, Vertex},
+};
+
+/// Elements of a vault indexed by an attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IndexTarget {
+    Vertex,
+    Edge,
+}
+
+/// Structure of an index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IndexKind {
+    /// serves lookups by equality
+    Hash,
+    /// serves lookups by equality and by range
+    BTree,
+}
+
+/// Definition of an index, saved with the vault
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IndexDef {
+    pub(crate) attr_k: String,
+    pub(crate) target: IndexTarget,
+    pub(crate) kind: IndexKind,
+}
+
+/// Value of an attribute as key of an index,
+/// with integers and floats as numbers; lists are not indexed
+#[derive(Debug, Clone)]
+enum IndexKey {
+    Number(f64),
+    Bool(bool),
+    Str(String),
+    Bytes(Vec<u8>),
+    Timestamp(i64),
+}
+
+impl IndexKey {
+    fn of(value: &AttrValue) -> Option<IndexKey> {
+        match value {
+            // the same key for 0 and -0
+            AttrValue::Int(_) | AttrValue::Float(_) => {
+                Some(IndexKey::Number(value.as_f64()? + 0.0))
+            }
+            AttrValue::Bool(v) => Some(IndexKey::Bool(*v)),
+            AttrValue::Str(v) => Some(IndexKey::Str(v.clone())),
+            AttrValue::Bytes(v) => Some(IndexKey::Bytes(v.clone())),
+            AttrValue::Timestamp(v) => Some(IndexKey::Timestamp(*v)),
+            AttrValue::List(_) => None,
+        }
+    }
+
+    /// Keys of the values written as the text
+    fn of_text(text: &str) -> Vec<IndexKey> {
+        let mut keys = vec![IndexKey::Str(text.to_string())];
+        if let Ok(number) = text.parse::<f64>() {
+            keys.push(IndexKey::Number(number + 0.0));
+        }
+        if let Ok(boolean) = text.parse::<bool>() {
+            keys.push(IndexKey::Bool(boolean));
+        }
+        if let Ok(millis) = text.parse::<i64>() {
+            keys.push(IndexKey::Timestamp(millis));
+        }
+        keys
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            IndexKey::Number(_) => 0,
+            IndexKey::Bool(_) => 1,
+            IndexKey::Str(_) => 2,
+            IndexKey::Bytes(_) => 3,
+            IndexKey::Timestamp(_) => 4,
+        }
+    }
+}
+
+impl Ord for IndexKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (IndexKey::Number(a), IndexKey::Number(b)) => a.total_cmp(b),
+            (IndexKey::Bool(a), IndexKey::Bool(b)) => a.cmp(b),
+            (IndexKey::Str(a), IndexKey::Str(b)) => a.cmp(b),
+            (IndexKey::Bytes(a), IndexKey::Bytes(b)) => a.cmp(b),
+            (IndexKey::Timestamp(a), IndexKey::Timestamp(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for IndexKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for IndexKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for IndexKey {}
+
+impl Hash for IndexKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            IndexKey::Number(v) => v.to_bits().hash(state),
+            IndexKey::Bool(v) => v.hash(state),
+            IndexKey::Str(v) => v.hash(state),
+            IndexKey::Bytes(v) => v.hash(state),
+            IndexKey::Timestamp(v) => v.hash(state),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entries<T> {
+    Hash(HashMap<IndexKey, HashSet<T>>),
+    BTree(BTreeMap<IndexKey, HashSet<T>>),
+}
+
+/// Index of the elements of a vault by the value of an attribute
+#[derive(Debug, Clone)]
+pub(crate) struct AttrIndex<T> {
+    entries: Entries<T>,
+    /// elements with a value that is not indexed, like a list
+    others: HashSet<T>,
+    /// key of every element, None for the other ones
+    placed: HashMap<T, Option<IndexKey>>,
+}
+
+impl<T: Clone + Eq + Hash> AttrIndex<T> {
+    fn new(kind: IndexKind) -> Self {
+        let entries = match kind {
+            IndexKind::Hash => Entries::Hash(HashMap::new()),
+            IndexKind::BTree => Entries::BTree(BTreeMap::new()),
+        };
+        AttrIndex {
+            entries,
+            others: HashSet::new(),
+            placed: HashMap::new(),
+        }
+    }
+
+    fn kind(&self) -> IndexKind {
+        match self.entries {
+            Entries::Hash(_) => IndexKind::Hash,
+            Entries::BTree(_) => IndexKind::BTree,
+        }
+    }
+
+    /// Places an element by the value, or removes it when there is none
+    fn insert(&mut self, value: Option<&AttrValue>, element: T) {
+        self.remove(&element);
+        let Some(value) = value else {
+            return;
+        };
+        let Some(key) = IndexKey::of(value) else {
+            self.others.insert(element.clone());
+            self.placed.insert(element, None);
+            return;
+        };
+        match &mut self.entries {
+            Entries::Hash(entries) => entries.entry(key.clone()).or_default(),
+            Entries::BTree(entries) => entries.entry(key.clone()).or_default(),
+        }
+        .insert(element.clone());
+        self.placed.insert(element, Some(key));
+    }
+
+    fn remove(&mut self, element: &T) {
+        let Some(placed) = self.placed.remove(element) else {
+            return;
+        };
+        let Some(key) = placed else {
+            self.others.remove(element);
+            return;
+        };
+        let elements = match &mut self.entries {
+            Entries::Hash(entries) => entries.get_mut(&key),
+            Entries::BTree(entries) => entries.get_mut(&key),
+        };
+        if let Some(elements) = elements {
+            elements.remove(element);
+            if elements.is_empty() {
+                match &mut self.entries {
+                    Entries::Hash(entries) => entries.remove(&key),
+                    Entries::BTree(entries) => entries.remove(&key),
+                };
+            }
+        }
+    }
+
+    fn get(&self, key: &IndexKey) -> Option<&HashSet<T>> {
+        match &self.entries {
+            Entries::Hash(entries) => entries.get(key),
+            Entries::BTree(entries) => entries.get(key),
+        }
+    }
+
+    /// Candidates with a value equal to the provided one
+    pub(crate) fn equal(&self, value: &AttrValue) -> Vec<T> {
+        match IndexKey::of(value) {
+            Some(key) => self.get(&key).into_iter().flatten().cloned().collect(),
+            None => self.others.iter().cloned().collect(),
+        }
+    }
+
+    /// Candidates with a value written as the provided text
+    pub(crate) fn equal_text(&self, text: &str) -> Vec<T> {
+        let mut candidates: HashSet<T> = self.others.clone();
+        for key in IndexKey::of_text(text) {
+            candidates.extend(self.get(&key).into_iter().flatten().cloned());
+        }
+        candidates.into_iter().collect()
+    }
+
+    /// Candidates with a value inside of the range, every element
+    /// for a hash index. The bounds are included, since integers
+    /// could share the key of a number
+    pub(crate) fn range(&self, range: &AttrRange) -> Vec<T> {
+        let bound = |bound: &Bound<AttrValue>| match bound {
+            Bound::Included(value) | Bound::Excluded(value) => {
+                IndexKey::of(value).map(Bound::Included)
+            }
+            Bound::Unbounded => Some(Bound::Unbounded),
+        };
+        match &self.entries {
+            Entries::Hash(entries) => entries.values().flatten().cloned().collect(),
+            Entries::BTree(entries) => match (bound(&range.0), bound(&range.1)) {
+                (Some(Bound::Included(start)), Some(Bound::Included(end))) if start > end => {
+                    Vec::new()
+                }
+                (Some(start), Some(end)) => entries
+                    .range((start, end))
+                    .flat_map(|(_, elements)| elements)
+                    .cloned()
+                    .collect(),
+                // a list is never inside of a range
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+impl AttrIndex<usize> {
+    /// Moves back one place the edges after the provided position
+    fn shift_after(&mut self, position: usize) {
+        let shift = |p: usize| if p > position { p - 1 } else { p };
+        let shift_all = |positions: &mut HashSet<usize>| {
+            *positions = positions.drain().map(shift).collect();
+        };
+        match &mut self.entries {
+            Entries::Hash(entries) => entries.values_mut().for_each(shift_all),
+            Entries::BTree(entries) => entries.values_mut().for_each(shift_all),
+        }
+        shift_all(&mut self.others);
+        self.placed = self
+            .placed
+            .drain()
+            .map(|(p, key)| (shift(p), key))
+            .collect();
+    }
+}
+
+/// Indexes of a vault, by attribute key
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VaultIndexes {
+    /// ids of the vertices
+    vertices: HashMap<String, AttrIndex<String>>,
+    /// positions of the edges on the vault
+    edges: HashMap<String, AttrIndex<usize>>,
+    /// ids of the vertices changed out of the Graphs, shared with
+    /// the vertices watched
+    stale: StaleIds,
+}
+
+impl VaultIndexes {
+    /// Builds the indexes of the definitions for the vertices and edges
+    pub(crate) fn build(
+        defs: &[IndexDef],
+        vertices: &HashMap<String, Vertex>,
+        edges: &[Edge],
+    ) -> Self {
+        let mut indexes = VaultIndexes::default();
+        for def in defs {
+            indexes.create(def, vertices, edges);
+        }
+        indexes
+    }
+
+    /// Definitions of the indexes, sorted
+    pub(crate) fn defs(&self) -> Vec<IndexDef> {
+        let vertices = self.vertices.iter().map(|(attr_k, index)| IndexDef {
+            attr_k: attr_k.clone(),
+            target: IndexTarget::Vertex,
+            kind: index.kind(),
+        });
+        let edges = self.edges.iter().map(|(attr_k, index)| IndexDef {
+            attr_k: attr_k.clone(),
+            target: IndexTarget::Edge,
+            kind: index.kind(),
+        });
+        let mut defs: Vec<IndexDef> = vertices.chain(edges).collect();
+        defs.sort_by(|a, b| (&a.attr_k, a.target as u8).cmp(&(&b.attr_k, b.target as u8)));
+        defs
+    }
+
+    /// Estimated bytes of the definitions once serialized
+    pub(crate) fn size(&self) -> usize {
+        self.defs().iter().map(serialized_size).sum()
+    }
+
+    fn create(&mut self, def: &IndexDef, vertices: &HashMap<String, Vertex>, edges: &[Edge]) {
+        match def.target {
+            IndexTarget::Vertex => {
+                let mut index = AttrIndex::new(def.kind);
+                for (id, vertex) in vertices.iter() {
+                    vertex.watch(&self.stale);
+                    let value = vertex.attr_value(&def.attr_k);
+                    index.insert(value.as_ref(), id.clone());
+                }
+                self.vertices.insert(def.attr_k.clone(), index);
+            }
+            IndexTarget::Edge => {
+                let mut index = AttrIndex::new(def.kind);
+                for (position, edge) in edges.iter().enumerate() {
+                    index.insert(edge.attr.get(&def.attr_k), position);
+                }
+                self.edges.insert(def.attr_k.clone(), index);
+            }
+        }
+    }
+
+    fn drop_index(&mut self, attr_k: &str, target: IndexTarget) -> bool {
+        match target {
+            IndexTarget::Vertex => self.vertices.remove(attr_k).is_some(),
+            IndexTarget::Edge => self.edges.remove(attr_k).is_some(),
+        }
+    }
+
+    pub(crate) fn vertex_index(&self, attr_k: &str) -> Option<&AttrIndex<String>> {
+        self.vertices.get(attr_k)
+    }
+
+    pub(crate) fn edge_index(&self, attr_k: &str) -> Option<&AttrIndex<usize>> {
+        self.edges.get(attr_k)
+    }
+
+    /// Indexes a vertex registered on the vault
+    pub(crate) fn add_vertex(&mut self, vertex: &Vertex) {
+        if self.vertices.is_empty() {
+            return;
+        }
+        vertex.watch(&self.stale);
+        let id = vertex.get_id();
+        for (attr_k, index) in self.vertices.iter_mut() {
+            index.insert(vertex.attr_value(attr_k).as_ref(), id.clone());
+        }
+    }
+
+    /// Removes a vertex from the indexes
+    pub(crate) fn remove_vertex(&mut self, vertex: &Vertex) {
+        let id = vertex.get_id();
+        for index in self.vertices.values_mut() {
+            index.remove(&id);
+        }
+    }
+
+    /// Ids of the vertices changed out of the Graphs
+    pub(crate) fn stale_vertices(&self) -> Vec<String> {
+        stale_ids(&self.stale)
+    }
+
+    /// Marks the vertices as changed out of the Graphs
+    pub(crate) fn mark_stale(&self, ids: Vec<String>) {
+        mark_stale_ids(&self.stale, ids);
+    }
+
+    /// Indexes again the vertices changed out of the Graphs
+    pub(crate) fn refresh(&mut self, vertices: &HashMap<String, Vertex>) {
+        for id in take_stale_ids(&self.stale) {
+            for (attr_k, index) in self.vertices.iter_mut() {
+                match vertices.get(&id) {
+                    Some(vertex) => index.insert(vertex.attr_value(attr_k).as_ref(), id.clone()),
+                    None => index.remove(&id),
+                }
+            }
+        }
+    }
+
+    /// Indexes an edge placed at the provided position
+    pub(crate) fn add_edge(&mut self, edge: &Edge, position: usize) {
+        for (attr_k, index) in self.edges.iter_mut() {
+            index.insert(edge.attr.get(attr_k), position);
+        }
+    }
+
+    /// Removes an edge placed at the provided position,
+    /// the positions of the following edges are shifted
+    /// as it happens on the vault
+    pub(crate) fn remove_edge(&mut self, position: usize) {
+        for index in self.edges.values_mut() {
+            index.remove(&position);
+            index.shift_after(position);
+        }
+    }
+}
+
+impl<'a> VaultRef<'a> {
+    /// Vertices that could match a lookup on an attribute,
+    /// from the index, with the stale ones, when there is one
+    /// or every vertex otherwise
+    pub(crate) fn vertex_candidates<F>(&self, attr_k: &str, lookup: F) -> Vec<&'a Vertex>
+    where
+        F: FnOnce(&AttrIndex<String>) -> Vec<String>,
+    {
+        match self.vertex_ids(attr_k, lookup) {
+            Some(ids) => ids.iter().filter_map(|id| self.vertices.get(id)).collect(),
+            None => self.vertices.values().collect(),
+        }
+    }
+
+    /// Ids of the vertices retrieved from the index, with the stale ones
+    fn vertex_ids<F>(&self, attr_k: &str, lookup: F) -> Option<HashSet<String>>
+    where
+        F: FnOnce(&AttrIndex<String>) -> Vec<String>,
+    {
+        let index = self.indexes.vertex_index(attr_k)?;
+        let mut ids: HashSet<String> = lookup(index).into_iter().collect();
+        ids.extend(self.indexes.stale_vertices());
+        Some(ids)
+    }
+
+    /// Edges that could match a lookup on an attribute,
+    /// from the index when there is one or every edge otherwise,
+    /// in the order of the vault
+    pub(crate) fn edge_candidates<F>(&self, attr_k: &str, lookup: F) -> Vec<&'a Edge>
+    where
+        F: FnOnce(&AttrIndex<usize>) -> Vec<usize>,
+    {
+        match self.indexes.edge_index(attr_k) {
+            Some(index) => self.edges_at(lookup(index)),
+            None => self.edges.iter().collect(),
+        }
+    }
+
+    /// Edges with a "from" or "to" vertex that could match a lookup
+    /// on an attribute, from the index of the vertices and the adjacency
+    /// when there is one or every edge otherwise, in the order of the vault
+    pub(crate) fn vertex_edge_candidates<F>(&self, attr_k: &str, lookup: F) -> Vec<&'a Edge>
+    where
+        F: FnOnce(&AttrIndex<String>) -> Vec<String>,
+    {
+        match self.vertex_ids(attr_k, lookup) {
+            Some(ids) => {
+                let positions = ids
+                    .iter()
+                    .flat_map(|id| {
+                        let mut positions = self.adjacency.out_positions(id, None);
+                        positions.extend(self.adjacency.in_positions(id, None));
+                        positions
+                    })
+                    .collect();
+                self.edges_at(positions)
+            }
+            None => self.edges.iter().collect(),
+        }
+    }
+
+    fn edges_at(&self, mut positions: Vec<usize>) -> Vec<&'a Edge> {
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+            .into_iter()
+            .filter_map(|position| self.edges.get(position))
+            .collect()
+    }
+}
+
+impl Graphs {
+    /// Creates an index on a vault for an attribute of its vertices
+    /// or its edges, for some provided vault_name or default when None;
+    /// an index for the same attribute and target is replaced.
+    /// The lookups by equality and by range on the attribute,
+    /// like `find_vertices_with_attr_equals_to` or `find_edges_with_attr_in_range`,
+    /// are served from the index
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::{Graphs, IndexKind, IndexTarget}, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut frodo = Vertex::new("Frodo");
+    /// frodo.set_attr("email", "frodo@shire.me");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    ///
+    /// graphs.create_index("email", IndexTarget::Vertex, IndexKind::Hash, None).unwrap();
+    /// let found = graphs.find_vertices_with_attr_equals_to("email", "frodo@shire.me", None).unwrap();
+    /// assert_eq!(found[0].get_label(), "Frodo");
+    /// ```
+    pub fn create_index(
+        &mut self,
+        attr_k: &str,
+        target: IndexTarget,
+        kind: IndexKind,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        if !self.vault.contains_key(&current_vault) {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        }
+        let def = IndexDef {
+            attr_k: attr_k.to_string(),
+            target,
+            kind,
+        };
+        self.reserve_memory(serialized_size(&def), &current_vault)?;
+        self.log_mutation(WalRecord::CreateIndex(
+            current_vault.clone(),
+            attr_k.to_string(),
+            target,
+            kind,
+        ))?;
+        let indexes = self.indexes.entry(current_vault.clone()).or_default();
+        let before = indexes.size();
+        indexes.drop_index(attr_k, target);
+        indexes.create(
+            &def,
+            &self.vertices[&current_vault],
+            &self.vault[&current_vault],
+        );
+        let after = self.indexes[&current_vault].size();
+        self.account(&current_vault, after as isize - before as isize);
+        info!(
+            "Index {:?} on {:?} attribute {} created for vault {}",
+            kind, target, attr_k, current_vault
+        );
+        self.mutated();
+        Ok(())
+    }
+
+    /// Drops the index on a vault for an attribute of its vertices or its edges,
+    /// for some provided vault_name or default when None
+    pub fn drop_index(
+        &mut self,
+        attr_k: &str,
+        target: IndexTarget,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        let Some(indexes) = self.indexes.get(&current_vault) else {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        };
+        if !indexes
+            .defs()
+            .iter()
+            .any(|def| def.attr_k == attr_k && def.target == target)
+        {
+            warn!("Index on {:?} attribute {} not found", target, attr_k);
+            return Err(GruPHstError::IndexNotFound(attr_k.to_string()));
+        }
+        self.log_mutation(WalRecord::DropIndex(
+            current_vault.clone(),
+            attr_k.to_string(),
+            target,
+        ))?;
+        let indexes = self.indexes.get_mut(&current_vault).unwrap();
+        let before = indexes.size();
+        indexes.drop_index(attr_k, target);
+        let after = indexes.size();
+        self.account(&current_vault, after as isize - before as isize);
+        self.mutated();
+        Ok(())
+    }
+
+    /// Retrieves the indexes of a vault, by attribute and target,
+    /// for some provided vault_name or default when None
+    pub fn get_indexes(
+        &self,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<(String, IndexTarget, IndexKind)>, GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        match self.vault_ref(&current_vault)? {
+            Some(vault) => Ok(vault
+                .indexes
+                .defs()
+                .into_iter()
+                .map(|def| (def.attr_k, def.target, def.kind))
+                .collect()),
+            None => Err(self.select_vault_not_exists_error(current_vault)),
+        }
+    }
+}
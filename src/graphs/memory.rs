@@ -0,0 +1,621 @@
+//! Policies for a Graphs that reaches the memory limit
+//!
+//! The memory is checked before every write that grows the Graphs,
+//! against GRUPHST_MAX_MEM_USAGE. The policy is read from GRUPHST_MEMORY_POLICY
+//! when a Graphs is initialized or loaded, or set with `set_memory_policy`.
+//! With the evict policy the least recently used vaults are written
+//! to a file on GRUPHST_EVICTION_PATH, or the temporary directory,
+//! and loaded again by the next access to them or with `reload_vault`.
+//! The file is removed once the vault is reloaded or deleted,
+//! or the Graphs, and its clones, dropped. It is encrypted
+//! when the Graphs has the key of `save_encrypted` or `load_encrypted`.
+//!
+//! A vault loaded by a read, through a shared reference, is kept apart
+//! and moved into the Graphs by the next write.
+//!
+//! The memory in use is a running estimate, updated on every mutation
+//! with the size of the serialized vertices and edges,
+//! checked against the exact size only when it is close to the limit.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use log::{error, info, warn};
+
+use crate::{
+    config::{get_compression, get_eviction_path, get_memory_policy},
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{adjacency::Adjacency, index::VaultIndexes, schema::Schema, snapshot::size, Graphs},
+    util::graphs_memory_watcher,
+    vertex::Vertex,
+};
+
+/// What to do when a write would exceed the memory limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryPolicy {
+    /// the write fails with MemoryLimitExceeded
+    Reject,
+    /// the least recently used vaults are evicted to disk,
+    /// the write fails with MemoryLimitExceeded
+    /// when there is nothing else to evict
+    Evict,
+    /// the Graphs is saved and the process panics
+    #[default]
+    Panic,
+}
+
+/// File of a vault on disk, a temporary one
+/// is removed when no Graphs refers to it
+#[derive(Debug)]
+struct VaultFile {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl Drop for VaultFile {
+    fn drop(&mut self) {
+        if !self.temporary {
+            return;
+        }
+        if let Err(err) = fs::remove_file(&self.path) {
+            warn!("Evicted vault file {:?} not removed: {}", self.path, err);
+        }
+    }
+}
+
+/// A vault loaded from disk, with its estimated bytes
+#[derive(Debug, Clone)]
+pub(crate) struct LoadedVault {
+    pub(crate) edges: Vec<Edge>,
+    pub(crate) vertices: HashMap<String, Vertex>,
+    pub(crate) adjacency: Adjacency,
+    pub(crate) indexes: VaultIndexes,
+    pub(crate) schema: Option<Schema>,
+    size: usize,
+}
+
+/// A vault out of the Graphs, evicted or not loaded yet
+#[derive(Debug, Clone)]
+struct DiskVault {
+    file: Arc<VaultFile>,
+    /// set once loaded by a read, until the next write
+    loaded: OnceLock<LoadedVault>,
+}
+
+impl DiskVault {
+    fn new(path: PathBuf, temporary: bool) -> Self {
+        DiskVault {
+            file: Arc::new(VaultFile { path, temporary }),
+            loaded: OnceLock::new(),
+        }
+    }
+}
+
+/// Edges, vertices, adjacency, indexes and schema of a vault in memory
+pub(crate) struct VaultRef<'a> {
+    pub(crate) edges: &'a Vec<Edge>,
+    pub(crate) vertices: &'a HashMap<String, Vertex>,
+    pub(crate) adjacency: &'a Adjacency,
+    pub(crate) indexes: &'a VaultIndexes,
+    pub(crate) schema: Option<&'a Schema>,
+}
+
+/// Memory policy of a Graphs and the usage of its vaults
+#[derive(Debug)]
+pub(crate) struct MemorySlot {
+    policy: MemoryPolicy,
+    /// increases on every access to a vault
+    clock: AtomicU64,
+    /// last access to every vault in memory
+    used: HashMap<String, AtomicU64>,
+    /// vaults on disk, evicted or not loaded yet
+    evicted: HashMap<String, DiskVault>,
+    /// estimated bytes of every vault in memory
+    sizes: HashMap<String, usize>,
+    /// estimated bytes out of the vaults,
+    /// corrected on every exact check
+    base: isize,
+}
+
+impl MemorySlot {
+    fn new(policy: MemoryPolicy) -> Self {
+        MemorySlot {
+            policy,
+            clock: AtomicU64::new(0),
+            used: HashMap::new(),
+            evicted: HashMap::new(),
+            sizes: HashMap::new(),
+            base: 0,
+        }
+    }
+
+    /// With the policy of GRUPHST_MEMORY_POLICY
+    pub(crate) fn from_config() -> Self {
+        MemorySlot::new(get_memory_policy())
+    }
+}
+
+impl Default for MemorySlot {
+    fn default() -> Self {
+        MemorySlot::new(MemoryPolicy::default())
+    }
+}
+
+/// A cloned Graphs shares the files of the vaults on disk
+impl Clone for MemorySlot {
+    fn clone(&self) -> Self {
+        let clock = self.clock.load(Ordering::Relaxed);
+        MemorySlot {
+            policy: self.policy,
+            clock: AtomicU64::new(clock),
+            used: self
+                .used
+                .iter()
+                .map(|(vault, used)| (vault.clone(), AtomicU64::new(used.load(Ordering::Relaxed))))
+                .collect(),
+            evicted: self.evicted.clone(),
+            sizes: self.sizes.clone(),
+            base: self.base,
+        }
+    }
+}
+
+impl Graphs {
+    /// Sets what to do when a write would exceed the memory limit
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::graphs::{Graphs, MemoryPolicy};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// graphs.set_memory_policy(MemoryPolicy::Reject);
+    /// assert_eq!(graphs.get_memory_policy(), MemoryPolicy::Reject);
+    /// ```
+    pub fn set_memory_policy(&mut self, policy: MemoryPolicy) {
+        self.memory.policy = policy;
+    }
+
+    /// Retrieves what to do when a write would exceed the memory limit
+    pub fn get_memory_policy(&self) -> MemoryPolicy {
+        self.memory.policy
+    }
+
+    /// Checks if a vault is on disk, evicted or not loaded yet
+    pub fn is_evicted(&self, vault_name: &str) -> bool {
+        self.memory
+            .evicted
+            .get(vault_name)
+            .is_some_and(|disk| disk.loaded.get().is_none())
+    }
+
+    /// Retrieves the names of the vaults on disk, evicted or not loaded yet
+    pub fn get_evicted_vaults(&self) -> Vec<String> {
+        self.memory
+            .evicted
+            .iter()
+            .filter(|(_, disk)| disk.loaded.get().is_none())
+            .map(|(vault_name, _)| vault_name.clone())
+            .collect()
+    }
+
+    /// Retrieves the running estimate of the memory in use, in bytes,
+    /// updated on every mutation without serializing the Graphs.
+    /// The vaults on disk are not in memory.
+    /// It differs from `get_mem` when a vertex is shared by several vaults,
+    /// or its attributes are changed out of the Graphs
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let edge = Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam"));
+    /// graphs.add_edge(&edge, None).unwrap();
+    /// assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+    /// ```
+    pub fn get_mem_estimate(&self) -> usize {
+        let vaults: usize = self.memory.sizes.values().sum();
+        let loaded: usize = self
+            .memory
+            .evicted
+            .values()
+            .filter_map(|disk| disk.loaded.get())
+            .map(|loaded| loaded.size)
+            .sum();
+        (vaults + loaded).saturating_add_signed(self.memory.base)
+    }
+
+    /// Measures the exact memory in use, serializing the Graphs,
+    /// and corrects the running estimate with it
+    pub(crate) fn sync_mem_estimate(&mut self) -> usize {
+        let mem = self.get_mem().unwrap();
+        self.memory.base += mem as isize - self.get_mem_estimate() as isize;
+        mem
+    }
+
+    /// Starts the running estimate of the memory in use
+    /// with the bytes out of the vaults, tracked on their own
+    pub(crate) fn measure_mem(&mut self) {
+        self.memory.base = size::graphs(self) as isize;
+    }
+
+    /// Sets the label, updating the estimate with its length
+    pub(crate) fn relabel(&mut self, label: &str) {
+        self.memory.base += label.len() as isize - self.label.len() as isize;
+        self.label = label.to_string();
+    }
+
+    /// Adds, or removes when negative, some bytes to the estimate of a vault
+    pub(crate) fn account(&mut self, vault_name: &str, bytes: isize) {
+        let size = self.memory.sizes.entry(vault_name.to_string()).or_default();
+        *size = size.saturating_add_signed(bytes);
+    }
+
+    /// Estimates the bytes of a vault in memory
+    fn measure_vault(&self, vault_name: &str) -> usize {
+        let vertices: usize = self
+            .vertices
+            .get(vault_name)
+            .map_or(0, |vertices| vertices.values().map(size::vertex).sum());
+        let edges: usize = self
+            .vault
+            .get(vault_name)
+            .map_or(0, |edges| edges.iter().map(size::edge).sum());
+        let indexes = self.indexes.get(vault_name).map_or(0, VaultIndexes::size);
+        let schema = self.schemas.get(vault_name).map_or(0, size::schema);
+        size::vault(vault_name) + vertices + edges + indexes + schema
+    }
+
+    /// Reloads a vault on disk, the vertices shared
+    /// with other vaults are linked again to the ones in memory.
+    /// The reload is not checked against the memory limit
+    pub fn reload_vault(&mut self, vault_name: &str) -> Result<(), Box<dyn Error>> {
+        let Some(disk) = self.memory.evicted.get_mut(vault_name) else {
+            return Err(GruPHstError::VaultNotExists(vault_name.to_string()).into());
+        };
+        let loaded = match disk.loaded.take() {
+            Some(loaded) => loaded,
+            None => {
+                let path = disk.file.path.clone();
+                self.read_vault(vault_name, &path)?
+            }
+        };
+        self.savepoint_vault(vault_name);
+        self.vault.insert(vault_name.to_string(), loaded.edges);
+        self.vertices
+            .insert(vault_name.to_string(), loaded.vertices);
+        self.adjacency
+            .insert(vault_name.to_string(), loaded.adjacency);
+        self.indexes.insert(vault_name.to_string(), loaded.indexes);
+        if let Some(schema) = loaded.schema {
+            self.schemas.insert(vault_name.to_string(), schema);
+        }
+        self.track_vault(vault_name);
+        info!("Vault {} reloaded", vault_name);
+        Ok(())
+    }
+
+    /// Reloads the vault, when on disk, before a write on it
+    pub(crate) fn reload_evicted(&mut self, vault_name: &str) -> Result<(), GruPHstError> {
+        if !self.memory.evicted.contains_key(vault_name) {
+            return Ok(());
+        }
+        self.reload_vault(vault_name).map_err(|err| {
+            error!("Evicted vault {} not reloaded: {}", vault_name, err);
+            GruPHstError::VaultEvicted(vault_name.to_string())
+        })
+    }
+
+    /// Moves the vaults loaded by a read into the Graphs
+    pub(crate) fn settle_loaded_vaults(&mut self) {
+        let loaded: Vec<String> = self
+            .memory
+            .evicted
+            .iter()
+            .filter(|(_, disk)| disk.loaded.get().is_some())
+            .map(|(vault_name, _)| vault_name.clone())
+            .collect();
+        for vault_name in loaded {
+            if let Err(err) = self.reload_vault(&vault_name) {
+                error!("Loaded vault {} not settled: {}", vault_name, err);
+            }
+        }
+    }
+
+    /// Retrieves a vault in memory, loading it when on disk
+    /// and kept apart until the next write
+    pub(crate) fn vault_ref(&self, vault_name: &str) -> Result<Option<VaultRef<'_>>, GruPHstError> {
+        if let Some(edges) = self.vault.get(vault_name) {
+            return Ok(self.in_memory(vault_name, edges));
+        }
+        let Some(disk) = self.memory.evicted.get(vault_name) else {
+            return Ok(None);
+        };
+        if disk.loaded.get().is_none() {
+            let loaded = self
+                .read_vault(vault_name, &disk.file.path)
+                .map_err(|err| {
+                    error!("Vault {} not loaded: {}", vault_name, err);
+                    GruPHstError::VaultEvicted(vault_name.to_string())
+                })?;
+            info!("Vault {} loaded on read", vault_name);
+            // another thread could have loaded it meanwhile
+            let _ = disk.loaded.set(loaded);
+        }
+        Ok(disk.loaded.get().map(|loaded| VaultRef {
+            edges: &loaded.edges,
+            vertices: &loaded.vertices,
+            adjacency: &loaded.adjacency,
+            indexes: &loaded.indexes,
+            schema: loaded.schema.as_ref(),
+        }))
+    }
+
+    /// Retrieves every vault in memory, also the ones loaded by a read
+    pub(crate) fn vaults_in_memory(&self) -> impl Iterator<Item = (&String, VaultRef<'_>)> {
+        let loaded = self.memory.evicted.iter().filter_map(|(vault_name, disk)| {
+            disk.loaded.get().map(|loaded| {
+                let vault = VaultRef {
+                    edges: &loaded.edges,
+                    vertices: &loaded.vertices,
+                    adjacency: &loaded.adjacency,
+                    indexes: &loaded.indexes,
+                    schema: loaded.schema.as_ref(),
+                };
+                (vault_name, vault)
+            })
+        });
+        self.vault
+            .iter()
+            .filter_map(|(vault_name, edges)| {
+                self.in_memory(vault_name, edges)
+                    .map(|vault| (vault_name, vault))
+            })
+            .chain(loaded)
+    }
+
+    /// Retrieves every vault of the Graphs, loading the ones on disk
+    /// not loaded yet, kept apart until the next write
+    pub(crate) fn vaults_in_graphs(
+        &self,
+    ) -> impl Iterator<Item = Result<(&String, VaultRef<'_>), GruPHstError>> {
+        self.vault
+            .keys()
+            .chain(self.memory.evicted.keys())
+            .filter_map(|vault_name| {
+                let vault = self.vault_ref(vault_name).transpose()?;
+                Some(vault.map(|vault| (vault_name, vault)))
+            })
+    }
+
+    fn in_memory<'a>(&'a self, vault_name: &str, edges: &'a Vec<Edge>) -> Option<VaultRef<'a>> {
+        Some(VaultRef {
+            edges,
+            vertices: self.vertices.get(vault_name)?,
+            adjacency: self.adjacency.get(vault_name)?,
+            indexes: self.indexes.get(vault_name)?,
+            schema: self.schemas.get(vault_name),
+        })
+    }
+
+    /// Reads a vault from its file, linking the vertices shared
+    /// with other vaults to the ones in memory, and indexing them
+    fn read_vault(&self, vault_name: &str, path: &Path) -> Result<LoadedVault, Box<dyn Error>> {
+        let mut decoded = Graphs::decode(&fs::read(path)?, self.key.as_ref())?;
+        let mut edges = decoded.vault.remove(vault_name).unwrap_or_default();
+        let mut vertices = decoded.vertices.remove(vault_name).unwrap_or_default();
+        for (id, vertex) in vertices.iter_mut() {
+            let shared = self
+                .vaults_in_memory()
+                .find_map(|(_, vault)| vault.vertices.get(id).cloned());
+            if let Some(shared) = shared {
+                *vertex = shared;
+            }
+        }
+        for edge in edges.iter_mut() {
+            if let Some(from) = vertices.get(&edge.get_from_vertex().get_id()) {
+                edge.update_from(from);
+            }
+            if let Some(to) = vertices.get(&edge.get_to_vertex().get_id()) {
+                edge.update_to(to);
+            }
+        }
+        let defs = decoded
+            .indexes
+            .remove(vault_name)
+            .map(|indexes| indexes.defs())
+            .unwrap_or_default();
+        let indexes = VaultIndexes::build(&defs, &vertices, &edges);
+        let schema = decoded.schemas.remove(vault_name);
+        let size = size::vault(vault_name)
+            + vertices.values().map(size::vertex).sum::<usize>()
+            + edges.iter().map(size::edge).sum::<usize>()
+            + indexes.size()
+            + schema.as_ref().map_or(0, size::schema);
+        Ok(LoadedVault {
+            adjacency: Adjacency::build(&edges),
+            indexes,
+            schema,
+            edges,
+            vertices,
+            size,
+        })
+    }
+
+    /// Registers a vault not loaded yet, on a file of a store
+    pub(crate) fn track_stored_vault(&mut self, vault_name: &str, path: PathBuf) {
+        self.memory
+            .evicted
+            .insert(vault_name.to_string(), DiskVault::new(path, false));
+    }
+
+    /// File of a vault on disk
+    pub(crate) fn evicted_path(&self, vault_name: &str) -> Option<&Path> {
+        self.memory
+            .evicted
+            .get(vault_name)
+            .map(|disk| disk.file.path.as_path())
+    }
+
+    /// Names of every vault on disk, also the ones loaded by a read
+    pub(crate) fn vaults_on_disk(&self) -> Vec<String> {
+        self.memory.evicted.keys().cloned().collect()
+    }
+
+    /// Releases a vault loaded by a read
+    pub(crate) fn release_loaded(&mut self, vault_name: &str) {
+        if let Some(disk) = self.memory.evicted.get_mut(vault_name) {
+            disk.loaded.take();
+        }
+    }
+
+    /// Checks the memory before a write of some additional bytes on a vault,
+    /// applying the policy when it would exceed the limit
+    pub(crate) fn reserve_memory(
+        &mut self,
+        additional: usize,
+        vault_name: &str,
+    ) -> Result<(), GruPHstError> {
+        self.settle_loaded_vaults();
+        loop {
+            match graphs_memory_watcher(self, additional) {
+                Err(GruPHstError::MemoryLimitExceeded)
+                    if self.memory.policy == MemoryPolicy::Evict =>
+                {
+                    if !self.evict_least_recently_used(vault_name) {
+                        return Err(GruPHstError::MemoryLimitExceeded);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Registers an access to a vault
+    pub(crate) fn touch_vault(&self, vault_name: &str) {
+        if let Some(used) = self.memory.used.get(vault_name) {
+            let tick = self.memory.clock.fetch_add(1, Ordering::Relaxed) + 1;
+            used.store(tick, Ordering::Relaxed);
+        }
+    }
+
+    /// Starts tracking the usage and the size of a new vault,
+    /// replacing one on disk
+    pub(crate) fn track_vault(&mut self, vault_name: &str) {
+        self.memory.evicted.remove(vault_name);
+        self.memory
+            .used
+            .insert(vault_name.to_string(), AtomicU64::new(0));
+        let size = self.measure_vault(vault_name);
+        self.memory.sizes.insert(vault_name.to_string(), size);
+        self.touch_vault(vault_name);
+    }
+
+    /// Stops tracking a deleted vault, removing it from disk when evicted.
+    /// Returns true when it was on disk
+    pub(crate) fn untrack_vault(&mut self, vault_name: &str) -> bool {
+        self.memory.used.remove(vault_name);
+        self.memory.sizes.remove(vault_name);
+        self.memory.evicted.remove(vault_name).is_some()
+    }
+
+    /// Evicts the least recently used vault with edges or vertices,
+    /// other than the current one and the one to write on.
+    /// Returns false when there is none
+    fn evict_least_recently_used(&mut self, vault_name: &str) -> bool {
+        let candidate = self
+            .vault
+            .iter()
+            .filter(|(name, edges)| {
+                *name != vault_name
+                    && **name != self.label
+                    && (!edges.is_empty()
+                        || self.vertices.get(*name).is_some_and(|v| !v.is_empty()))
+            })
+            .map(|(name, _)| {
+                let used = self.memory.used.get(name);
+                (used.map_or(0, |used| used.load(Ordering::Relaxed)), name)
+            })
+            .min()
+            .map(|(_, name)| name.clone());
+        let Some(candidate) = candidate else {
+            warn!("No vault to evict for a write on {}", vault_name);
+            return false;
+        };
+        match self.evict_vault(&candidate) {
+            Ok(()) => true,
+            Err(err) => {
+                error!("Vault {} not evicted: {}", candidate, err);
+                false
+            }
+        }
+    }
+
+    /// Encodes a vault in memory alone, as a Graphs labeled with its name,
+    /// encrypted when the Graphs has a key
+    pub(crate) fn encode_vault(&self, vault_name: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut single = Graphs::init(vault_name);
+        single.set_auto_persist(Default::default(), None);
+        if let Some(edges) = self.vault.get(vault_name) {
+            single.vault.insert(vault_name.to_string(), edges.clone());
+        }
+        if let Some(vertices) = self.vertices.get(vault_name) {
+            single
+                .vertices
+                .insert(vault_name.to_string(), vertices.clone());
+        }
+        if let Some(indexes) = self.indexes.get(vault_name) {
+            single
+                .indexes
+                .insert(vault_name.to_string(), indexes.clone());
+        }
+        if let Some(schema) = self.schemas.get(vault_name) {
+            single
+                .schemas
+                .insert(vault_name.to_string(), schema.clone());
+        }
+        single.encode(get_compression(), self.key.as_ref())
+    }
+
+    /// Removes a vault from memory, it is on the provided file
+    pub(crate) fn unload_to(&mut self, vault_name: &str, path: PathBuf, temporary: bool) {
+        self.savepoint_vault(vault_name);
+        self.vault.remove(vault_name);
+        self.vertices.remove(vault_name);
+        self.adjacency.remove(vault_name);
+        self.indexes.remove(vault_name);
+        self.schemas.remove(vault_name);
+        self.memory.used.remove(vault_name);
+        self.memory.sizes.remove(vault_name);
+        self.memory
+            .evicted
+            .insert(vault_name.to_string(), DiskVault::new(path, temporary));
+    }
+
+    /// Writes a vault to a file, or to the store when opened on one,
+    /// and removes it from memory
+    fn evict_vault(&mut self, vault_name: &str) -> Result<(), Box<dyn Error>> {
+        if self.has_store() {
+            return self.unload_vault(vault_name);
+        }
+        let dir = match get_eviction_path() {
+            Some(path) => PathBuf::from(path),
+            None => std::env::temp_dir().join("gruphst"),
+        };
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.vault.grphst", uuid::Uuid::new_v4()));
+        fs::write(&path, self.encode_vault(vault_name)?)?;
+        info!("Vault {} evicted to {:?}", vault_name, path);
+        self.unload_to(vault_name, path, true);
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//! Merge of vertices and edges
+//!
+//! Retrieves a vertex or an edge already on a vault,
+//! or adds it when there is none, so repeated imports
+//! do not add the same vertex or edge twice.
+
+use log::debug;
+
+use crate::{
+    attributes::AttrValue, edge::Edge, errors::GruPHstError, graphs::Graphs, vertex::Vertex,
+};
+
+impl Graphs {
+    /// Retrieves the Vertex with the label and the values
+    /// on every attribute of the key, or adds a new one with them,
+    /// for some provided vault_name or default when None.
+    /// The vertex retrieved is shared with the vault, so its changes
+    /// are seen on it; integers and floats are compared as numbers
+    /// and the vertices are looked up on the index of the first attribute
+    /// of the key when there is one
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{attributes::AttrValue, graphs::Graphs};
+    ///
+    /// let mut graphs = Graphs::init("shire");
+    /// let key = [("name", AttrValue::from("Frodo"))];
+    /// let frodo = graphs.merge_vertex("Hobbit", &key, None).unwrap();
+    /// let again = graphs.merge_vertex("Hobbit", &key, None).unwrap();
+    /// assert_eq!(frodo.get_id(), again.get_id());
+    /// assert_eq!(graphs.get_vertices(None).unwrap().len(), 1);
+    /// ```
+    pub fn merge_vertex(
+        &mut self,
+        label: &str,
+        key_attrs: &[(&str, AttrValue)],
+        vault_name: Option<&str>,
+    ) -> Result<Vertex, GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        if let Some(vault) = self.vault_ref(&current_vault)? {
+            let candidates = match key_attrs.first() {
+                Some((attr_k, attr_v)) => vault.vertex_candidates(attr_k, |i| i.equal(attr_v)),
+                None => vault.vertices.values().collect(),
+            };
+            let found = candidates
+                .into_iter()
+                .filter(|vertex| {
+                    vertex.get_label() == label
+                        && key_attrs
+                            .iter()
+                            .all(|(attr_k, attr_v)| vertex.has_attr_equal(attr_k, attr_v))
+                })
+                .min_by_key(|vertex| vertex.get_id());
+            if let Some(vertex) = found {
+                debug!("Merged with vertex [{}]", vertex.get_id());
+                return Ok(vertex.clone());
+            }
+        }
+        let mut vertex = Vertex::new(label);
+        for (attr_k, attr_v) in key_attrs {
+            vertex.set_attr(attr_k, attr_v.clone());
+        }
+        self.add_vertex(&vertex, Some(&current_vault))?;
+        Ok(vertex)
+    }
+
+    /// Retrieves the Edge with the relation between the vertices,
+    /// by their ids, or adds a new one,
+    /// for some provided vault_name or default when None
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("shire");
+    /// let frodo = Vertex::new("Frodo");
+    /// let sam = Vertex::new("Sam");
+    /// let friends = graphs.merge_edge(&frodo, "friend of", &sam, None).unwrap();
+    /// let again = graphs.merge_edge(&frodo, "friend of", &sam, None).unwrap();
+    /// assert_eq!(friends.get_id(), again.get_id());
+    /// assert_eq!(graphs.len(), 1);
+    /// ```
+    pub fn merge_edge(
+        &mut self,
+        from: &Vertex,
+        relation: &str,
+        to: &Vertex,
+        vault_name: Option<&str>,
+    ) -> Result<Edge, GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        if let Some(vault) = self.vault_ref(&current_vault)? {
+            let to_id = to.get_id();
+            let found = vault
+                .adjacency
+                .out_positions(&from.get_id(), Some(relation))
+                .into_iter()
+                .filter_map(|position| vault.edges.get(position))
+                .find(|edge| edge.get_to_vertex().get_id() == to_id);
+            if let Some(edge) = found {
+                debug!("Merged with edge [{}]", edge.get_id());
+                return Ok(edge.clone());
+            }
+        }
+        let edge = Edge::create(from, relation, to);
+        self.add_edge(&edge, Some(&current_vault))?;
+        Ok(edge)
+    }
+}
@@ -1,27 +1,81 @@
 //! Graphs modules
 
 use log::{error, warn};
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
-    edge::Edge, errors::GruPHstError, graphs_stats::GraphsStats, util::graphs_memory_watcher,
-    vertex::Vertex,
+    attributes::AttrValue, edge::Edge, errors::GruPHstError, graphs_stats::GraphsStats,
+    util::serialized_size, vertex::Vertex,
 };
+use adjacency::Adjacency;
+use auto_persist::AutoPersistSlot;
+use index::VaultIndexes;
+use memory::{MemorySlot, VaultRef};
+use snapshot::size;
+use store::StoreSlot;
+use transaction::TransactionSlot;
+use wal::{WalRecord, WalSlot};
 
+mod adjacency;
+mod auto_persist;
+pub mod cypher;
+mod delete;
+pub mod filter;
+mod index;
+mod memory;
+mod merge;
+pub mod paths;
 mod persistence;
 mod queries;
+mod schema;
+#[cfg(feature = "sync")]
+mod shared;
+mod snapshot;
 mod stats;
+mod store;
+mod transaction;
+pub mod traversal;
+mod wal;
+
+pub use auto_persist::AutoPersist;
+pub use delete::DeleteMode;
+pub use index::{IndexKind, IndexTarget};
+pub use memory::MemoryPolicy;
+pub use schema::Schema;
+#[cfg(feature = "sync")]
+pub use shared::SharedGraphs;
+pub use snapshot::{Compression, EncryptionKey};
 
 /// A colection of Graph
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct Graphs {
     /// The container of Edges
     vault: HashMap<String, Vec<Edge>>,
+    /// The container of Vertices for every vault, by vertex id
+    vertices: HashMap<String, HashMap<String, Vertex>>,
+    /// Outgoing and incoming adjacency index for every vault
+    adjacency: HashMap<String, Adjacency>,
+    /// Secondary indexes on attributes for every vault
+    indexes: HashMap<String, VaultIndexes>,
+    /// Schemas checked on the additions for some vaults
+    schemas: HashMap<String, Schema>,
     /// Name for the current vault
     label: String,
     /// Some attributes to handle metada for Graphs
     stats: GraphsStats,
+    /// Write-ahead log, when opened on a directory
+    wal: WalSlot,
+    /// Policy to save the Graphs after its mutations
+    persist: AutoPersistSlot,
+    /// Policy when the memory limit is reached and usage of the vaults
+    memory: MemorySlot,
+    /// Store with a file per vault, when opened or saved on one
+    store: StoreSlot,
+    /// Vaults changed by the running transactions
+    transactions: TransactionSlot,
+    /// Key the Graphs was saved or loaded encrypted with,
+    /// its auto-persisted and evicted files are encrypted too
+    key: Option<EncryptionKey>,
 }
 
 impl Graphs {
@@ -33,13 +87,24 @@ impl Graphs {
     /// Graphs::init("my graph");
     /// ```
     pub fn init(label: &str) -> Self {
-        let mut vault: HashMap<String, Vec<Edge>> = HashMap::new();
-        vault.insert(String::from(label), vec![]);
-        Graphs {
+        let mut graphs = Graphs {
             label: String::from(label),
-            vault,
+            vault: HashMap::new(),
+            vertices: HashMap::new(),
+            adjacency: HashMap::new(),
+            indexes: HashMap::new(),
+            schemas: HashMap::new(),
             stats: GraphsStats::init(),
-        }
+            wal: WalSlot::default(),
+            persist: AutoPersistSlot::from_config(),
+            memory: MemorySlot::from_config(),
+            store: StoreSlot::default(),
+            transactions: Default::default(),
+            key: None,
+        };
+        graphs.create_vault(label);
+        graphs.measure_mem();
+        graphs
     }
 
     /// Initializes a new Graphs element adding a Edge to new vault
@@ -55,30 +120,34 @@ impl Graphs {
     /// ```
     pub fn init_with(label: &str, vertex: &Edge) -> Self {
         let mut graphs = Graphs::init(label);
-        graphs.add_edge(vertex, None);
+        if let Err(err) = graphs.add_edge(vertex, None) {
+            error!("Edge not added to the new Graphs {}: {}", label, err);
+        }
         graphs
     }
 
-    /// Creates a new entry on Graphs valut
+    /// Creates a new entry on Graphs valut,
+    /// fails with MemoryLimitExceeded when the memory policy rejects it
     /// # Examples
     /// ```rust
     /// use gruphst::graphs::Graphs;
     ///  
     /// let mut graphs = Graphs::init("my graphs");
-    /// graphs.insert("my other graphs");
+    /// graphs.insert("my other graphs").unwrap();
     /// ```
-    pub fn insert(&mut self, name: &str) {
-        self.vault.insert(String::from(name), vec![]);
-        self.label = String::from(name);
-        graphs_memory_watcher(self);
+    pub fn insert(&mut self, name: &str) -> Result<(), GruPHstError> {
+        self.reserve_memory(size::vault(name), name)?;
+        self.log_mutation(WalRecord::Insert(name.to_string()))?;
+        self.create_vault(name);
+        self.relabel(name);
+        self.mutated();
+        Ok(())
     }
 
     /// Creates a new entry on Graphs valut with a Graph
-    pub fn insert_with(&mut self, name: &str, edge: &Edge) {
-        self.vault.insert(String::from(name), vec![]);
-        self.label = String::from(name);
-        self.add_edge(edge, Some(name));
-        graphs_memory_watcher(self);
+    pub fn insert_with(&mut self, name: &str, edge: &Edge) -> Result<(), GruPHstError> {
+        self.insert(name)?;
+        self.add_edge(edge, Some(name))
     }
 
     /// Returns the label or name for the graphs
@@ -86,9 +155,15 @@ impl Graphs {
         self.label.clone()
     }
 
-    /// Sets the label or name for the graphs
+    /// Sets the label or name for the graphs,
+    /// kept when it could not be written on the write-ahead log
     pub fn set_label(&mut self, label: &str) {
-        self.label = label.to_string()
+        if let Err(err) = self.log_mutation(WalRecord::SetLabel(label.to_string())) {
+            error!("Label of Graphs {} not changed: {}", self.label, err);
+            return;
+        }
+        self.relabel(label);
+        self.mutated();
     }
 
     /// Returns the stats for a grpahs
@@ -104,7 +179,10 @@ impl Graphs {
     }
 
     pub fn get_vaults(&self) -> Result<HashMap<String, Vec<Edge>>, GruPHstError> {
-        let vaults = self.vault.clone();
+        let vaults: HashMap<String, Vec<Edge>> = self
+            .vaults_in_graphs()
+            .map(|vault| vault.map(|(vault_name, vault)| (vault_name.clone(), vault.edges.clone())))
+            .collect::<Result<_, _>>()?;
         if vaults.values().len() == 1 {
             for val in vaults.values() {
                 if val.is_empty() {
@@ -123,16 +201,31 @@ impl Graphs {
     /// at vault.
     /// If None name is provided, the current one
     /// is use for the addition.
-    pub fn add_edge(&mut self, edge: &Edge, vault_name: Option<&str>) {
+    /// Fails with MemoryLimitExceeded when the memory policy rejects it
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let edge = Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam"));
+    /// graphs.add_edge(&edge, None).unwrap();
+    /// assert_eq!(graphs.len(), 1);
+    /// ```
+    pub fn add_edge(&mut self, edge: &Edge, vault_name: Option<&str>) -> Result<(), GruPHstError> {
         let current_vault = self.select_vault_label(vault_name);
-        if let Some(e) = self.vault.get_mut(&current_vault) {
-            e.push(edge.clone());
-        } else {
-            self.insert(&current_vault);
-            let v = self.vault.get_mut(&current_vault).unwrap();
-            v.push(edge.clone());
+        self.reload_evicted(&current_vault)?;
+        self.check_schema_edges(&current_vault, std::slice::from_ref(edge))?;
+        self.reserve_memory(size::edge_with_vertices(edge), &current_vault)?;
+        if !self.vault.contains_key(&current_vault) {
+            self.insert(&current_vault)?;
         }
-        graphs_memory_watcher(self);
+        self.log_mutation(WalRecord::AddEdges(
+            current_vault.clone(),
+            vec![edge.clone()],
+        ))?;
+        self.push_edge(edge.clone(), &current_vault);
+        self.mutated();
+        Ok(())
     }
 
     /// Adds a collection of Edges to the Graphs' vault
@@ -141,53 +234,246 @@ impl Graphs {
     /// at vault.
     /// If None name is provided, the current one
     /// is use for the addition.
-    pub fn add_edges(&mut self, edges: &mut Vec<Edge>, vault_name: Option<&str>) {
+    /// Fails with MemoryLimitExceeded, without adding any Edge,
+    /// when the memory policy rejects it
+    pub fn add_edges(
+        &mut self,
+        edges: &mut Vec<Edge>,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
         let current_vault = self.select_vault_label(vault_name);
-        if let Some(e) = self.vault.get_mut(&current_vault) {
-            e.append(edges);
-        } else {
-            self.insert(&current_vault);
-            let v = self.vault.get_mut(&current_vault).unwrap();
-            v.append(edges);
+        self.reload_evicted(&current_vault)?;
+        self.check_schema_edges(&current_vault, edges)?;
+        let additional = edges.iter().map(size::edge_with_vertices).sum();
+        self.reserve_memory(additional, &current_vault)?;
+        if !self.vault.contains_key(&current_vault) {
+            self.insert(&current_vault)?;
+        }
+        self.log_mutation(WalRecord::AddEdges(current_vault.clone(), edges.clone()))?;
+        for edge in edges.drain(..) {
+            self.push_edge(edge, &current_vault);
+        }
+        self.mutated();
+        Ok(())
+    }
+
+    /// Adds a Vertex to the Graphs' vault
+    /// for the provided graphs vault name,
+    /// the vertex does not need to be related by any Edge.
+    /// If the vault does not exists it creates a new entry
+    /// at vault.
+    /// If None name is provided, the current one
+    /// is use for the addition.
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// graphs.add_vertex(&Vertex::new("Tom Bombadil"), None).unwrap();
+    /// assert_eq!(graphs.get_vertices(None).unwrap().len(), 1);
+    /// ```
+    pub fn add_vertex(
+        &mut self,
+        vertex: &Vertex,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        self.check_schema_vertex(&current_vault, vertex)?;
+        self.reserve_memory(size::vertex(vertex), &current_vault)?;
+        if !self.vault.contains_key(&current_vault) {
+            self.insert(&current_vault)?;
+        }
+        self.log_mutation(WalRecord::AddVertex(current_vault.clone(), vertex.clone()))?;
+        let replaced = self
+            .vertices
+            .get_mut(&current_vault)
+            .unwrap()
+            .insert(vertex.get_id(), vertex.clone());
+        let indexes = self.indexes.entry(current_vault.clone()).or_default();
+        if let Some(replaced) = replaced.as_ref() {
+            indexes.remove_vertex(replaced);
+        }
+        indexes.add_vertex(vertex);
+        let replaced = replaced.as_ref().map_or(0, size::vertex);
+        self.account(
+            &current_vault,
+            size::vertex(vertex) as isize - replaced as isize,
+        );
+        self.mutated();
+        Ok(())
+    }
+
+    /// Removes the Vertex that matches with the provided id
+    /// from the vault, only if there is no Edge on the vault
+    /// related with the Vertex; see delete_vertex to delete it
+    /// along with its edges
+    pub fn remove_vertex(
+        &mut self,
+        id: &str,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        let Some(edges) = self.vault.get(&current_vault) else {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        };
+        if edges.iter().any(|edge| {
+            edge.get_from_vertex().get_id() == id || edge.get_to_vertex().get_id() == id
+        }) {
+            warn!(
+                "Vertex [{}] to remove has edges on vault {}",
+                id, current_vault
+            );
+            return Err(GruPHstError::VertexHasEdges(id.to_string()));
+        }
+        if !self.vertices[&current_vault].contains_key(id) {
+            error!("Vertex [{}] to remove not found", id);
+            return Err(GruPHstError::VertexNotFound);
+        }
+        self.log_mutation(WalRecord::RemoveVertex(
+            current_vault.clone(),
+            id.to_string(),
+        ))?;
+        let vertex = self
+            .vertices
+            .get_mut(&current_vault)
+            .unwrap()
+            .remove(id)
+            .unwrap();
+        if let Some(indexes) = self.indexes.get_mut(&current_vault) {
+            indexes.remove_vertex(&vertex);
+        }
+        self.account(&current_vault, -(size::vertex(&vertex) as isize));
+        self.mutated();
+        Ok(())
+    }
+
+    /// Sets an attribute of the Vertex that matches with the provided id
+    /// on the vault, the change is seen from every Edge of the Vertex,
+    /// checked against the schema of the vault
+    /// and logged when the Graphs was opened on a directory
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("The Shire")), None).unwrap();
+    /// graphs.set_vertex_attr(&frodo.get_id(), "years old", 50, None).unwrap();
+    /// let edges = graphs.get_edges(None).unwrap();
+    /// assert_eq!(edges[0].get_from_vertex().get_attr_i64("years old").unwrap(), 50);
+    /// ```
+    pub fn set_vertex_attr<T>(
+        &mut self,
+        id: &str,
+        attr_k: &str,
+        attr_v: T,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError>
+    where
+        T: Into<AttrValue>,
+    {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        let mut vertex = self.find_vertex_by_id(id, vault_name)?;
+        let attr_v: AttrValue = attr_v.into();
+        let mut changed = vertex.detached();
+        changed.set_attr(attr_k, attr_v.clone());
+        self.check_schema_vertex(&current_vault, &changed)?;
+        self.reserve_memory(attr_k.len() + serialized_size(&attr_v), &current_vault)?;
+        self.log_mutation(WalRecord::SetVertexAttr(
+            current_vault.clone(),
+            id.to_string(),
+            attr_k.to_string(),
+            attr_v.clone(),
+        ))?;
+        let before = size::vertex(&vertex);
+        vertex.set_attr(attr_k, attr_v);
+        self.account(
+            &current_vault,
+            size::vertex(&vertex) as isize - before as isize,
+        );
+        self.mutated();
+        Ok(())
+    }
+
+    /// Deletes an attribute of the Vertex that matches with the provided id
+    /// on the vault, checked against the schema of the vault
+    /// and logged when the Graphs was opened on a directory
+    pub fn delete_vertex_attr(
+        &mut self,
+        id: &str,
+        attr_k: &str,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        let mut vertex = self.find_vertex_by_id(id, vault_name)?;
+        if vertex.attr_value(attr_k).is_none() {
+            warn!("attribute {} not found for remove", attr_k);
+            return Err(GruPHstError::AttributeNotFound);
         }
-        graphs_memory_watcher(self);
+        let mut changed = vertex.detached();
+        changed.del_attr(attr_k)?;
+        self.check_schema_vertex(&current_vault, &changed)?;
+        self.log_mutation(WalRecord::DeleteVertexAttr(
+            current_vault.clone(),
+            id.to_string(),
+            attr_k.to_string(),
+        ))?;
+        let before = size::vertex(&vertex);
+        vertex.del_attr(attr_k)?;
+        self.account(
+            &current_vault,
+            size::vertex(&vertex) as isize - before as isize,
+        );
+        self.mutated();
+        Ok(())
+    }
+
+    /// Retrieves the collection of vertices on a vault,
+    /// with or without relations
+    /// the default one or by name
+    pub fn get_vertices(&self, vault_name: Option<&str>) -> Result<Vec<Vertex>, GruPHstError> {
+        let vertices = self.select_vault_vertices(vault_name)?;
+        Ok(vertices.values().cloned().collect())
     }
 
     /// Retrieves the collection of edges
     /// the default one or by name
     pub fn get_edges(&self, vault_name: Option<&str>) -> Result<Vec<Edge>, GruPHstError> {
-        Ok(self.select_vault(vault_name)?)
+        self.select_vault(vault_name)
     }
 
     /// Returns a collection with the unique vertices on a vault
     pub fn get_uniq_vertices(&self, vault_name: Option<&str>) -> Result<Vec<Vertex>, GruPHstError> {
-        let edges = self.select_vault(vault_name)?;
-        let mut vertices_map: HashMap<String, Vertex> = HashMap::new();
-        for edge in edges {
-            vertices_map.insert(edge.get_from_vertex().get_id(), edge.get_from_vertex());
-            vertices_map.insert(edge.get_to_vertex().get_id(), edge.get_to_vertex());
+        let vertices = self.select_vault_vertices(vault_name)?;
+        match vertices.is_empty() {
+            false => Ok(vertices.values().cloned().collect()),
+            true => Err(GruPHstError::VaultEmpty),
         }
-        let uniq_vertices: Vec<Vertex> = vertices_map.into_values().collect();
-        Ok(uniq_vertices)
     }
 
     /// Returns a collection with the unique vertices from all vaults
     pub fn get_uniq_vertices_on_graphs(&self) -> Result<Vec<Vertex>, GruPHstError> {
-        let vaults = self.get_vaults()?;
         let mut vertices_map: HashMap<String, Vertex> = HashMap::new();
-        for (_, edges) in vaults {
-            for edge in edges {
-                vertices_map.insert(edge.get_from_vertex().get_id(), edge.get_from_vertex());
-                vertices_map.insert(edge.get_to_vertex().get_id(), edge.get_to_vertex());
+        for vault in self.vaults_in_graphs() {
+            let (_vault_name, vault) = vault?;
+            for (id, vertex) in vault.vertices.iter() {
+                vertices_map.insert(id.clone(), vertex.clone());
             }
         }
+        if vertices_map.is_empty() {
+            return Err(GruPHstError::NoVaultOnGraphs);
+        }
         let uniq_vertices: Vec<Vertex> = vertices_map.into_values().collect();
         Ok(uniq_vertices)
     }
 
     /// Updates the name of the Graphs
     pub fn update_label(&mut self, label: &str) {
-        self.label = label.to_string();
+        self.set_label(label);
     }
 
     /// Deletes the Edge that matches with the provided id
@@ -196,10 +482,13 @@ impl Graphs {
         id: String,
         vault_name: Option<&str>,
     ) -> Result<(), GruPHstError> {
+        self.reload_evicted(&self.select_vault_label(vault_name))?;
         let edges = self.select_vault_mut(vault_name)?;
         if let Some(index) = edges.iter().position(|edge| edge.get_id() == id) {
-            edges.remove(index);
-            graphs_memory_watcher(self);
+            let current_vault = self.select_vault_label(vault_name);
+            self.log_mutation(WalRecord::DeleteEdge(current_vault.clone(), id))?;
+            self.remove_edge_at(index, &current_vault);
+            self.mutated();
             Ok(())
         } else {
             error!("Edge [{}] to delete not found", id);
@@ -213,15 +502,22 @@ impl Graphs {
         edge_to_update: &Edge,
         vault_name: Option<&str>,
     ) -> Result<(), GruPHstError> {
+        self.reload_evicted(&self.select_vault_label(vault_name))?;
         let edges: &mut Vec<Edge> = self.select_vault_mut(vault_name)?;
         let index = edges
             .iter()
             .position(|vertex| vertex.get_id() == edge_to_update.get_id());
-        if index.is_some() {
-            let i = index.unwrap();
-            edges.remove(i);
-            edges.push(edge_to_update.clone());
-            graphs_memory_watcher(self);
+        if let Some(i) = index {
+            let current_vault = self.select_vault_label(vault_name);
+            self.check_schema_edges(&current_vault, std::slice::from_ref(edge_to_update))?;
+            self.reserve_memory(size::edge_with_vertices(edge_to_update), &current_vault)?;
+            self.log_mutation(WalRecord::UpdateEdge(
+                current_vault.clone(),
+                edge_to_update.clone(),
+            ))?;
+            self.remove_edge_at(i, &current_vault);
+            self.push_edge(edge_to_update.clone(), &current_vault);
+            self.mutated();
             Ok(())
         } else {
             #[rustfmt::skip]
@@ -230,16 +526,34 @@ impl Graphs {
         }
     }
 
+    /// Registers a mutation, already logged and applied,
+    /// for the compaction of the write-ahead log and the auto-persistence;
+    /// the vertices changed out of the Graphs are indexed again
+    pub(crate) fn mutated(&mut self) {
+        for (vault_name, indexes) in self.indexes.iter_mut() {
+            if let Some(vertices) = self.vertices.get(vault_name) {
+                indexes.refresh(vertices);
+            }
+        }
+        self.compact_if_due();
+        self.persist_mutation();
+    }
+
     /// Retrieves the current vault or returns the current one
     fn select_vault_label(&self, vault_label: Option<&str>) -> String {
         let mut current_vault = self.label.clone();
         if let Some(vlt) = vault_label {
             current_vault = vlt.to_string();
         }
+        self.touch_vault(&current_vault);
         current_vault.to_string()
     }
 
-    fn select_vault_not_exists_error(vault: String) -> GruPHstError {
+    fn select_vault_not_exists_error(&self, vault: String) -> GruPHstError {
+        if self.is_evicted(&vault) {
+            warn!("Vault {} is evicted to disk", vault);
+            return GruPHstError::VaultEvicted(vault);
+        }
         warn!("Vault {} does not exists", vault);
         GruPHstError::VaultNotExists(vault)
     }
@@ -249,26 +563,115 @@ impl Graphs {
         vault_label: Option<&str>,
     ) -> Result<&mut Vec<Edge>, GruPHstError> {
         let vault = self.select_vault_label(vault_label);
-        if let Some(edges) = self.vault.get_mut(&vault) {
-            match edges.is_empty() {
-                false => Ok(edges),
-                true => Err(GruPHstError::VaultEmpty),
-            }
-        } else {
-            Err(Graphs::select_vault_not_exists_error(vault))
+        self.reload_evicted(&vault)?;
+        if !self.vault.contains_key(&vault) {
+            return Err(self.select_vault_not_exists_error(vault));
+        }
+        let edges = self.vault.get_mut(&vault).unwrap();
+        match edges.is_empty() {
+            false => Ok(edges),
+            true => Err(GruPHstError::VaultEmpty),
         }
     }
 
-    fn select_vault(&self, vault_label: Option<&str>) -> Result<Vec<Edge>, GruPHstError> {
+    /// Retrieves the vault, loading it on the first access
+    /// when it is on disk
+    fn select_vault_ref(&self, vault_label: Option<&str>) -> Result<&Vec<Edge>, GruPHstError> {
+        Ok(self.select_vault_with_edges(vault_label)?.edges)
+    }
+
+    /// Retrieves a vault with some Edge
+    fn select_vault_with_edges(
+        &self,
+        vault_label: Option<&str>,
+    ) -> Result<VaultRef<'_>, GruPHstError> {
         let vault = self.select_vault_label(vault_label);
-        if let Some(edges) = self.vault.get(&vault) {
-            match edges.is_empty() {
-                false => Ok(edges.clone()),
+        if let Some(selected) = self.vault_ref(&vault)? {
+            match selected.edges.is_empty() {
+                false => Ok(selected),
                 true => Err(GruPHstError::VaultEmpty),
             }
         } else {
-            Err(Graphs::select_vault_not_exists_error(vault))
+            Err(self.select_vault_not_exists_error(vault))
+        }
+    }
+
+    fn select_vault_vertices(
+        &self,
+        vault_label: Option<&str>,
+    ) -> Result<&HashMap<String, Vertex>, GruPHstError> {
+        let vault = self.select_vault_label(vault_label);
+        match self.vault_ref(&vault)? {
+            Some(selected) => Ok(selected.vertices),
+            None => Err(self.select_vault_not_exists_error(vault)),
+        }
+    }
+
+    /// Creates the containers for a new vault,
+    /// an existing vault with the same name is replaced
+    fn create_vault(&mut self, name: &str) {
+        self.vault.insert(String::from(name), vec![]);
+        self.vertices.insert(String::from(name), HashMap::new());
+        self.adjacency
+            .insert(String::from(name), Adjacency::default());
+        self.indexes
+            .insert(String::from(name), VaultIndexes::default());
+        self.track_vault(name);
+    }
+
+    /// Adds an Edge at the end of an existing vault,
+    /// registering its vertices and indexing them
+    fn push_edge(&mut self, mut edge: Edge, vault_label: &str) {
+        let registered = self.register_edge_vertices(&mut edge, vault_label);
+        self.account(vault_label, (registered + size::edge(&edge)) as isize);
+        let edges = self.vault.get_mut(vault_label).unwrap();
+        let adjacency = self.adjacency.entry(vault_label.to_string()).or_default();
+        adjacency.add(&edge, edges.len());
+        let indexes = self.indexes.entry(vault_label.to_string()).or_default();
+        indexes.add_edge(&edge, edges.len());
+        edges.push(edge);
+    }
+
+    /// Removes the Edge at the provided position of an existing vault
+    fn remove_edge_at(&mut self, index: usize, vault_label: &str) -> Edge {
+        let edge = self.vault.get_mut(vault_label).unwrap().remove(index);
+        if let Some(adjacency) = self.adjacency.get_mut(vault_label) {
+            adjacency.remove(&edge, index);
         }
+        if let Some(indexes) = self.indexes.get_mut(vault_label) {
+            indexes.remove_edge(index);
+        }
+        self.account(vault_label, -(size::edge(&edge) as isize));
+        edge
+    }
+
+    /// Registers the "from" and "to" vertices of an Edge on the vault,
+    /// if a vertex with the same id is already registered
+    /// the Edge is linked to the registered one.
+    /// Returns the estimated bytes of the newly registered vertices
+    fn register_edge_vertices(&mut self, edge: &mut Edge, vault_label: &str) -> usize {
+        let vertices = self.vertices.entry(vault_label.to_string()).or_default();
+        let indexes = self.indexes.entry(vault_label.to_string()).or_default();
+        let mut bytes = 0;
+        let from = edge.get_from_vertex();
+        let registered = vertices.entry(from.get_id()).or_insert_with(|| {
+            bytes += size::vertex(&from);
+            indexes.add_vertex(&from);
+            from.clone()
+        });
+        edge.update_from(registered);
+        let to = edge.get_to_vertex();
+        let registered = vertices.entry(to.get_id()).or_insert_with(|| {
+            bytes += size::vertex(&to);
+            indexes.add_vertex(&to);
+            to.clone()
+        });
+        edge.update_to(registered);
+        bytes
+    }
+
+    fn select_vault(&self, vault_label: Option<&str>) -> Result<Vec<Edge>, GruPHstError> {
+        self.select_vault_ref(vault_label).cloned()
     }
 
     /// Removes a graph from the vault
@@ -279,14 +682,23 @@ impl Graphs {
     ///
     /// let mut graphs = Graphs::init("graph-one");
     /// assert_eq!(graphs.len_graphs(), 1);
-    /// graphs.insert("graph-two");
+    /// graphs.insert("graph-two").unwrap();
     /// assert_eq!(graphs.len_graphs(), 2);
     /// graphs.delete_vault("graph-two").unwrap();
     /// ```
     pub fn delete_vault(&mut self, graph_name: &str) -> Result<(), GruPHstError> {
-        match self.vault.remove(graph_name) {
-            Some(_) => Ok(()),
-            None => Err(GruPHstError::VaultNotExists(graph_name.to_string())),
+        if !self.vault.contains_key(graph_name) && self.evicted_path(graph_name).is_none() {
+            return Err(GruPHstError::VaultNotExists(graph_name.to_string()));
         }
+        self.log_mutation(WalRecord::DeleteVault(graph_name.to_string()))?;
+        self.vault.remove(graph_name);
+        // an evicted vault is deleted from disk
+        self.untrack_vault(graph_name);
+        self.vertices.remove(graph_name);
+        self.adjacency.remove(graph_name);
+        self.indexes.remove(graph_name);
+        self.schemas.remove(graph_name);
+        self.mutated();
+        Ok(())
     }
 }
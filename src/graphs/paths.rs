@@ -0,0 +1,327 @@
+//! Shortest path between two vertices of a vault,
+//! unweighted, Dijkstra or A* over a numeric edge attribute
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use log::warn;
+
+use crate::{
+    attributes::AttrValue,
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{traversal::Direction, Graphs},
+    vertex::Vertex,
+};
+
+/// Estimate of the cost from a vertex to the target one for A*,
+/// that can be sent and shared between threads with or without
+/// the "sync" feature
+pub trait Heuristic: Fn(&Vertex, &Vertex) -> f64 + Send + Sync + 'static {}
+
+impl<H> Heuristic for H where H: Fn(&Vertex, &Vertex) -> f64 + Send + Sync + 'static {}
+
+/// Options to find the shortest path between two vertices
+///
+/// # Examples
+/// ```rust
+/// use gruphst::graphs::{paths::PathOptions, traversal::Direction};
+///
+/// let hops = PathOptions::unweighted();
+/// let distance = PathOptions::dijkstra("km").direction(Direction::Both);
+/// let guessed = PathOptions::astar("km", |_current, _target| 0.0);
+/// ```
+pub struct PathOptions {
+    /// edge attribute used as weight, None for unweighted paths
+    weight_attr: Option<String>,
+    heuristic: Option<Box<dyn Heuristic>>,
+    direction: Direction,
+    relations: Vec<String>,
+}
+
+impl PathOptions {
+    /// Options for a path with the fewest edges, found breadth-first,
+    /// every edge costs 1
+    pub fn unweighted() -> Self {
+        PathOptions {
+            weight_attr: None,
+            heuristic: None,
+            direction: Direction::Out,
+            relations: Vec::new(),
+        }
+    }
+
+    /// Options for a path with the lowest cost, found with Dijkstra,
+    /// the cost of every edge is the numeric value of the provided attribute
+    pub fn dijkstra(weight_attr: &str) -> Self {
+        PathOptions {
+            weight_attr: Some(weight_attr.to_string()),
+            ..PathOptions::unweighted()
+        }
+    }
+
+    /// Options for a path with the lowest cost, found with A*,
+    /// the cost of every edge is the numeric value of the provided attribute
+    /// and the heuristic estimates the cost from a vertex to the target one;
+    /// it must never overestimate it to get the lowest cost path
+    pub fn astar<H>(weight_attr: &str, heuristic: H) -> Self
+    where
+        H: Heuristic,
+    {
+        PathOptions {
+            heuristic: Some(Box::new(heuristic)),
+            ..PathOptions::dijkstra(weight_attr)
+        }
+    }
+
+    /// Sets the direction to follow the edges, Out by default
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Adds a relation to follow, when no relation is added
+    /// any relation is followed
+    pub fn relation(mut self, relation: &str) -> Self {
+        self.relations.push(relation.to_string());
+        self
+    }
+
+    fn weight(&self, edge: &Edge) -> Result<f64, GruPHstError> {
+        match &self.weight_attr {
+            None => Ok(1.0),
+            Some(weight_attr) => {
+                let value = edge.get_attr_value(weight_attr)?;
+                let weight = match &value {
+                    AttrValue::Str(v) => v.trim().parse::<f64>().ok(),
+                    v => v.as_f64(),
+                };
+                match weight {
+                    Some(weight) if weight.is_finite() && weight >= 0.0 => Ok(weight),
+                    _ => {
+                        warn!(
+                            "Edge {} has a not valid weight {}: {}",
+                            edge.get_id(),
+                            weight_attr,
+                            value
+                        );
+                        Err(GruPHstError::EdgeWeightNotValid(value.to_string()))
+                    }
+                }
+            }
+        }
+    }
+
+    fn estimate(&self, vertex: &Vertex, target: &Vertex) -> f64 {
+        match &self.heuristic {
+            Some(heuristic) => heuristic(vertex, target),
+            None => 0.0,
+        }
+    }
+}
+
+/// A path between two vertices, the ordered edges
+/// to follow and the total cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    edges: Vec<Edge>,
+    cost: f64,
+}
+
+impl Path {
+    /// Retrieves the ordered edges of the path
+    pub fn get_edges(&self) -> Vec<Edge> {
+        self.edges.clone()
+    }
+
+    /// Retrieves the total cost of the path
+    pub fn get_cost(&self) -> f64 {
+        self.cost
+    }
+}
+
+/// A vertex pending to be visited, ordered by the lowest priority
+struct Pending {
+    priority: f64,
+    cost: f64,
+    vertex: Vertex,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority)
+    }
+}
+
+impl Graphs {
+    /// Finds the shortest path between the vertices that matches
+    /// the provided ids, following the provided options.
+    /// For some provided vault_name or default when None
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    /// use gruphst::graphs::paths::PathOptions;
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let the_shire = Vertex::new("The Shire");
+    /// let bree = Vertex::new("Bree");
+    /// let rivendell = Vertex::new("Rivendell");
+    /// let mut edge = Edge::create(&the_shire, "road to", &bree);
+    /// edge.set_attr("km", 200);
+    /// graphs.add_edge(&edge, None).unwrap();
+    /// let mut edge = Edge::create(&bree, "road to", &rivendell);
+    /// edge.set_attr("km", 300);
+    /// graphs.add_edge(&edge, None).unwrap();
+    /// let mut edge = Edge::create(&the_shire, "river to", &rivendell);
+    /// edge.set_attr("km", 800);
+    /// graphs.add_edge(&edge, None).unwrap();
+    ///
+    /// let path = graphs.shortest_path(
+    ///     &the_shire.get_id(),
+    ///     &rivendell.get_id(),
+    ///     &PathOptions::dijkstra("km"),
+    ///     None).unwrap();
+    /// assert_eq!(path.get_cost(), 500.0);
+    /// assert_eq!(path.get_edges()[1].get_to_vertex().get_label(), "Rivendell");
+    ///
+    /// let path = graphs.shortest_path(
+    ///     &the_shire.get_id(),
+    ///     &rivendell.get_id(),
+    ///     &PathOptions::unweighted(),
+    ///     None).unwrap();
+    /// assert_eq!(path.get_cost(), 1.0);
+    /// ```
+    pub fn shortest_path(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        options: &PathOptions,
+        vault_name: Option<&str>,
+    ) -> Result<Path, GruPHstError> {
+        let vertices = self.select_vault_vertices(vault_name)?;
+        let (Some(from), Some(to)) = (vertices.get(from_id), vertices.get(to_id)) else {
+            warn!("Vertices {} or {} not found for a path", from_id, to_id);
+            return Err(GruPHstError::VertexNotFound);
+        };
+        let vaults = vec![self.select_vault_label(vault_name)];
+        // for every reached vertex the edge used to reach it
+        let reached = match options.weight_attr {
+            None => self.breadth_first_path(from, to, options, &vaults),
+            Some(_) => self.lowest_cost_path(from, to, options, &vaults)?,
+        };
+        let Some(reached) = reached else {
+            warn!("No path found from {} to {}", from_id, to_id);
+            return Err(GruPHstError::NoPathFound(
+                from_id.to_string(),
+                to_id.to_string(),
+            ));
+        };
+        let mut edges = Vec::new();
+        let mut cost = 0.0;
+        let mut current = to_id.to_string();
+        while current != from_id {
+            let edge = reached[&current].clone();
+            cost += options.weight(&edge)?;
+            current = Graphs::other_side(&edge, &current);
+            edges.push(edge);
+        }
+        edges.reverse();
+        Ok(Path { edges, cost })
+    }
+
+    fn breadth_first_path(
+        &self,
+        from: &Vertex,
+        to: &Vertex,
+        options: &PathOptions,
+        vaults: &[String],
+    ) -> Option<HashMap<String, Edge>> {
+        let mut reached: HashMap<String, Edge> = HashMap::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<String> = VecDeque::new();
+        visited.insert(from.get_id());
+        queue.push_back(from.get_id());
+        while let Some(id) = queue.pop_front() {
+            if id == to.get_id() {
+                return Some(reached);
+            }
+            for (edge, vertex) in self.adjacent(&id, options.direction, &options.relations, vaults)
+            {
+                if visited.insert(vertex.get_id()) {
+                    reached.insert(vertex.get_id(), edge);
+                    queue.push_back(vertex.get_id());
+                }
+            }
+        }
+        None
+    }
+
+    fn lowest_cost_path(
+        &self,
+        from: &Vertex,
+        to: &Vertex,
+        options: &PathOptions,
+        vaults: &[String],
+    ) -> Result<Option<HashMap<String, Edge>>, GruPHstError> {
+        let mut reached: HashMap<String, Edge> = HashMap::new();
+        let mut costs: HashMap<String, f64> = HashMap::new();
+        let mut pending: BinaryHeap<Pending> = BinaryHeap::new();
+        costs.insert(from.get_id(), 0.0);
+        pending.push(Pending {
+            priority: options.estimate(from, to),
+            cost: 0.0,
+            vertex: from.clone(),
+        });
+        while let Some(Pending { cost, vertex, .. }) = pending.pop() {
+            let id = vertex.get_id();
+            if id == to.get_id() {
+                return Ok(Some(reached));
+            }
+            // a cheaper way to this vertex has been already expanded
+            if cost > costs[&id] {
+                continue;
+            }
+            for (edge, next) in self.adjacent(&id, options.direction, &options.relations, vaults) {
+                let next_cost = cost + options.weight(&edge)?;
+                let next_id = next.get_id();
+                if costs.get(&next_id).is_some_and(|known| *known <= next_cost) {
+                    continue;
+                }
+                costs.insert(next_id.clone(), next_cost);
+                reached.insert(next_id, edge);
+                pending.push(Pending {
+                    priority: next_cost + options.estimate(&next, to),
+                    cost: next_cost,
+                    vertex: next,
+                });
+            }
+        }
+        Ok(None)
+    }
+
+    /// Retrieves the id of the vertex on the other side of the edge
+    fn other_side(edge: &Edge, id: &str) -> String {
+        let from_id = edge.get_from_vertex().get_id();
+        match from_id == id {
+            true => edge.get_to_vertex().get_id(),
+            false => from_id,
+        }
+    }
+}
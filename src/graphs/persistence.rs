@@ -1,14 +1,16 @@
 use log::info;
 use std::error::Error;
+use std::fs;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::io::Write;
+use std::path::Path;
 
-use crate::config::get_max_mem_usage;
+use crate::config::{get_compression, get_max_mem_usage};
 use crate::errors::GruPHstError;
-use crate::graphs::Graphs;
+use crate::graphs::{Compression, EncryptionKey, Graphs};
 
 // TODO: improve this; there is no need to deal with extension and add the possibility to use a custom filename
 
@@ -33,20 +35,18 @@ impl Graphs {
     #[deprecated(since = "0.15.0", note = "please, for good, use `save` method instead")]
     pub fn persists(&self) -> Result<(), Box<dyn Error>> {
         let file_name = format!("{}.grphst", self.get_label().replace(' ', "_"));
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(file_name.clone())?;
-        let bytes = bincode::serialize(self)?;
-        file.write_all(&bytes)?;
+        let bytes = self.encode(get_compression(), None)?;
+        write_atomically(&file_name, &bytes)?;
         #[rustfmt::skip]
         info!("Current Graphs persisted at {} file with {} bytes written", file_name, bytes.len());
         Ok(())
     }
 
     /// Saves the current Graphs into a file with the Graphs's name
-    /// or in the provided path and failename
+    /// or in the provided path and failename,
+    /// compressed with the GRUPHST_COMPRESSION codec.
+    /// The file is written to a temporary file that replaces the previous one
+    /// once complete, so a crash while saving keeps the previous file
     /// # Examples
     /// ```rust
     /// use gruphst::{edge::Edge, vertex::Vertex, graphs::Graphs};
@@ -62,26 +62,84 @@ impl Graphs {
     /// graphs.save(None);
     /// ```
     pub fn save(&self, file_path: Option<&str>) -> Result<(), Box<dyn Error>> {
+        self.save_compressed(file_path, get_compression())
+    }
+
+    /// Saves the current Graphs like `save`, compressed with the provided codec;
+    /// the codec is recorded on the file so `load` decompresses it
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, vertex::Vertex, graphs::{Compression, Graphs}};
+    ///
+    /// let edge = Edge::create(
+    ///     &Vertex::new("Sauron"),
+    ///     "created",
+    ///     &Vertex::new("One Ring"));
+    /// let mut graphs = Graphs::init_with("Middle-earth", &edge);
+    ///
+    /// graphs.save_compressed(None, Compression::None);
+    /// ```
+    pub fn save_compressed(
+        &self,
+        file_path: Option<&str>,
+        compression: Compression,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_snapshot(file_path, compression, None)
+    }
+
+    /// Saves the current Graphs like `save`, encrypted with ChaCha20-Poly1305
+    /// and the provided key, so only `load_encrypted` with the same key loads it.
+    /// The Graphs keeps the key for its auto-persisted and evicted files
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, vertex::Vertex, graphs::{EncryptionKey, Graphs}};
+    ///
+    /// let edge = Edge::create(
+    ///     &Vertex::new("Sauron"),
+    ///     "created",
+    ///     &Vertex::new("One Ring"));
+    /// let mut graphs = Graphs::init_with("Middle-earth", &edge);
+    ///
+    /// let key = EncryptionKey::from_passphrase("speak friend and enter");
+    /// graphs.save_encrypted(None, &key);
+    /// ```
+    pub fn save_encrypted(
+        &mut self,
+        file_path: Option<&str>,
+        key: &EncryptionKey,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_snapshot(file_path, get_compression(), Some(key))?;
+        self.key = Some(key.clone());
+        Ok(())
+    }
+
+    pub(crate) fn write_snapshot(
+        &self,
+        file_path: Option<&str>,
+        compression: Compression,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), Box<dyn Error>> {
         let file_name = match file_path {
             Some(fp) => format!("{}{}.grphst", fp, self.get_label().replace(' ', "_")),
             None => format!("{}.grphst", self.get_label().replace(' ', "_")),
         };
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(file_name.clone())?;
-        let bytes = bincode::serialize(self)?;
-        file.write_all(&bytes)?;
+        let bytes = self.encode(compression, key)?;
+        write_atomically(&file_name, &bytes)?;
         info!(
-            "Current Graphs persisted at {} file with {} bytes written",
+            "Current Graphs persisted at {} file with {} bytes written, compression {:?}, encrypted {}",
             file_name,
-            bytes.len()
+            bytes.len(),
+            compression,
+            key.is_some()
         );
         Ok(())
     }
 
-    /// Loads the persisted Graphs on a file
+    /// Loads the persisted Graphs on a file.
+    /// Fails with SnapshotBadMagic when the file is not a persisted Graphs,
+    /// SnapshotUnsupportedVersion when it was persisted with an unknown format,
+    /// SnapshotChecksumMismatch when it is corrupted
+    /// and SnapshotEncrypted when it was saved encrypted
     /// # Examples
     /// ```rust
     /// use gruphst::{edge::Edge, vertex::Vertex, graphs::Graphs};
@@ -96,6 +154,41 @@ impl Graphs {
     /// let loaded_graphs = Graphs::load("Middle-earth.grphst").unwrap();
     /// ```
     pub fn load(file_name: &str) -> Result<Graphs, Box<dyn Error>> {
+        Graphs::read_snapshot(file_name, None)
+    }
+
+    /// Loads the persisted Graphs on a file saved encrypted with the key,
+    /// failing with SnapshotWrongKey when it was encrypted with another one.
+    /// Files saved without encryption are loaded too.
+    /// The loaded Graphs keeps the key, like `save_encrypted`
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, errors::GruPHstError, vertex::Vertex, graphs::{EncryptionKey, Graphs}};
+    ///
+    /// let edge = Edge::create(
+    ///     &Vertex::new("Sauron"),
+    ///     "created",
+    ///     &Vertex::new("One Ring"));
+    /// let mut graphs = Graphs::init_with("Mordor", &edge);
+    /// let key = EncryptionKey::new([42; 32]);
+    /// graphs.save_encrypted(None, &key);
+    ///
+    /// let loaded_graphs = Graphs::load_encrypted("Mordor.grphst", &key).unwrap();
+    /// let err = Graphs::load_encrypted("Mordor.grphst", &EncryptionKey::new([7; 32]))
+    ///     .unwrap_err();
+    /// assert_eq!(err.downcast_ref(), Some(&GruPHstError::SnapshotWrongKey));
+    /// # std::fs::remove_file("Mordor.grphst").unwrap();
+    /// ```
+    pub fn load_encrypted(file_name: &str, key: &EncryptionKey) -> Result<Graphs, Box<dyn Error>> {
+        let mut graphs = Graphs::read_snapshot(file_name, Some(key))?;
+        graphs.key = Some(key.clone());
+        Ok(graphs)
+    }
+
+    fn read_snapshot(
+        file_name: &str,
+        key: Option<&EncryptionKey>,
+    ) -> Result<Graphs, Box<dyn Error>> {
         let read_file = File::open(file_name)?;
         let file_size: usize = read_file.metadata().unwrap().len().try_into().unwrap();
         let mut reader = BufReader::with_capacity(file_size, read_file);
@@ -105,7 +198,35 @@ impl Graphs {
         if reader.buffer().len() > max_mem {
             return Err(GruPHstError::PersistenceFile.into());
         }
-        let readed_graph: Graphs = bincode::deserialize(reader.buffer())?;
+        let readed_graph = Graphs::decode(reader.buffer(), key)?;
         Ok(readed_graph)
     }
 }
+
+/// Writes the bytes to a temporary file next to the target
+/// and renames it over the target once flushed to disk
+pub(crate) fn write_atomically(file_name: &str, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    let tmp = format!("{}.tmp", file_name);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    drop(file);
+    if let Err(err) = fs::rename(&tmp, file_name) {
+        let _ = fs::remove_file(&tmp);
+        return Err(err.into());
+    }
+    // the rename is durable once the directory is synced;
+    // not every platform allows to open a directory
+    let dir = match Path::new(file_name).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
@@ -1,18 +1,21 @@
 use log::warn;
+use std::ops::RangeBounds;
 
+use crate::attributes::{attr_range, AttrRange, AttrValue};
 use crate::edge::Edge;
 use crate::errors::GruPHstError;
 use crate::graphs::Graphs;
+use crate::graphs::{index::AttrIndex, memory::VaultRef};
 
 impl Graphs {
     /// Returns a collection of Edges that matches the relation
     /// for provided vault or default when None
     pub fn find_edges_by_relation(
-        &mut self,
+        &self,
         relation_name: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let edges = self.select_vault_mut(vault_name)?;
+        let edges = self.select_vault_ref(vault_name)?;
         let result = edges
             .iter()
             .filter(|edge| edge.get_relation() == relation_name)
@@ -29,11 +32,11 @@ impl Graphs {
     /// in the array
     /// for provided vault or default when None
     pub fn find_edges_by_relations(
-        &mut self,
+        &self,
         relations: Vec<&str>,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let vault = self.select_vault_mut(vault_name)?;
+        let vault = self.select_vault_ref(vault_name)?;
         let edges = vault
             .iter()
             .filter(|edge| relations.contains(&edge.get_relation().as_str()))
@@ -49,11 +52,11 @@ impl Graphs {
     /// Returns a collection of edges like any attribute vertex key
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_key_like(
-        &mut self,
+        &self,
         attr_k: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let edges = self.select_vault_mut(vault_name)?;
+        let edges = self.select_vault_ref(vault_name)?;
         let vrtcs = edges
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_key_like(attr_k))
@@ -69,11 +72,11 @@ impl Graphs {
     /// Returns a collection of edges that matches any attribute vertex by key
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_key(
-        &mut self,
+        &self,
         attr_k: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
+        let current_vault = self.select_vault_ref(vault_name)?;
         let edges = current_vault
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_key(attr_k))
@@ -89,11 +92,11 @@ impl Graphs {
     /// Returns a collection of edges that matches a string attribute vertex by key
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_str_key(
-        &mut self,
+        &self,
         attr_k: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
+        let current_vault = self.select_vault_ref(vault_name)?;
         let edges = current_vault
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_str_key(attr_k))
@@ -109,11 +112,11 @@ impl Graphs {
     /// Returns a collection of edges like string attribute vertex key
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_str_key_like(
-        &mut self,
+        &self,
         attr_k: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
+        let current_vault = self.select_vault_ref(vault_name)?;
         let edges = current_vault
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_str_key_like(attr_k))
@@ -127,7 +130,8 @@ impl Graphs {
     }
 
     /// Returns a collection of edges that matches a string attribute vertex
-    /// for some provided vault_name or default when None
+    /// for some provided vault_name or default when None.
+    /// Served from the index on the attribute of the vertices when there is one
     pub fn find_edges_with_vertex_attr_str_equals_to<T>(
         &self,
         attr_k: &str,
@@ -137,27 +141,24 @@ impl Graphs {
     where
         T: std::fmt::Display + std::clone::Clone,
     {
-        let current_vault = self.select_vault(vault_name)?;
-        let edges = current_vault
-            .into_iter()
-            .filter(|edge| edge.has_vertex_with_attr_str_value_equals_to(attr_k, attr_v.clone()))
-            .collect::<Vec<Edge>>();
-        if !edges.is_empty() {
-            Ok(edges)
-        } else {
-            warn!("Any edge found for attribute: {}", attr_k);
-            Err(GruPHstError::EdgeNotFound)
-        }
+        let text = attr_v.to_string();
+        let edges = self.find_edges_matching(
+            vault_name,
+            attr_k,
+            |vault| vault.vertex_edge_candidates(attr_k, |index| index.equal_text(&text)),
+            |edge| edge.has_vertex_with_attr_str_value_equals_to(attr_k, attr_v.clone()),
+        )?;
+        Ok(edges.into_iter().cloned().collect())
     }
 
     /// Returns a collection of edges that matches a vector u8 attribute vertex by key
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_vec_u8_key(
-        &mut self,
+        &self,
         attr_k: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
+        let current_vault = self.select_vault_ref(vault_name)?;
         let edges = current_vault
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_vec_u8_key(attr_k))
@@ -173,11 +174,11 @@ impl Graphs {
     /// Returns a collection of edges like vector u8 attribute vertex key
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_vec_u8_key_like(
-        &mut self,
+        &self,
         attr_k: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
+        let current_vault = self.select_vault_ref(vault_name)?;
         let edges = current_vault
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_vec_u8_key_like(attr_k))
@@ -193,12 +194,12 @@ impl Graphs {
     /// Returns a collection of edges where vector u8 attribute value is equals to
     /// for some provided vault_name or default when None
     pub fn find_edges_with_vertex_attr_vec_u8_equals_to(
-        &mut self,
+        &self,
         attr_k: &str,
         attr_v: &Vec<u8>,
         vault_name: Option<&str>,
     ) -> Result<Vec<&Edge>, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
+        let current_vault = self.select_vault_ref(vault_name)?;
         let vrtcs = current_vault
             .iter()
             .filter(|edge| edge.has_vertex_with_attr_vec_u8_value_equals_to(attr_k, attr_v))
@@ -211,6 +212,197 @@ impl Graphs {
         }
     }
 
+    /// Returns a collection of edges with an attribute equal to the value,
+    /// integers and floats are compared as numbers,
+    /// for some provided vault_name or default when None.
+    /// Served from the index on the attribute when there is one
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::{Graphs, IndexKind, IndexTarget}, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut edge = Edge::create(&Vertex::new("Gandalf"), "friend of", &Vertex::new("Frodo"));
+    /// edge.set_attr("since", 2941);
+    /// graphs.add_edge(&edge, None).unwrap();
+    /// graphs.create_index("since", IndexTarget::Edge, IndexKind::BTree, None).unwrap();
+    ///
+    /// let found = graphs.find_edges_with_attr_equals_to("since", 2941, None).unwrap();
+    /// assert_eq!(found[0].get_relation(), "friend of");
+    /// ```
+    pub fn find_edges_with_attr_equals_to<T>(
+        &self,
+        attr_k: &str,
+        attr_v: T,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<&Edge>, GruPHstError>
+    where
+        T: Into<AttrValue>,
+    {
+        let attr_v: AttrValue = attr_v.into();
+        self.find_edges_matching(
+            vault_name,
+            attr_k,
+            |vault| vault.edge_candidates(attr_k, |index| index.equal(&attr_v)),
+            |edge| edge.has_attr_equal(attr_k, &attr_v),
+        )
+    }
+
+    /// Returns a collection of edges with an attribute inside of a range,
+    /// like `10..=50`,
+    /// for some provided vault_name or default when None.
+    /// Served from the index on the attribute when there is one
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut edge = Edge::create(&Vertex::new("Gandalf"), "friend of", &Vertex::new("Frodo"));
+    /// edge.set_attr("duration in years", 42);
+    /// graphs.add_edge(&edge, None).unwrap();
+    ///
+    /// let found = graphs.find_edges_with_attr_in_range("duration in years", 10..=50, None).unwrap();
+    /// assert_eq!(found[0].get_relation(), "friend of");
+    /// ```
+    pub fn find_edges_with_attr_in_range<R, T>(
+        &self,
+        attr_k: &str,
+        range: R,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<&Edge>, GruPHstError>
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        let range = attr_range(&range);
+        self.find_edges_matching(
+            vault_name,
+            attr_k,
+            |vault| vault.edge_candidates(attr_k, by_range(&range)),
+            |edge| edge.has_attr_within(attr_k, &range),
+        )
+    }
+
+    /// Returns a collection of edges with an attribute inside of a range
+    /// on any graphs' vault
+    pub fn find_edges_with_attr_in_range_in_graphs<R, T>(
+        &self,
+        attr_k: &str,
+        range: R,
+    ) -> Result<Vec<&Edge>, GruPHstError>
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        let range = attr_range(&range);
+        self.find_edges_matching_in_graphs(
+            attr_k,
+            |vault| vault.edge_candidates(attr_k, by_range(&range)),
+            |edge| edge.has_attr_within(attr_k, &range),
+        )
+    }
+
+    /// Returns a collection of edges where "from" or "to" vertex
+    /// has an attribute inside of a range,
+    /// for some provided vault_name or default when None.
+    /// Served from the index on the attribute of the vertices when there is one
+    pub fn find_edges_with_vertex_attr_in_range<R, T>(
+        &self,
+        attr_k: &str,
+        range: R,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<&Edge>, GruPHstError>
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        let range = attr_range(&range);
+        self.find_edges_matching(
+            vault_name,
+            attr_k,
+            |vault| vault.vertex_edge_candidates(attr_k, by_range(&range)),
+            |edge| edge.has_vertex_with_attr_within(attr_k, &range),
+        )
+    }
+
+    /// Returns a collection of edges where "from" or "to" vertex
+    /// has an attribute inside of a range on any graphs' vault
+    pub fn find_edges_with_vertex_attr_in_range_in_graphs<R, T>(
+        &self,
+        attr_k: &str,
+        range: R,
+    ) -> Result<Vec<&Edge>, GruPHstError>
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        let range = attr_range(&range);
+        self.find_edges_matching_in_graphs(
+            attr_k,
+            |vault| vault.vertex_edge_candidates(attr_k, by_range(&range)),
+            |edge| edge.has_vertex_with_attr_within(attr_k, &range),
+        )
+    }
+
+    /// Edges of the vault matching the predicate,
+    /// checked on the candidates retrieved from the vault
+    fn find_edges_matching<'a, C, F>(
+        &'a self,
+        vault_name: Option<&str>,
+        attr_k: &str,
+        candidates: C,
+        predicate: F,
+    ) -> Result<Vec<&'a Edge>, GruPHstError>
+    where
+        C: FnOnce(&VaultRef<'a>) -> Vec<&'a Edge>,
+        F: Fn(&Edge) -> bool,
+    {
+        let current_vault = self.select_vault_label(vault_name);
+        let vault = match self.vault_ref(&current_vault)? {
+            Some(vault) if vault.edges.is_empty() => return Err(GruPHstError::VaultEmpty),
+            Some(vault) => vault,
+            None => return Err(self.select_vault_not_exists_error(current_vault)),
+        };
+        let result: Vec<&Edge> = candidates(&vault)
+            .into_iter()
+            .filter(|edge| predicate(edge))
+            .collect();
+        if !result.is_empty() {
+            Ok(result)
+        } else {
+            warn!("Any edge found for attribute: {}", attr_k);
+            Err(GruPHstError::EdgeNotFound)
+        }
+    }
+
+    fn find_edges_matching_in_graphs<'a, C, F>(
+        &'a self,
+        attr_k: &str,
+        candidates: C,
+        predicate: F,
+    ) -> Result<Vec<&'a Edge>, GruPHstError>
+    where
+        C: Fn(&VaultRef<'a>) -> Vec<&'a Edge>,
+        F: Fn(&Edge) -> bool,
+    {
+        let mut result: Vec<&Edge> = Vec::new();
+        for vault in self.vaults_in_graphs() {
+            let (_, vault) = vault?;
+            result.extend(
+                candidates(&vault)
+                    .into_iter()
+                    .filter(|edge| predicate(edge)),
+            );
+        }
+        if !result.is_empty() {
+            Ok(result)
+        } else {
+            warn!("Any edge found in graphs for attribute: {}", attr_k);
+            Err(GruPHstError::EdgeNotFound)
+        }
+    }
+
     /// Returns an Edge that provided id matches with Edge Id, or From, To vertices
     /// for some provided vault_name or default when None
     pub fn find_edge_by_id(
@@ -233,6 +425,9 @@ impl Graphs {
 
     /// Find edge by id on any graphs' vault
     pub fn find_edge_by_id_in_graphs(&mut self, id: &str) -> Result<&mut Edge, GruPHstError> {
+        for vault_name in self.vaults_on_disk() {
+            self.reload_evicted(&vault_name)?;
+        }
         for (_vault_name, edges) in self.vault.iter_mut() {
             if let Some(edge) = edges.iter_mut().find(|vrtx| {
                 vrtx.get_id() == id
@@ -246,3 +441,11 @@ impl Graphs {
         Err(GruPHstError::EdgeNotFound)
     }
 }
+
+/// Lookup of the candidates inside of a range on an index
+fn by_range<T>(range: &AttrRange) -> impl FnOnce(&AttrIndex<T>) -> Vec<T> + '_
+where
+    T: Clone + Eq + std::hash::Hash,
+{
+    move |index| index.range(range)
+}
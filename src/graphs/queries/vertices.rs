@@ -1,5 +1,8 @@
 use log::warn;
+use std::collections::HashMap;
+use std::ops::RangeBounds;
 
+use crate::attributes::{attr_range, AttrValue};
 use crate::errors::GruPHstError;
 use crate::graphs::Graphs;
 use crate::vertex::Vertex;
@@ -8,26 +11,27 @@ impl Graphs {
     /// Returns a Vertex that provided id matches with id of From, To vertices
     /// for some provided vault_name or default when None
     pub fn find_vertex_by_id(
-        &mut self,
+        &self,
         id: &str,
         vault_name: Option<&str>,
     ) -> Result<Vertex, GruPHstError> {
-        let current_vault = self.select_vault_mut(vault_name)?;
-        for edge in current_vault {
-            if let Ok(vertex) = edge.find_vertex_by_id(id) {
-                return Ok(vertex);
+        let vertices = self.select_vault_vertices(vault_name)?;
+        match vertices.get(id) {
+            Some(vertex) => Ok(vertex.clone()),
+            None => {
+                warn!("Vertex with id: {} not found", id);
+                Err(GruPHstError::VertexNotFound)
             }
         }
-        warn!("Vertex with id: {} not found", id);
-        Err(GruPHstError::VertexNotFound)
     }
 
     /// Returns a Vertex that provided id matches with id of From, To vertices
     /// on any graphs' vault
-    pub fn find_vertex_by_id_in_graphs(&mut self, id: &str) -> Result<Vertex, GruPHstError> {
-        for (vault_name, _edges) in self.vault.clone() {
-            if let Ok(vertex) = self.find_vertex_by_id(id, Some(vault_name.as_str())) {
-                return Ok(vertex);
+    pub fn find_vertex_by_id_in_graphs(&self, id: &str) -> Result<Vertex, GruPHstError> {
+        for vault in self.vaults_in_graphs() {
+            let (_vault_name, vault) = vault?;
+            if let Some(vertex) = vault.vertices.get(id) {
+                return Ok(vertex.clone());
             }
         }
         warn!("Vertex with id: {} not found in graphs", id);
@@ -41,13 +45,14 @@ impl Graphs {
         relation_in: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<Vertex>, GruPHstError> {
-        let mut relations_in: Vec<Vertex> = Vec::new();
-        let current_vault = self.select_vault(vault_name)?;
-        for edge in current_vault {
-            if edge.get_relation() == relation_in && !relations_in.contains(&edge.get_to_vertex()) {
-                relations_in.push(edge.get_to_vertex().clone());
-            }
-        }
+        let vault = self.select_vault_with_edges(vault_name)?;
+        let relations_in: Vec<Vertex> = vault
+            .adjacency
+            .with_relation_in(relation_in)
+            .iter()
+            .filter_map(|id| vault.vertices.get(id))
+            .cloned()
+            .collect();
         if !relations_in.is_empty() {
             Ok(relations_in)
         } else {
@@ -63,15 +68,14 @@ impl Graphs {
         relation_out: &str,
         vault_name: Option<&str>,
     ) -> Result<Vec<Vertex>, GruPHstError> {
-        let mut relations_out: Vec<Vertex> = Vec::new();
-        let current_vault = self.select_vault(vault_name)?;
-        for edge in current_vault {
-            if edge.get_relation() == relation_out
-                && !relations_out.contains(&edge.get_from_vertex())
-            {
-                relations_out.push(edge.get_from_vertex().clone());
-            }
-        }
+        let vault = self.select_vault_with_edges(vault_name)?;
+        let relations_out: Vec<Vertex> = vault
+            .adjacency
+            .with_relation_out(relation_out)
+            .iter()
+            .filter_map(|id| vault.vertices.get(id))
+            .cloned()
+            .collect();
         if !relations_out.is_empty() {
             Ok(relations_out)
         } else {
@@ -79,4 +83,133 @@ impl Graphs {
             Err(GruPHstError::VertexNotFound)
         }
     }
+
+    /// Retrieves all the vertices with an attribute equal to the value,
+    /// integers and floats are compared as numbers,
+    /// for some provided vault_name or default when None.
+    /// Served from the index on the attribute when there is one
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut frodo = Vertex::new("Frodo");
+    /// frodo.set_attr("years old", 50);
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    ///
+    /// let found = graphs.find_vertices_with_attr_equals_to("years old", 50.0, None).unwrap();
+    /// assert_eq!(found[0].get_label(), "Frodo");
+    /// ```
+    pub fn find_vertices_with_attr_equals_to<T>(
+        &self,
+        attr_k: &str,
+        attr_v: T,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<Vertex>, GruPHstError>
+    where
+        T: Into<AttrValue>,
+    {
+        let attr_v: AttrValue = attr_v.into();
+        let current_vault = self.select_vault_label(vault_name);
+        let Some(vault) = self.vault_ref(&current_vault)? else {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        };
+        let result: Vec<Vertex> = vault
+            .vertex_candidates(attr_k, |index| index.equal(&attr_v))
+            .into_iter()
+            .filter(|vertex| vertex.has_attr_equal(attr_k, &attr_v))
+            .cloned()
+            .collect();
+        if !result.is_empty() {
+            Ok(result)
+        } else {
+            warn!(
+                "Any vertex found for attribute {} equals to {}",
+                attr_k, attr_v
+            );
+            Err(GruPHstError::VertexNotFound)
+        }
+    }
+
+    /// Retrieves all the vertices with an attribute inside of a range,
+    /// like `1000..` or `10..=50`,
+    /// for some provided vault_name or default when None.
+    /// Served from the index on the attribute when there is one
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut gandalf = Vertex::new("Gandalf");
+    /// gandalf.set_attr("years old", 24000);
+    /// let mut frodo = Vertex::new("Frodo");
+    /// frodo.set_attr("years old", 50);
+    /// graphs.add_edge(&Edge::create(&gandalf, "friend of", &frodo), None).unwrap();
+    ///
+    /// let ancients = graphs.find_vertices_with_attr_in_range("years old", 1000.., None).unwrap();
+    /// assert_eq!(ancients.len(), 1);
+    /// assert_eq!(ancients[0].get_label(), "Gandalf");
+    /// ```
+    pub fn find_vertices_with_attr_in_range<R, T>(
+        &self,
+        attr_k: &str,
+        range: R,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<Vertex>, GruPHstError>
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        let range = attr_range(&range);
+        let current_vault = self.select_vault_label(vault_name);
+        let Some(vault) = self.vault_ref(&current_vault)? else {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        };
+        let result: Vec<Vertex> = vault
+            .vertex_candidates(attr_k, |index| index.range(&range))
+            .into_iter()
+            .filter(|vertex| vertex.has_attr_within(attr_k, &range))
+            .cloned()
+            .collect();
+        if !result.is_empty() {
+            Ok(result)
+        } else {
+            warn!("Any vertex found for attribute {} in range", attr_k);
+            Err(GruPHstError::VertexNotFound)
+        }
+    }
+
+    /// Retrieves all the vertices with an attribute inside of a range
+    /// on any graphs' vault
+    pub fn find_vertices_with_attr_in_range_in_graphs<R, T>(
+        &self,
+        attr_k: &str,
+        range: R,
+    ) -> Result<Vec<Vertex>, GruPHstError>
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        let range = attr_range(&range);
+        let mut result: HashMap<String, Vertex> = HashMap::new();
+        for vault in self.vaults_in_graphs() {
+            let (_vault_name, vault) = vault?;
+            for vertex in vault.vertex_candidates(attr_k, |index| index.range(&range)) {
+                if vertex.has_attr_within(attr_k, &range) {
+                    result.insert(vertex.get_id(), vertex.clone());
+                }
+            }
+        }
+        if !result.is_empty() {
+            Ok(result.into_values().collect())
+        } else {
+            warn!(
+                "Any vertex found in graphs for attribute {} in range",
+                attr_k
+            );
+            Err(GruPHstError::VertexNotFound)
+        }
+    }
 }
@@ -0,0 +1,370 @@
+//! Schemas of the vaults
+//!
+//! A schema restricts the edges and vertices of a vault: the relations
+//! allowed, with the labels permitted for their vertices, the attributes
+//! required and their types, for the vertices by label and for the edges
+//! by relation, and the unique keys of the vertices by label.
+//!
+//! The schema is checked when edges or vertices are added or updated,
+//! also the attributes of a vertex with `set_vertex_attr`
+//! and `delete_vertex_attr`, failing with SchemaViolation without
+//! changing the vault. The changes out of the Graphs are not checked.
+//! A unique key is checked against the vertices with the same value
+//! on its first attribute, retrieved from the index on it when there is one.
+//! The schema is saved with the vault, and logged when the Graphs
+//! was opened on a directory.
+
+use std::collections::HashMap;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attributes::{AttrType, AttrValue},
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{snapshot::size, wal::WalRecord, Graphs},
+    vertex::Vertex,
+};
+
+/// Attributes required and their types
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct AttrRules {
+    required: Vec<String>,
+    types: HashMap<String, AttrType>,
+}
+
+impl AttrRules {
+    fn check<F>(&self, what: &str, value: F) -> Result<(), GruPHstError>
+    where
+        F: Fn(&str) -> Option<AttrValue>,
+    {
+        for attr_k in self.required.iter() {
+            if value(attr_k).is_none() {
+                return Err(violation(format!(
+                    "{} requires the attribute \"{}\"",
+                    what, attr_k
+                )));
+            }
+        }
+        for (attr_k, attr_type) in self.types.iter() {
+            match value(attr_k) {
+                Some(attr_v) if attr_v.get_type() != *attr_type => {
+                    return Err(violation(format!(
+                        "attribute \"{}\" of {} is {} not {}",
+                        attr_k,
+                        what,
+                        attr_v.type_name(),
+                        attr_type.name()
+                    )))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Labels permitted for the vertices of a relation, any when empty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct RelationRule {
+    from: Vec<String>,
+    to: Vec<String>,
+}
+
+/// Rules for the edges and vertices of a vault
+///
+/// # Examples
+/// ```rust
+/// use gruphst::{attributes::AttrType, graphs::Schema};
+///
+/// let mut schema = Schema::new();
+/// schema.allow_relation("friend of", &["Hobbit"], &["Hobbit", "Wizard"]);
+/// schema.require_vertex_attr("Hobbit", "name");
+/// schema.set_vertex_attr_type("Hobbit", "years old", AttrType::Int);
+/// schema.add_unique_key("Hobbit", &["name"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    /// relations allowed, any relation when empty
+    relations: HashMap<String, RelationRule>,
+    /// rules for the attributes of the vertices by label
+    vertices: HashMap<String, AttrRules>,
+    /// rules for the attributes of the edges by relation
+    edges: HashMap<String, AttrRules>,
+    /// unique keys of the vertices by label
+    unique: HashMap<String, Vec<Vec<String>>>,
+}
+
+impl Schema {
+    /// Creates a schema without rules, allowing any edge or vertex
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// Allows a relation between vertices with the provided labels,
+    /// any label when empty; once a relation is allowed
+    /// the relations not allowed are rejected
+    pub fn allow_relation(&mut self, relation: &str, from_labels: &[&str], to_labels: &[&str]) {
+        let to_strings = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect();
+        self.relations.insert(
+            relation.to_string(),
+            RelationRule {
+                from: to_strings(from_labels),
+                to: to_strings(to_labels),
+            },
+        );
+    }
+
+    /// Requires an attribute on the vertices with the label
+    pub fn require_vertex_attr(&mut self, label: &str, attr_k: &str) {
+        let rules = self.vertices.entry(label.to_string()).or_default();
+        rules.required.push(attr_k.to_string());
+    }
+
+    /// Sets the type of an attribute, when present, on the vertices with the label
+    pub fn set_vertex_attr_type(&mut self, label: &str, attr_k: &str, attr_type: AttrType) {
+        let rules = self.vertices.entry(label.to_string()).or_default();
+        rules.types.insert(attr_k.to_string(), attr_type);
+    }
+
+    /// Requires an attribute on the edges with the relation
+    pub fn require_edge_attr(&mut self, relation: &str, attr_k: &str) {
+        let rules = self.edges.entry(relation.to_string()).or_default();
+        rules.required.push(attr_k.to_string());
+    }
+
+    /// Sets the type of an attribute, when present, on the edges with the relation
+    pub fn set_edge_attr_type(&mut self, relation: &str, attr_k: &str, attr_type: AttrType) {
+        let rules = self.edges.entry(relation.to_string()).or_default();
+        rules.types.insert(attr_k.to_string(), attr_type);
+    }
+
+    /// Adds a unique key, of one or more attributes, for the vertices with the label;
+    /// two vertices with the label can not have the same values
+    /// on every attribute of the key
+    pub fn add_unique_key(&mut self, label: &str, attr_keys: &[&str]) {
+        if attr_keys.is_empty() {
+            warn!("Unique key without attributes for label {}", label);
+            return;
+        }
+        let key = attr_keys.iter().map(|k| k.to_string()).collect();
+        self.unique.entry(label.to_string()).or_default().push(key);
+    }
+
+    /// Checks the relation, the labels of the vertices
+    /// and the attributes of an edge
+    fn check_edge(&self, edge: &Edge) -> Result<(), GruPHstError> {
+        let relation = edge.get_relation();
+        if !self.relations.is_empty() {
+            let Some(rule) = self.relations.get(&relation) else {
+                return Err(violation(format!(
+                    "relation \"{}\" is not allowed",
+                    relation
+                )));
+            };
+            for (end, labels, vertex) in [
+                ("from", &rule.from, edge.get_from_vertex()),
+                ("to", &rule.to, edge.get_to_vertex()),
+            ] {
+                let label = vertex.get_label();
+                if !labels.is_empty() && !labels.contains(&label) {
+                    return Err(violation(format!(
+                        "relation \"{}\" does not permit the label \"{}\" on its {} vertex",
+                        relation, label, end
+                    )));
+                }
+            }
+        }
+        if let Some(rules) = self.edges.get(&relation) {
+            let what = format!("edge \"{}\" [{}]", relation, edge.get_id());
+            rules.check(&what, |attr_k| edge.attr.get(attr_k).cloned())?;
+        }
+        Ok(())
+    }
+
+    /// Checks the attributes of a vertex
+    fn check_vertex(&self, vertex: &Vertex) -> Result<(), GruPHstError> {
+        if let Some(rules) = self.vertices.get(&vertex.get_label()) {
+            rules.check(&describe(vertex), |attr_k| vertex.attr_value(attr_k))?;
+        }
+        Ok(())
+    }
+}
+
+fn violation(message: String) -> GruPHstError {
+    warn!("Schema violation: {}", message);
+    GruPHstError::SchemaViolation(message)
+}
+
+fn describe(vertex: &Vertex) -> String {
+    format!("vertex \"{}\" [{}]", vertex.get_label(), vertex.get_id())
+}
+
+impl Graphs {
+    /// Sets the schema of a vault, checked when edges or vertices
+    /// are added or updated, for some provided vault_name or default when None.
+    /// Fails with SchemaViolation when the vault does not follow it;
+    /// logged when the Graphs was opened on a directory
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, errors::GruPHstError, graphs::{Graphs, Schema}, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("shire");
+    /// let mut schema = Schema::new();
+    /// schema.allow_relation("friend of", &["Hobbit"], &["Hobbit"]);
+    /// schema.add_unique_key("Hobbit", &["name"]);
+    /// graphs.set_schema(schema, None).unwrap();
+    ///
+    /// let mut frodo = Vertex::new("Hobbit");
+    /// frodo.set_attr("name", "Frodo");
+    /// let mut sam = Vertex::new("Hobbit");
+    /// sam.set_attr("name", "Sam");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &sam), None).unwrap();
+    ///
+    /// let mut another_frodo = Vertex::new("Hobbit");
+    /// another_frodo.set_attr("name", "Frodo");
+    /// let result = graphs.add_edge(&Edge::create(&another_frodo, "friend of", &sam), None);
+    /// assert!(matches!(result, Err(GruPHstError::SchemaViolation(_))));
+    /// assert_eq!(graphs.len(), 1);
+    /// ```
+    pub fn set_schema(
+        &mut self,
+        schema: Schema,
+        vault_name: Option<&str>,
+    ) -> Result<(), GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        let Some(vault) = self.vault_ref(&current_vault)? else {
+            return Err(self.select_vault_not_exists_error(current_vault));
+        };
+        for edge in vault.edges.iter() {
+            schema.check_edge(edge)?;
+        }
+        for vertex in vault.vertices.values() {
+            schema.check_vertex(vertex)?;
+            self.check_unique_keys(&schema, &current_vault, vertex, &[])?;
+        }
+        let added = size::schema(&schema);
+        let replaced = self.schemas.get(&current_vault).map_or(0, size::schema);
+        self.reserve_memory(added, &current_vault)?;
+        self.log_mutation(WalRecord::SetSchema(current_vault.clone(), schema.clone()))?;
+        info!("Schema set for vault {}", current_vault);
+        self.schemas.insert(current_vault.clone(), schema);
+        self.account(&current_vault, added as isize - replaced as isize);
+        self.mutated();
+        Ok(())
+    }
+
+    /// Retrieves the schema of a vault,
+    /// for some provided vault_name or default when None
+    pub fn get_schema(&self, vault_name: Option<&str>) -> Option<&Schema> {
+        let current_vault = self.select_vault_label(vault_name);
+        match self.vault_ref(&current_vault) {
+            Ok(vault) => vault.and_then(|vault| vault.schema),
+            Err(_) => None,
+        }
+    }
+
+    /// Removes the schema of a vault, returning it,
+    /// for some provided vault_name or default when None;
+    /// logged when the Graphs was opened on a directory
+    pub fn remove_schema(
+        &mut self,
+        vault_name: Option<&str>,
+    ) -> Result<Option<Schema>, GruPHstError> {
+        let current_vault = self.select_vault_label(vault_name);
+        self.reload_evicted(&current_vault)?;
+        if !self.schemas.contains_key(&current_vault) {
+            return Ok(None);
+        }
+        self.log_mutation(WalRecord::RemoveSchema(current_vault.clone()))?;
+        let removed = self.schemas.remove(&current_vault);
+        let size = removed.as_ref().map_or(0, size::schema);
+        self.account(&current_vault, -(size as isize));
+        info!("Schema removed from vault {}", current_vault);
+        self.mutated();
+        Ok(removed)
+    }
+
+    /// Checks the edges to add on a vault, and their vertices,
+    /// against its schema
+    pub(crate) fn check_schema_edges(
+        &self,
+        vault_name: &str,
+        edges: &[Edge],
+    ) -> Result<(), GruPHstError> {
+        let Some(schema) = self.schemas.get(vault_name) else {
+            return Ok(());
+        };
+        let mut added: Vec<Vertex> = Vec::new();
+        for edge in edges {
+            schema.check_edge(edge)?;
+            for vertex in [edge.get_from_vertex(), edge.get_to_vertex()] {
+                schema.check_vertex(&vertex)?;
+                self.check_unique_keys(schema, vault_name, &vertex, &added)?;
+                added.push(vertex);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks a vertex to add on a vault against its schema
+    pub(crate) fn check_schema_vertex(
+        &self,
+        vault_name: &str,
+        vertex: &Vertex,
+    ) -> Result<(), GruPHstError> {
+        let Some(schema) = self.schemas.get(vault_name) else {
+            return Ok(());
+        };
+        schema.check_vertex(vertex)?;
+        self.check_unique_keys(schema, vault_name, vertex, &[])
+    }
+
+    /// Checks no other vertex on the vault, or added along with it,
+    /// has the same values on a unique key of the vertex
+    fn check_unique_keys(
+        &self,
+        schema: &Schema,
+        vault_name: &str,
+        vertex: &Vertex,
+        added: &[Vertex],
+    ) -> Result<(), GruPHstError> {
+        let label = vertex.get_label();
+        let Some(keys) = schema.unique.get(&label) else {
+            return Ok(());
+        };
+        let id = vertex.get_id();
+        for key in keys {
+            let values: Option<Vec<AttrValue>> =
+                key.iter().map(|attr_k| vertex.attr_value(attr_k)).collect();
+            // a vertex without every attribute of the key is not restricted
+            let Some(values) = values else {
+                continue;
+            };
+            let same_key = |other: &Vertex| {
+                other.get_id() != id
+                    && other.get_label() == label
+                    && key
+                        .iter()
+                        .zip(values.iter())
+                        .all(|(attr_k, attr_v)| other.has_attr_equal(attr_k, attr_v))
+            };
+            let registered = match self.vault_ref(vault_name)? {
+                Some(vault) => vault.vertex_candidates(&key[0], |index| index.equal(&values[0])),
+                None => Vec::new(),
+            };
+            if let Some(other) = registered.into_iter().chain(added).find(|v| same_key(v)) {
+                return Err(violation(format!(
+                    "{} has the same unique key ({}) as {}",
+                    describe(vertex),
+                    key.join(", "),
+                    describe(other)
+                )));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    thread,
+};
+
+use log::error;
+
+use crate::graphs::{AutoPersist, Graphs};
+
+/// A thread-safe handle to a Graphs,
+/// available with the "sync" feature.
+///
+/// Many threads can read the Graphs at the same time,
+/// while only one can write on it.
+/// Cloning the handle shares the same Graphs.
+/// With an `AutoPersist::Interval` policy the Graphs is saved
+/// on a background thread, until every handle is dropped.
+///
+/// # Examples
+/// ```rust
+/// use std::thread;
+/// use gruphst::{edge::Edge, graphs::SharedGraphs, vertex::Vertex};
+///
+/// let graphs = SharedGraphs::init("middle-earth");
+/// let handles: Vec<_> = ["Frodo", "Sam"]
+///     .iter()
+///     .map(|hobbit| {
+///         let graphs = graphs.clone();
+///         thread::spawn(move || {
+///             let edge = Edge::create(
+///                 &Vertex::new(hobbit),
+///                 "lives at",
+///                 &Vertex::new("The Shire"));
+///             graphs.write().add_edge(&edge, None).unwrap();
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// assert_eq!(graphs.read().len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedGraphs {
+    graphs: Arc<RwLock<Graphs>>,
+}
+
+impl SharedGraphs {
+    /// Creates a new handle for the provided Graphs
+    pub fn new(graphs: Graphs) -> Self {
+        let shared = SharedGraphs {
+            graphs: Arc::new(RwLock::new(graphs)),
+        };
+        shared.spawn_auto_persist();
+        shared
+    }
+
+    /// Sets when the Graphs is saved after its mutations,
+    /// like `Graphs::set_auto_persist`, starting the background thread
+    /// for an `AutoPersist::Interval` policy
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use gruphst::{edge::Edge, graphs::{AutoPersist, Graphs, SharedGraphs}, vertex::Vertex};
+    ///
+    /// let graphs = SharedGraphs::init("auto-persisted-shared");
+    /// graphs.set_auto_persist(AutoPersist::Interval(Duration::from_millis(10)), Some("./"));
+    /// let edge = Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam"));
+    /// graphs.write().add_edge(&edge, None).unwrap();
+    ///
+    /// std::thread::sleep(Duration::from_millis(200));
+    /// let saved = Graphs::load("./auto-persisted-shared.grphst").unwrap();
+    /// assert_eq!(saved.len(), 1);
+    /// # std::fs::remove_file("./auto-persisted-shared.grphst").unwrap();
+    /// ```
+    pub fn set_auto_persist(&self, policy: AutoPersist, file_path: Option<&str>) {
+        self.write().set_auto_persist(policy, file_path);
+        self.spawn_auto_persist();
+    }
+
+    /// Saves the Graphs every interval of its policy on a thread,
+    /// that stops when the handles are dropped or the policy changes
+    fn spawn_auto_persist(&self) {
+        let Some((interval, generation)) = self.read().auto_persist_interval() else {
+            return;
+        };
+        let graphs = Arc::downgrade(&self.graphs);
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            let Some(shared) = graphs.upgrade() else {
+                break;
+            };
+            let mut graphs = shared.write().unwrap_or_else(PoisonError::into_inner);
+            if graphs.auto_persist_interval() != Some((interval, generation)) {
+                break;
+            }
+            if let Err(err) = graphs.flush() {
+                error!("Graphs {} not auto-persisted: {}", graphs.get_label(), err);
+            }
+        });
+    }
+
+    /// Initializes a new Graphs element behind a thread-safe handle
+    pub fn init(label: &str) -> Self {
+        SharedGraphs::new(Graphs::init(label))
+    }
+
+    /// Locks the Graphs for reading, other readers are allowed
+    /// at the same time, writers wait until the guard is dropped.
+    /// A poisoned lock, from a panicking writer, is recovered.
+    pub fn read(&self) -> RwLockReadGuard<'_, Graphs> {
+        self.graphs.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the Graphs for writing, waiting for any reader or writer.
+    /// A poisoned lock, from a panicking writer, is recovered.
+    pub fn write(&self) -> RwLockWriteGuard<'_, Graphs> {
+        self.graphs.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs the provided closure with read access to the Graphs
+    pub fn with_read<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Graphs) -> R,
+    {
+        f(&self.read())
+    }
+
+    /// Runs the provided closure with write access to the Graphs
+    pub fn with_write<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Graphs) -> R,
+    {
+        f(&mut self.write())
+    }
+}
+
+impl From<Graphs> for SharedGraphs {
+    fn from(graphs: Graphs) -> Self {
+        SharedGraphs::new(graphs)
+    }
+}
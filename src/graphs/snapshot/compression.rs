@@ -0,0 +1,66 @@
+//! Codecs to compress the persisted Graphs
+
+use std::{borrow::Cow, error::Error};
+
+use crate::errors::GruPHstError;
+
+/// Codec to compress a persisted Graphs,
+/// recorded on the header of the file so it is decompressed on load;
+/// the codecs available depend on the enabled features
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Compression {
+    /// written as it is
+    #[default]
+    None,
+    /// LZ4, fast to compress and decompress; needs the lz4 feature
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+/// Ids of the codecs on the header
+const NONE: u8 = 0;
+#[cfg(feature = "lz4")]
+const LZ4: u8 = 1;
+
+impl Compression {
+    pub(super) fn codec(&self) -> u8 {
+        match self {
+            Compression::None => NONE,
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => LZ4,
+        }
+    }
+
+    pub(super) fn compress(&self, bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            Compression::None => bytes,
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => lz4_flex::compress_prepend_size(&bytes),
+        }
+    }
+}
+
+/// Decompresses the bytes with the codec of the header
+pub(super) fn decompress(codec: u8, bytes: &[u8]) -> Result<Cow<'_, [u8]>, Box<dyn Error>> {
+    match codec {
+        NONE => Ok(Cow::Borrowed(bytes)),
+        #[cfg(feature = "lz4")]
+        LZ4 => Ok(Cow::Owned(decompress_lz4(bytes)?)),
+        _ => Err(GruPHstError::SnapshotUnsupportedCompression(codec).into()),
+    }
+}
+
+#[cfg(feature = "lz4")]
+fn decompress_lz4(bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    // the size of the decompressed Graphs is prepended,
+    // checked before allocating it
+    let size = bytes
+        .get(..4)
+        .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]))
+        .ok_or(GruPHstError::SnapshotChecksumMismatch)?;
+    if size as usize > crate::config::get_max_mem_usage() {
+        return Err(GruPHstError::PersistenceFile.into());
+    }
+    Ok(lz4_flex::decompress_size_prepended(bytes)?)
+}
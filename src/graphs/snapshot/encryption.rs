@@ -0,0 +1,125 @@
+//! Authenticated encryption of the persisted Graphs
+//!
+//! The snapshot written by save_encrypted is encrypted, and the Graphs
+//! keeps the key for its auto-persisted snapshots and evicted vaults.
+//! The write-ahead log and the files of a store are written in plaintext,
+//! so a Graphs with a key is not compacted nor saved on a store.
+
+use std::{error::Error, fmt};
+
+use argon2::{
+    password_hash::rand_core::{OsRng, RngCore},
+    Argon2,
+};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+use crate::errors::GruPHstError;
+
+/// Ids of the ciphers on the header
+pub(super) const NONE: u8 = 0;
+pub(super) const CHACHA20_POLY1305: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Key to encrypt a persisted Graphs with ChaCha20-Poly1305,
+/// supplied by the caller or derived from a passphrase with Argon2
+#[derive(Clone)]
+pub struct EncryptionKey {
+    secret: Secret,
+}
+
+#[derive(Clone)]
+enum Secret {
+    Key([u8; 32]),
+    Passphrase(Vec<u8>),
+}
+
+impl EncryptionKey {
+    /// Creates a key from the provided 256 bits
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::graphs::EncryptionKey;
+    ///
+    /// let key = EncryptionKey::new([42; 32]);
+    /// ```
+    pub fn new(key: [u8; 32]) -> Self {
+        EncryptionKey {
+            secret: Secret::Key(key),
+        }
+    }
+
+    /// Creates a key derived from a passphrase with Argon2,
+    /// with a random salt written on every file
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::graphs::EncryptionKey;
+    ///
+    /// let key = EncryptionKey::from_passphrase("speak friend and enter");
+    /// ```
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        EncryptionKey {
+            secret: Secret::Passphrase(passphrase.as_bytes().to_vec()),
+        }
+    }
+
+    fn derive(&self, salt: &[u8]) -> Result<[u8; 32], Box<dyn Error>> {
+        match &self.secret {
+            Secret::Key(key) => Ok(*key),
+            Secret::Passphrase(passphrase) => {
+                let mut key = [0u8; 32];
+                Argon2::default().hash_password_into(passphrase, salt, &mut key)?;
+                Ok(key)
+            }
+        }
+    }
+}
+
+/// The secret is never printed
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.secret {
+            Secret::Key(_) => "key",
+            Secret::Passphrase(_) => "passphrase",
+        };
+        write!(f, "EncryptionKey({})", kind)
+    }
+}
+
+/// Encrypts the bytes, written as the salt,
+/// the nonce and the ciphertext with the authentication tag.
+/// The salt is only used by keys derived from a passphrase
+pub(super) fn encrypt(key: &EncryptionKey, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key.derive(&salt)?));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), bytes)
+        .map_err(|_| GruPHstError::SnapshotEncryptionFailed)?;
+    let mut encrypted = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypts the bytes, failing with SnapshotWrongKey
+/// when they were not encrypted with the key
+pub(super) fn decrypt(key: &EncryptionKey, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    if bytes.len() < SALT_LEN + NONCE_LEN {
+        return Err(GruPHstError::SnapshotChecksumMismatch.into());
+    }
+    let (salt, rest) = bytes.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key.derive(salt)?));
+    Ok(cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| GruPHstError::SnapshotWrongKey)?)
+}
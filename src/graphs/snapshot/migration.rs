@@ -0,0 +1,443 @@
+//! Previous formats of the persisted Graphs and the migrations between them
+//!
+//! Every format is migrated to the next one, until the current one:
+//!
+//! | format | persisted by        | changes                                          |
+//! |--------|---------------------|--------------------------------------------------|
+//! | 0.12   | gruphst up to 0.12  | edges keep a copy of their vertices              |
+//! | 0.16   | gruphst 0.13 - 0.16 | Vec<u8> attributes on vertices, max memory stats |
+//! | 1      |                     | vertices written once, registered on the vaults  |
+//! | 2      |                     | typed attributes, with the version of the format |
+//! | 3      |                     | definitions of the indexes of every vault        |
+//! | 4      |                     | schema of every vault                            |
+//! | 5      | current             | version of the format only on the header         |
+//!
+//! The formats before the 2 were persisted without a version,
+//! so they are recognized trying to decode them, from the newest to the oldest.
+//! The formats 2 to 4 could also be persisted without the header,
+//! they are recognized by the version written as their first field.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+use bincode::Options;
+use log::info;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    attributes::AttrValue, errors::GruPHstError, graphs::index::IndexDef,
+    graphs_stats::GraphsStats, vertex::Vertex_,
+};
+
+use super::{EdgeSnapshot, GraphsSnapshot, VaultSnapshot, SNAPSHOT_VERSION};
+
+/// Version of the format with typed attributes, without indexes
+const VERSION_V2: u32 = 2;
+
+/// Version of the format with indexes, without schemas
+const VERSION_V3: u32 = 3;
+
+/// Version of the format with schemas and the version also as the first field
+const VERSION_V4: u32 = 4;
+
+/// Vertex with stringly-typed attributes, as persisted by gruphst 0.12
+#[derive(Debug, Deserialize)]
+struct VertexV0_12 {
+    id: String,
+    label: String,
+    attr: HashMap<String, String>,
+}
+
+/// Edge with a copy of its vertices, as persisted by gruphst 0.12
+#[derive(Debug, Deserialize)]
+struct EdgeV0_12 {
+    id: String,
+    relation: String,
+    from: VertexV0_12,
+    to: VertexV0_12,
+    attr: HashMap<String, String>,
+}
+
+/// Graphs as persisted by gruphst 0.12
+#[derive(Debug, Deserialize)]
+struct GraphsV0_12 {
+    vault: HashMap<String, Vec<EdgeV0_12>>,
+    label: String,
+    /// stats without the maximum memory usage,
+    /// not migrated since they are generated again
+    _stats: [usize; 6],
+}
+
+/// Vertex with the Vec<u8> attributes on a separated map,
+/// as persisted from gruphst 0.13 to 0.16
+#[derive(Debug, Deserialize)]
+struct VertexV0_16 {
+    id: String,
+    label: String,
+    attr: HashMap<String, String>,
+    attr_vec_u8: HashMap<String, Vec<u8>>,
+}
+
+/// Edge with a copy of its vertices, as persisted from gruphst 0.13 to 0.16
+#[derive(Debug, Deserialize)]
+struct EdgeV0_16 {
+    id: String,
+    relation: String,
+    from: VertexV0_16,
+    to: VertexV0_16,
+    attr: HashMap<String, String>,
+}
+
+/// Graphs as persisted from gruphst 0.13 to 0.16
+#[derive(Debug, Deserialize)]
+struct GraphsV0_16 {
+    vault: HashMap<String, Vec<EdgeV0_16>>,
+    label: String,
+    stats: GraphsStats,
+}
+
+/// Vertex with stringly-typed attributes
+/// and the Vec<u8> attributes on a separated map
+type VertexV1 = VertexV0_16;
+
+#[derive(Debug, Deserialize)]
+struct EdgeSnapshotV1 {
+    id: String,
+    relation: String,
+    from: String,
+    to: String,
+    attr: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VaultSnapshotV1 {
+    vertices: Vec<String>,
+    edges: Vec<EdgeSnapshotV1>,
+}
+
+/// Graphs with stringly-typed attributes, without format version
+#[derive(Debug, Deserialize)]
+struct GraphsSnapshotV1 {
+    label: String,
+    stats: GraphsStats,
+    vertices: Vec<VertexV1>,
+    vaults: HashMap<String, VaultSnapshotV1>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VaultSnapshotV2 {
+    vertices: Vec<String>,
+    edges: Vec<EdgeSnapshot>,
+}
+
+/// Graphs with typed attributes, without the indexes of the vaults
+#[derive(Debug, Deserialize)]
+struct GraphsSnapshotV2 {
+    version: u32,
+    label: String,
+    stats: GraphsStats,
+    vertices: Vec<Vertex_>,
+    vaults: HashMap<String, VaultSnapshotV2>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VaultSnapshotV3 {
+    vertices: Vec<String>,
+    edges: Vec<EdgeSnapshot>,
+    indexes: Vec<IndexDef>,
+}
+
+/// Graphs with the indexes of the vaults, without their schemas
+#[derive(Debug, Deserialize)]
+struct GraphsSnapshotV3 {
+    version: u32,
+    label: String,
+    stats: GraphsStats,
+    vertices: Vec<Vertex_>,
+    vaults: HashMap<String, VaultSnapshotV3>,
+}
+
+/// Graphs with the version of the format as the first field,
+/// besides the header
+#[derive(Debug, Deserialize)]
+struct GraphsSnapshotV4 {
+    version: u32,
+    label: String,
+    stats: GraphsStats,
+    vertices: Vec<Vertex_>,
+    vaults: HashMap<String, VaultSnapshot>,
+}
+
+impl From<VertexV0_12> for VertexV0_16 {
+    fn from(vertex: VertexV0_12) -> Self {
+        VertexV0_16 {
+            id: vertex.id,
+            label: vertex.label,
+            attr: vertex.attr,
+            attr_vec_u8: HashMap::new(),
+        }
+    }
+}
+
+impl From<GraphsV0_12> for GraphsV0_16 {
+    fn from(graphs: GraphsV0_12) -> Self {
+        let vault = graphs
+            .vault
+            .into_iter()
+            .map(|(name, edges)| {
+                let edges = edges
+                    .into_iter()
+                    .map(|edge| EdgeV0_16 {
+                        id: edge.id,
+                        relation: edge.relation,
+                        from: edge.from.into(),
+                        to: edge.to.into(),
+                        attr: edge.attr,
+                    })
+                    .collect();
+                (name, edges)
+            })
+            .collect();
+        GraphsV0_16 {
+            vault,
+            label: graphs.label,
+            stats: GraphsStats::init(),
+        }
+    }
+}
+
+impl From<GraphsV0_16> for GraphsSnapshotV1 {
+    /// The copies of a vertex are merged on the first one found
+    fn from(graphs: GraphsV0_16) -> Self {
+        let mut vertices: Vec<VertexV1> = Vec::new();
+        let mut written: HashSet<String> = HashSet::new();
+        let mut vaults: HashMap<String, VaultSnapshotV1> = HashMap::new();
+        for (name, edges) in graphs.vault {
+            let mut vault = VaultSnapshotV1 {
+                vertices: Vec::new(),
+                edges: Vec::with_capacity(edges.len()),
+            };
+            for edge in edges {
+                vault.edges.push(EdgeSnapshotV1 {
+                    id: edge.id,
+                    relation: edge.relation,
+                    from: edge.from.id.clone(),
+                    to: edge.to.id.clone(),
+                    attr: edge.attr,
+                });
+                for vertex in [edge.from, edge.to] {
+                    if !vault.vertices.contains(&vertex.id) {
+                        vault.vertices.push(vertex.id.clone());
+                    }
+                    if written.insert(vertex.id.clone()) {
+                        vertices.push(vertex);
+                    }
+                }
+            }
+            vaults.insert(name, vault);
+        }
+        GraphsSnapshotV1 {
+            label: graphs.label,
+            stats: graphs.stats,
+            vertices,
+            vaults,
+        }
+    }
+}
+
+/// String attributes are kept as strings,
+/// since the type of the value was not persisted
+fn migrate_attr(attr: HashMap<String, String>) -> HashMap<String, AttrValue> {
+    attr.into_iter()
+        .map(|(k, v)| (k, AttrValue::Str(v)))
+        .collect()
+}
+
+impl From<GraphsSnapshotV1> for GraphsSnapshotV2 {
+    fn from(snapshot: GraphsSnapshotV1) -> Self {
+        let vertices = snapshot
+            .vertices
+            .into_iter()
+            .map(|vertex| {
+                let mut attr = migrate_attr(vertex.attr);
+                for (k, v) in vertex.attr_vec_u8 {
+                    attr.insert(k, AttrValue::Bytes(v));
+                }
+                Vertex_::from_parts(vertex.id, vertex.label, attr)
+            })
+            .collect();
+        let vaults = snapshot
+            .vaults
+            .into_iter()
+            .map(|(name, vault)| {
+                let edges = vault
+                    .edges
+                    .into_iter()
+                    .map(|edge| EdgeSnapshot {
+                        id: edge.id,
+                        relation: edge.relation,
+                        from: edge.from,
+                        to: edge.to,
+                        attr: migrate_attr(edge.attr),
+                    })
+                    .collect();
+                let vault = VaultSnapshotV2 {
+                    vertices: vault.vertices,
+                    edges,
+                };
+                (name, vault)
+            })
+            .collect();
+        GraphsSnapshotV2 {
+            version: VERSION_V2,
+            label: snapshot.label,
+            stats: snapshot.stats,
+            vertices,
+            vaults,
+        }
+    }
+}
+
+impl From<GraphsSnapshotV2> for GraphsSnapshotV3 {
+    /// The vaults are migrated without indexes
+    fn from(snapshot: GraphsSnapshotV2) -> Self {
+        let vaults = snapshot
+            .vaults
+            .into_iter()
+            .map(|(name, vault)| {
+                let vault = VaultSnapshotV3 {
+                    vertices: vault.vertices,
+                    edges: vault.edges,
+                    indexes: Vec::new(),
+                };
+                (name, vault)
+            })
+            .collect();
+        GraphsSnapshotV3 {
+            version: VERSION_V3,
+            label: snapshot.label,
+            stats: snapshot.stats,
+            vertices: snapshot.vertices,
+            vaults,
+        }
+    }
+}
+
+impl From<GraphsSnapshotV3> for GraphsSnapshot {
+    /// The vaults are migrated without schemas
+    fn from(snapshot: GraphsSnapshotV3) -> Self {
+        let vaults = snapshot
+            .vaults
+            .into_iter()
+            .map(|(name, vault)| {
+                let vault = VaultSnapshot {
+                    vertices: vault.vertices,
+                    edges: vault.edges,
+                    indexes: vault.indexes,
+                    schema: None,
+                };
+                (name, vault)
+            })
+            .collect();
+        GraphsSnapshot {
+            label: snapshot.label,
+            stats: snapshot.stats,
+            vertices: snapshot.vertices,
+            vaults,
+        }
+    }
+}
+
+impl From<GraphsSnapshotV4> for GraphsSnapshot {
+    /// The version of the format is dropped, kept on the header
+    fn from(snapshot: GraphsSnapshotV4) -> Self {
+        GraphsSnapshot {
+            label: snapshot.label,
+            stats: snapshot.stats,
+            vertices: snapshot.vertices,
+            vaults: snapshot.vaults,
+        }
+    }
+}
+
+/// Decodes the whole bytes as the format,
+/// so a previous format is not mistaken for another one
+pub(super) fn decode_exactly<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+        .deserialize(bytes)
+        .ok()
+}
+
+/// Decodes the format persisted with a version on the header,
+/// migrating it to the current one
+pub(super) fn decode_version(version: u32, bytes: &[u8]) -> Result<GraphsSnapshot, Box<dyn Error>> {
+    match version {
+        SNAPSHOT_VERSION => Ok(bincode::deserialize(bytes)?),
+        VERSION_V4 => {
+            let snapshot: GraphsSnapshotV4 = bincode::deserialize(bytes)?;
+            info!(
+                "Migrating Graphs {} with the version on the body",
+                snapshot.label
+            );
+            Ok(snapshot.into())
+        }
+        VERSION_V3 => {
+            let snapshot: GraphsSnapshotV3 = bincode::deserialize(bytes)?;
+            info!("Migrating Graphs {} without schemas", snapshot.label);
+            Ok(snapshot.into())
+        }
+        VERSION_V2 => {
+            let snapshot: GraphsSnapshotV2 = bincode::deserialize(bytes)?;
+            info!("Migrating Graphs {} without indexes", snapshot.label);
+            Ok(GraphsSnapshotV3::from(snapshot).into())
+        }
+        _ => Err(GruPHstError::SnapshotUnsupportedVersion(version).into()),
+    }
+}
+
+/// Tries to decode any previous format without version,
+/// migrating it to the current one
+pub(super) fn decode_unversioned(bytes: &[u8]) -> Option<GraphsSnapshot> {
+    if let Some(snapshot) = decode_exactly::<GraphsSnapshotV4>(bytes) {
+        if snapshot.version == VERSION_V4 {
+            info!(
+                "Migrating Graphs {} with the version on the body",
+                snapshot.label
+            );
+            return Some(snapshot.into());
+        }
+    }
+    if let Some(snapshot) = decode_exactly::<GraphsSnapshotV3>(bytes) {
+        if snapshot.version == VERSION_V3 {
+            info!("Migrating Graphs {} without schemas", snapshot.label);
+            return Some(snapshot.into());
+        }
+    }
+    if let Some(snapshot) = decode_exactly::<GraphsSnapshotV2>(bytes) {
+        if snapshot.version == VERSION_V2 {
+            info!("Migrating Graphs {} without indexes", snapshot.label);
+            return Some(GraphsSnapshotV3::from(snapshot).into());
+        }
+    }
+    if let Some(snapshot) = decode_exactly::<GraphsSnapshotV1>(bytes) {
+        info!(
+            "Migrating Graphs {} with stringly-typed attributes",
+            snapshot.label
+        );
+        return Some(GraphsSnapshotV3::from(GraphsSnapshotV2::from(snapshot)).into());
+    }
+    let graphs = match decode_exactly::<GraphsV0_16>(bytes) {
+        Some(graphs) => graphs,
+        None => decode_exactly::<GraphsV0_12>(bytes)?.into(),
+    };
+    info!(
+        "Migrating Graphs {} persisted by a previous release",
+        graphs.label
+    );
+    let snapshot = GraphsSnapshotV2::from(GraphsSnapshotV1::from(graphs));
+    Some(GraphsSnapshotV3::from(snapshot).into())
+}
@@ -0,0 +1,381 @@
+//! Serialization format for Graphs
+//!
+//! Vertices are shared between edges through a VertexRef,
+//! so every vertex is written once and the edges, and the vertices
+//! of every vault, only keep the id of the vertex.
+//! On deserialization the edges are linked again to the same shared vertex.
+//! Files persisted with a previous format, also by previous releases,
+//! are migrated on load.
+//!
+//! Persisted files start with a header: the magic number, the version
+//! of the format, the compression codec, the cipher and the CRC32 checksum
+//! of the serialized Graphs as written,
+//! so a corrupted or foreign file is never mistaken for a Graphs.
+//! The manifest of a store is written with the same header,
+//! with its own magic number.
+//! The serialized Graphs is compressed first and then encrypted.
+
+use std::{collections::HashMap, error::Error, fs};
+
+use log::warn;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    attributes::AttrValue,
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{
+        adjacency::Adjacency,
+        auto_persist::AutoPersistSlot,
+        index::{IndexDef, VaultIndexes},
+        memory::{MemorySlot, VaultRef},
+        schema::Schema,
+        Graphs,
+    },
+    graphs_stats::GraphsStats,
+    vertex::{Vertex, Vertex_},
+};
+
+mod compression;
+mod encryption;
+mod migration;
+pub(crate) mod size;
+
+pub use compression::Compression;
+pub use encryption::EncryptionKey;
+
+/// Current version of the format, only written on the header
+const SNAPSHOT_VERSION: u32 = 5;
+
+/// First bytes of a persisted file
+const MAGIC: &[u8; 8] = b"GRUPHST\0";
+
+/// Magic number, version, compression codec, cipher and checksum
+const HEADER_LEN: usize = MAGIC.len() + 2 + 1 + 1 + 4;
+
+/// Persisted representation of an Edge
+#[derive(Debug, Serialize, Deserialize)]
+struct EdgeSnapshot {
+    id: String,
+    relation: String,
+    /// id of the "from" vertex
+    from: String,
+    /// id of the "to" vertex
+    to: String,
+    attr: HashMap<String, AttrValue>,
+}
+
+/// Persisted representation of a vault
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultSnapshot {
+    /// ids of the vertices registered on the vault
+    vertices: Vec<String>,
+    edges: Vec<EdgeSnapshot>,
+    /// definitions of the indexes, built again on load
+    indexes: Vec<IndexDef>,
+    schema: Option<Schema>,
+}
+
+/// Persisted representation of the Graphs
+#[derive(Debug, Serialize, Deserialize)]
+struct GraphsSnapshot {
+    label: String,
+    stats: GraphsStats,
+    /// every vertex of the Graphs, only once
+    vertices: Vec<Vertex_>,
+    vaults: HashMap<String, VaultSnapshot>,
+}
+
+/// Builds the snapshot of a Graphs vault by vault,
+/// a vertex shared by several vaults is written once
+/// with the data of the first vault that adds it
+struct SnapshotBuilder {
+    label: String,
+    stats: GraphsStats,
+    vertices: HashMap<String, Vertex_>,
+    vaults: HashMap<String, VaultSnapshot>,
+}
+
+impl SnapshotBuilder {
+    fn new(graphs: &Graphs) -> Self {
+        SnapshotBuilder {
+            label: graphs.label.clone(),
+            stats: graphs.stats.clone(),
+            vertices: HashMap::new(),
+            vaults: HashMap::new(),
+        }
+    }
+
+    fn add_vault(&mut self, vault_name: &str, in_memory: &VaultRef) {
+        let mut vault = VaultSnapshot::default();
+        for (id, vertex) in in_memory.vertices.iter() {
+            self.vertices
+                .entry(id.clone())
+                .or_insert_with(|| vertex.read().clone());
+            vault.vertices.push(id.clone());
+        }
+        for edge in in_memory.edges.iter() {
+            let from = edge.get_from_vertex();
+            let to = edge.get_to_vertex();
+            let (from_id, to_id) = (from.get_id(), to.get_id());
+            self.vertices
+                .entry(from_id.clone())
+                .or_insert_with(|| from.read().clone());
+            self.vertices
+                .entry(to_id.clone())
+                .or_insert_with(|| to.read().clone());
+            vault.edges.push(EdgeSnapshot {
+                id: edge.get_id(),
+                relation: edge.get_relation(),
+                from: from_id,
+                to: to_id,
+                attr: edge.attr.clone(),
+            });
+        }
+        vault.indexes = in_memory.indexes.defs();
+        vault.schema = in_memory.schema.cloned();
+        self.vaults.insert(vault_name.to_string(), vault);
+    }
+
+    fn build(self) -> GraphsSnapshot {
+        GraphsSnapshot {
+            label: self.label,
+            stats: self.stats,
+            vertices: self.vertices.into_values().collect(),
+            vaults: self.vaults,
+        }
+    }
+}
+
+/// Snapshot of the vaults in memory
+impl From<&Graphs> for GraphsSnapshot {
+    fn from(graphs: &Graphs) -> Self {
+        let mut builder = SnapshotBuilder::new(graphs);
+        for (vault_name, in_memory) in graphs.vaults_in_memory() {
+            builder.add_vault(vault_name, &in_memory);
+        }
+        builder.build()
+    }
+}
+
+impl TryFrom<GraphsSnapshot> for Graphs {
+    type Error = GruPHstError;
+
+    fn try_from(snapshot: GraphsSnapshot) -> Result<Self, Self::Error> {
+        let mut shared: HashMap<String, Vertex> = HashMap::new();
+        for vrtx in snapshot.vertices {
+            let vertex = Vertex {
+                vrtx: vrtx.into_ref(),
+            };
+            shared.insert(vertex.get_id(), vertex);
+        }
+        let get_shared = |id: &str| -> Result<Vertex, GruPHstError> {
+            shared.get(id).cloned().ok_or(GruPHstError::VertexNotFound)
+        };
+        let mut vault: HashMap<String, Vec<Edge>> = HashMap::new();
+        let mut vertices: HashMap<String, HashMap<String, Vertex>> = HashMap::new();
+        let mut adjacency: HashMap<String, Adjacency> = HashMap::new();
+        let mut indexes: HashMap<String, VaultIndexes> = HashMap::new();
+        let mut schemas: HashMap<String, Schema> = HashMap::new();
+        for (vault_name, vault_snapshot) in snapshot.vaults {
+            let mut registered: HashMap<String, Vertex> = HashMap::new();
+            for id in vault_snapshot.vertices {
+                registered.insert(id.clone(), get_shared(&id)?);
+            }
+            let mut edges: Vec<Edge> = Vec::with_capacity(vault_snapshot.edges.len());
+            for edge_snapshot in vault_snapshot.edges {
+                let from = get_shared(&edge_snapshot.from)?;
+                let to = get_shared(&edge_snapshot.to)?;
+                let mut edge = Edge::create(&from, &edge_snapshot.relation, &to);
+                edge.id = edge_snapshot.id;
+                edge.attr = edge_snapshot.attr;
+                registered.entry(from.get_id()).or_insert(from);
+                registered.entry(to.get_id()).or_insert(to);
+                edges.push(edge);
+            }
+            adjacency.insert(vault_name.clone(), Adjacency::build(&edges));
+            let built = VaultIndexes::build(&vault_snapshot.indexes, &registered, &edges);
+            indexes.insert(vault_name.clone(), built);
+            if let Some(schema) = vault_snapshot.schema {
+                schemas.insert(vault_name.clone(), schema);
+            }
+            vault.insert(vault_name.clone(), edges);
+            vertices.insert(vault_name, registered);
+        }
+        let mut graphs = Graphs {
+            vault,
+            vertices,
+            adjacency,
+            indexes,
+            schemas,
+            label: snapshot.label,
+            stats: snapshot.stats,
+            wal: Default::default(),
+            persist: AutoPersistSlot::from_config(),
+            memory: MemorySlot::from_config(),
+            store: Default::default(),
+            transactions: Default::default(),
+            key: None,
+        };
+        let vault_names: Vec<String> = graphs.vault.keys().cloned().collect();
+        for vault_name in vault_names {
+            graphs.track_vault(&vault_name);
+        }
+        graphs.measure_mem();
+        Ok(graphs)
+    }
+}
+
+impl Serialize for Graphs {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        GraphsSnapshot::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Graphs {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let snapshot = GraphsSnapshot::deserialize(deserializer)?;
+        Graphs::try_from(snapshot).map_err(de::Error::custom)
+    }
+}
+
+impl Graphs {
+    /// Snapshot of the whole Graphs, the vaults on disk not loaded
+    /// are decoded from their files one at a time
+    fn snapshot_with_evicted_vaults(&self) -> Result<GraphsSnapshot, Box<dyn Error>> {
+        let mut builder = SnapshotBuilder::new(self);
+        for (vault_name, in_memory) in self.vaults_in_memory() {
+            builder.add_vault(vault_name, &in_memory);
+        }
+        for vault_name in self.get_evicted_vaults() {
+            let Some(path) = self.evicted_path(&vault_name) else {
+                continue;
+            };
+            let decoded = Graphs::decode(&fs::read(path)?, self.key.as_ref())?;
+            if let Some(on_disk) = decoded.vault_ref(&vault_name)? {
+                builder.add_vault(&vault_name, &on_disk);
+            }
+        }
+        Ok(builder.build())
+    }
+
+    /// Encodes the Graphs to persist it, with the header,
+    /// including the vaults evicted to disk
+    pub(crate) fn encode(
+        &self,
+        compression: Compression,
+        key: Option<&EncryptionKey>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let serialized = bincode::serialize(&self.snapshot_with_evicted_vaults()?)?;
+        encode_with_header(MAGIC, SNAPSHOT_VERSION, serialized, compression, key)
+    }
+
+    /// Decodes a persisted Graphs, checking the header.
+    /// Files persisted before the header existed are decoded as they are,
+    /// migrating them when they were persisted with a previous format
+    pub(crate) fn decode(
+        bytes: &[u8],
+        key: Option<&EncryptionKey>,
+    ) -> Result<Graphs, Box<dyn Error>> {
+        if !bytes.starts_with(MAGIC) {
+            return Graphs::decode_headerless(bytes);
+        }
+        let snapshot = decode_with_header(MAGIC, bytes, key, |version, body| {
+            migration::decode_version(version, body)
+                .inspect_err(|err| warn!("Snapshot with format version {}: {}", version, err))
+        })?;
+        Ok(Graphs::try_from(snapshot)?)
+    }
+
+    fn decode_headerless(bytes: &[u8]) -> Result<Graphs, Box<dyn Error>> {
+        if let Some(snapshot) = migration::decode_exactly::<GraphsSnapshot>(bytes) {
+            return Ok(Graphs::try_from(snapshot)?);
+        }
+        match migration::decode_unversioned(bytes) {
+            Some(snapshot) => Ok(Graphs::try_from(snapshot)?),
+            None => {
+                warn!("File is not a persisted Graphs");
+                Err(GruPHstError::SnapshotBadMagic.into())
+            }
+        }
+    }
+}
+
+/// Writes the header, with the magic number and the version of the format,
+/// and the serialized body, compressed and then encrypted when there is a key
+pub(crate) fn encode_with_header(
+    magic: &[u8; 8],
+    version: u32,
+    serialized: Vec<u8>,
+    compression: Compression,
+    key: Option<&EncryptionKey>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut body = compression.compress(serialized);
+    let cipher = match key {
+        Some(key) => {
+            body = encryption::encrypt(key, &body)?;
+            encryption::CHACHA20_POLY1305
+        }
+        None => encryption::NONE,
+    };
+    let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+    bytes.extend_from_slice(magic);
+    bytes.extend_from_slice(&(version as u16).to_le_bytes());
+    bytes.push(compression.codec());
+    bytes.push(cipher);
+    bytes.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Checks the header, with the magic number, and decodes the body,
+/// decrypted and decompressed, with the version of its format
+pub(crate) fn decode_with_header<T>(
+    magic: &[u8; 8],
+    bytes: &[u8],
+    key: Option<&EncryptionKey>,
+    decode: impl FnOnce(u32, &[u8]) -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    if !bytes.starts_with(magic) {
+        return Err(GruPHstError::SnapshotBadMagic.into());
+    }
+    let Some(header) = bytes.get(..HEADER_LEN) else {
+        warn!("Snapshot truncated on the header");
+        return Err(GruPHstError::SnapshotChecksumMismatch.into());
+    };
+    let version = u16::from_le_bytes(header[8..10].try_into()?) as u32;
+    let codec = header[10];
+    let cipher = header[11];
+    let checksum = u32::from_le_bytes(header[12..16].try_into()?);
+    let body = &bytes[HEADER_LEN..];
+    if crc32fast::hash(body) != checksum {
+        warn!("Snapshot checksum mismatch");
+        return Err(GruPHstError::SnapshotChecksumMismatch.into());
+    }
+    let decrypted;
+    let body = match (cipher, key) {
+        (encryption::NONE, _) => body,
+        (encryption::CHACHA20_POLY1305, Some(key)) => {
+            decrypted = encryption::decrypt(key, body)
+                .inspect_err(|err| warn!("Snapshot not decrypted: {}", err))?;
+            &decrypted
+        }
+        (encryption::CHACHA20_POLY1305, None) => {
+            warn!("Snapshot encrypted, loaded without key");
+            return Err(GruPHstError::SnapshotEncrypted.into());
+        }
+        _ => {
+            warn!("Snapshot with unsupported cipher {}", cipher);
+            return Err(GruPHstError::SnapshotUnsupportedCipher(cipher).into());
+        }
+    };
+    let body = compression::decompress(codec, body)
+        .inspect_err(|err| warn!("Snapshot with compression codec {}: {}", codec, err))?;
+    decode(version, &body)
+}
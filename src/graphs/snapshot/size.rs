@@ -0,0 +1,58 @@
+//! Size of the elements of a Graphs once serialized,
+//! for the running estimate of the memory in use
+//!
+//! Follows the layout of GraphsSnapshot, with the fixed-size integers
+//! of bincode, so a vault with its vertices and edges is measured
+//! without serializing it. A vertex is measured for every vault
+//! it is registered on, while it is serialized once.
+
+use crate::{
+    edge::Edge,
+    graphs::{schema::Schema, Graphs},
+    graphs_stats::GraphsStats,
+    util::serialized_size,
+    vertex::Vertex,
+};
+
+/// Length prefix of strings, sequences and maps
+const LEN: usize = 8;
+
+fn string(value: &str) -> usize {
+    LEN + value.len()
+}
+
+/// Label, stats and the length of the vertices and the vaults
+pub(crate) fn graphs(graphs: &Graphs) -> usize {
+    string(&graphs.label) + serialized_size::<GraphsStats>(&graphs.stats) + LEN + LEN
+}
+
+/// Name, the length of the vertices, the edges and the indexes
+/// and the tag of the schema of an empty vault
+pub(crate) fn vault(name: &str) -> usize {
+    string(name) + LEN + LEN + LEN + 1
+}
+
+/// The schema of a vault
+pub(crate) fn schema(schema: &Schema) -> usize {
+    serialized_size(schema)
+}
+
+/// A vertex registered on a vault, with its id on the vault
+pub(crate) fn vertex(vertex: &Vertex) -> usize {
+    let id = vertex.get_id();
+    serialized_size(&*vertex.read()) + string(&id)
+}
+
+/// An edge, with the ids of its vertices
+pub(crate) fn edge(edge: &Edge) -> usize {
+    string(&edge.id)
+        + string(&edge.relation)
+        + string(&edge.get_from_vertex().get_id())
+        + string(&edge.get_to_vertex().get_id())
+        + serialized_size(&edge.attr)
+}
+
+/// An edge with its vertices, as if they were not registered yet
+pub(crate) fn edge_with_vertices(edge: &Edge) -> usize {
+    self::edge(edge) + vertex(&edge.get_from_vertex()) + vertex(&edge.get_to_vertex())
+}
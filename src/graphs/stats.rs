@@ -20,8 +20,9 @@ impl Graphs {
     /// Returns an array with the unique relations in the whole Graphs
     pub fn uniq_relations(&self) -> Vec<String> {
         let mut uniq_rel = Vec::new();
-        for edges in self.vault.values() {
-            for edge in edges.iter() {
+        // a vault on disk that could not be loaded is logged and skipped
+        for (_vault_name, vault) in self.vaults_in_graphs().flatten() {
+            for edge in vault.edges.iter() {
                 uniq_rel.push(edge.get_relation());
             }
             uniq_rel.sort();
@@ -30,18 +31,19 @@ impl Graphs {
         uniq_rel
     }
 
-    /// Retrieves the length of the Graphs for whole vault
+    /// Retrieves the length of the Graphs for whole vault,
+    /// the vaults on disk are loaded to count them
     pub fn len(&self) -> usize {
         let mut length = 0;
-        for (_graphs_name, edges) in self.vault.iter() {
-            length += edges.len();
+        for (_graphs_name, vault) in self.vaults_in_graphs().flatten() {
+            length += vault.edges.len();
         }
         length
     }
 
     /// Retrieves the length of vault
     pub fn len_graphs(&self) -> usize {
-        self.vault.len()
+        self.vault.len() + self.vaults_on_disk().len()
     }
 
     /// Checks if the Graphs vault is empty
@@ -49,7 +51,9 @@ impl Graphs {
         self.len() == 0
     }
 
-    /// function to retrieve memory usage by graphs
+    /// function to retrieve memory usage by graphs,
+    /// exact but serializing the whole Graphs;
+    /// `get_mem_estimate` retrieves it without serializing
     pub fn get_mem(&self) -> Result<usize, &'static str> {
         let bytes = bincode::serialize(self).unwrap();
         Ok(bytes.len())
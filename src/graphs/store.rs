@@ -0,0 +1,283 @@
+//! Store of a Graphs with every vault on its own file
+//!
+//! A store is a directory with a manifest, `vaults.grphst`, holding
+//! the label, the stats and the file of every vault, and a file per vault
+//! with the same format as a saved Graphs labeled with the vault name.
+//! The manifest starts with the same header as a saved Graphs,
+//! with its own magic number and version.
+//! Opening a store only reads the manifest, every vault is loaded
+//! on the first access to it, also by the queries over every vault.
+//! Vaults can be saved, and unloaded, on their own; the manifest
+//! is written again on every save and the files of the deleted
+//! vaults removed.
+//! A store is not encrypted, so a Graphs with the key of
+//! `save_encrypted` or `load_encrypted` is not saved on one,
+//! failing with SnapshotEncryptionFailed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::get_compression,
+    errors::GruPHstError,
+    graphs::{
+        auto_persist::AutoPersistSlot,
+        memory::MemorySlot,
+        persistence::write_atomically,
+        snapshot::{decode_with_header, encode_with_header},
+        Graphs,
+    },
+    graphs_stats::GraphsStats,
+};
+
+/// File name of the manifest on the store directory
+const MANIFEST: &str = "vaults.grphst";
+
+/// First bytes of a manifest
+const MAGIC: &[u8; 8] = b"GRPHSTV\0";
+
+/// Current version of the format of the manifest
+const MANIFEST_VERSION: u32 = 1;
+
+/// Persisted manifest of a store
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    label: String,
+    stats: GraphsStats,
+    /// file name of every vault, on the store directory
+    files: HashMap<String, String>,
+}
+
+/// Store of a Graphs, when opened or saved on one
+#[derive(Debug, Default)]
+pub(crate) struct StoreSlot {
+    dir: Option<PathBuf>,
+    /// file name of every vault saved on the store
+    files: HashMap<String, String>,
+}
+
+/// A cloned Graphs is detached from the store
+impl Clone for StoreSlot {
+    fn clone(&self) -> Self {
+        StoreSlot::default()
+    }
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest, Box<dyn Error>> {
+    let bytes = fs::read(dir.join(MANIFEST))?;
+    decode_with_header(MAGIC, &bytes, None, |version, body| match version {
+        MANIFEST_VERSION => Ok(bincode::deserialize(body)?),
+        _ => {
+            warn!("Store manifest with format version {}", version);
+            Err(GruPHstError::SnapshotUnsupportedVersion(version).into())
+        }
+    })
+}
+
+impl Graphs {
+    /// Opens a Graphs saved on a store directory with `save_store`,
+    /// only reading its manifest; every vault is loaded
+    /// on the first access to it
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("shire");
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("Rivendell")), Some("rivendell")).unwrap();
+    /// graphs.save_store("./middle-earth-store").unwrap();
+    ///
+    /// let opened = Graphs::open_store("./middle-earth-store").unwrap();
+    /// assert!(opened.is_evicted("rivendell"));
+    /// assert_eq!(opened.get_edges(Some("rivendell")).unwrap().len(), 1);
+    /// assert!(!opened.is_evicted("rivendell"));
+    /// assert!(opened.is_evicted("shire"));
+    /// # std::fs::remove_dir_all("./middle-earth-store").unwrap();
+    /// ```
+    pub fn open_store(dir: &str) -> Result<Graphs, Box<dyn Error>> {
+        let dir = PathBuf::from(dir);
+        let manifest = read_manifest(&dir)?;
+        let mut graphs = Graphs {
+            vault: HashMap::new(),
+            vertices: HashMap::new(),
+            adjacency: HashMap::new(),
+            indexes: HashMap::new(),
+            schemas: HashMap::new(),
+            label: manifest.label,
+            stats: manifest.stats,
+            wal: Default::default(),
+            persist: AutoPersistSlot::from_config(),
+            memory: MemorySlot::from_config(),
+            store: StoreSlot::default(),
+            transactions: Default::default(),
+            key: None,
+        };
+        for (vault_name, file_name) in manifest.files.iter() {
+            graphs.track_stored_vault(vault_name, dir.join(file_name));
+        }
+        graphs.measure_mem();
+        info!(
+            "Graphs opened at store {} with {} vaults",
+            dir.display(),
+            manifest.files.len()
+        );
+        graphs.store = StoreSlot {
+            dir: Some(dir),
+            files: manifest.files,
+        };
+        Ok(graphs)
+    }
+
+    /// Saves every vault of the Graphs on its own file of a store directory,
+    /// the Graphs keeps saving and unloading its vaults on it
+    pub fn save_store(&mut self, dir: &str) -> Result<(), Box<dyn Error>> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+        if self.store.dir.as_ref() != Some(&dir) {
+            // the files of a previous store on the directory are replaced
+            let files = read_manifest(&dir).map_or_else(|_| HashMap::new(), |m| m.files);
+            self.store = StoreSlot {
+                dir: Some(dir),
+                files,
+            };
+        }
+        let mut vault_names: Vec<String> = self.vault.keys().cloned().collect();
+        vault_names.extend(self.vaults_on_disk());
+        for vault_name in vault_names {
+            self.write_stored_vault(&vault_name)?;
+        }
+        self.write_manifest()
+    }
+
+    /// Saves a vault on its own file of the store the Graphs
+    /// was opened or saved on, with the manifest
+    pub fn save_vault(&mut self, vault_name: &str) -> Result<(), Box<dyn Error>> {
+        if !self.has_store() {
+            warn!("Graphs {} without store to save {}", self.label, vault_name);
+            return Err(GruPHstError::NoVaultStore.into());
+        }
+        if !self.vault.contains_key(vault_name) && self.evicted_path(vault_name).is_none() {
+            return Err(GruPHstError::VaultNotExists(vault_name.to_string()).into());
+        }
+        self.write_stored_vault(vault_name)?;
+        self.write_manifest()
+    }
+
+    /// Saves a vault, like `save_vault`, and removes it from memory;
+    /// it is loaded again on the next access to it
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("shire");
+    /// graphs.insert("mordor").unwrap();
+    /// graphs.add_edge(&Edge::create(&Vertex::new("Sauron"), "created", &Vertex::new("One Ring")), None).unwrap();
+    /// graphs.save_store("./mordor-store").unwrap();
+    ///
+    /// graphs.unload_vault("mordor").unwrap();
+    /// assert!(graphs.is_evicted("mordor"));
+    /// assert_eq!(graphs.get_edges(Some("mordor")).unwrap().len(), 1);
+    /// # std::fs::remove_dir_all("./mordor-store").unwrap();
+    /// ```
+    pub fn unload_vault(&mut self, vault_name: &str) -> Result<(), Box<dyn Error>> {
+        self.save_vault(vault_name)?;
+        if !self.vault.contains_key(vault_name) {
+            self.release_loaded(vault_name);
+            return Ok(());
+        }
+        let path = self.stored_path(vault_name);
+        self.unload_to(vault_name, path, false);
+        info!("Vault {} unloaded", vault_name);
+        Ok(())
+    }
+
+    /// Checks if the Graphs was opened or saved on a store
+    pub(crate) fn has_store(&self) -> bool {
+        self.store.dir.is_some()
+    }
+
+    /// Fails with SnapshotEncryptionFailed for a Graphs with a key,
+    /// the files of a store are written in plaintext
+    fn refuse_encrypted(&self) -> Result<(), GruPHstError> {
+        if self.key.is_some() {
+            warn!("Graphs {} with a key not saved on a store", self.label);
+            return Err(GruPHstError::SnapshotEncryptionFailed);
+        }
+        Ok(())
+    }
+
+    /// Path of the file of a vault on the store, named when not saved yet
+    fn stored_path(&mut self, vault_name: &str) -> PathBuf {
+        let file_name = self
+            .store
+            .files
+            .entry(vault_name.to_string())
+            .or_insert_with(|| format!("{}.vault.grphst", uuid::Uuid::new_v4()));
+        self.store.dir.as_ref().unwrap().join(file_name)
+    }
+
+    /// Writes a vault on its file of the store, a vault on disk
+    /// is copied when it is not on the store yet
+    fn write_stored_vault(&mut self, vault_name: &str) -> Result<(), Box<dyn Error>> {
+        self.refuse_encrypted()?;
+        let path = self.stored_path(vault_name);
+        let bytes = match self.evicted_path(vault_name) {
+            None => self.encode_vault(vault_name)?,
+            Some(evicted) if evicted == path => return Ok(()),
+            Some(evicted) => fs::read(evicted)?,
+        };
+        write_atomically(&path.to_string_lossy(), &bytes)?;
+        if self.evicted_path(vault_name).is_some() {
+            // a vault loaded by a read is read again from the store
+            self.track_stored_vault(vault_name, path);
+        }
+        Ok(())
+    }
+
+    /// Writes the manifest with the vaults saved on the store,
+    /// removing the files of the deleted ones
+    fn write_manifest(&mut self) -> Result<(), Box<dyn Error>> {
+        self.refuse_encrypted()?;
+        let dir = self.store.dir.clone().unwrap();
+        let mut vault_names: HashSet<String> = self.vault.keys().cloned().collect();
+        vault_names.extend(self.vaults_on_disk());
+        let mut deleted = Vec::new();
+        self.store.files.retain(|vault_name, file_name| {
+            let exists = vault_names.contains(vault_name);
+            if !exists {
+                deleted.push(file_name.clone());
+            }
+            exists
+        });
+        let manifest = Manifest {
+            label: self.label.clone(),
+            stats: self.stats.clone(),
+            files: self.store.files.clone(),
+        };
+        let body = bincode::serialize(&manifest)?;
+        let bytes = encode_with_header(MAGIC, MANIFEST_VERSION, body, get_compression(), None)?;
+        write_atomically(&dir.join(MANIFEST).to_string_lossy(), &bytes)?;
+        for file_name in deleted {
+            if let Err(err) = fs::remove_file(dir.join(&file_name)) {
+                warn!("Deleted vault file {} not removed: {}", file_name, err);
+            }
+        }
+        info!(
+            "Graphs {} saved at store {} with {} vaults",
+            self.label,
+            dir.display(),
+            manifest.files.len()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//! Transactions over a Graphs
+//!
+//! A transaction runs a batch of changes that all succeed or all fail.
+//! Every vault is copied before the first change of the transaction on it,
+//! and the data of every vertex is recorded on an undo log before the first
+//! change to it, also through a Vertex out of the Graphs; when any of the
+//! changes fails the vaults and the vertices are restored.
+//! The changes are written on the write-ahead log as a single record,
+//! and auto-persisted, once the transaction is committed; when the record
+//! could not be written the transaction is rolled back.
+
+use std::{collections::HashMap, fmt};
+
+use log::{info, warn};
+
+use crate::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{
+        adjacency::Adjacency, index::VaultIndexes, memory::MemorySlot, schema::Schema, Graphs,
+    },
+    graphs_stats::GraphsStats,
+    vertex::{begin_undo, commit_undo, take_undo, Vertex},
+};
+
+/// A vault before the first change of a transaction on it
+struct VaultSavepoint {
+    edges: Vec<Edge>,
+    vertices: HashMap<String, Vertex>,
+    adjacency: Adjacency,
+    indexes: VaultIndexes,
+    /// vertices changed out of the Graphs, not indexed again yet
+    stale: Vec<String>,
+    schema: Option<Schema>,
+}
+
+/// Vaults changed by every running transaction, the outermost first,
+/// None for the ones that were not in memory
+#[derive(Default)]
+pub(crate) struct TransactionSlot(Vec<HashMap<String, Option<VaultSavepoint>>>);
+
+/// A cloned Graphs is detached from the running transactions
+impl Clone for TransactionSlot {
+    fn clone(&self) -> Self {
+        TransactionSlot::default()
+    }
+}
+
+impl fmt::Debug for TransactionSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} running", self.0.len())
+    }
+}
+
+/// State of a Graphs before a transaction
+struct Savepoint {
+    label: String,
+    stats: GraphsStats,
+    memory: MemorySlot,
+    /// mutations pending to auto-persist
+    pending: usize,
+    /// records staged on the write-ahead log by an outer transaction
+    staged: usize,
+    /// false for a transaction inside another one
+    outermost: bool,
+}
+
+impl Graphs {
+    /// Runs a batch of changes on the Graphs that all succeed or all fail;
+    /// when the changes return an error the Graphs, and the vertices
+    /// shared with it, are rolled back and the error returned.
+    /// Only the vaults changed are copied, before the first change on them,
+    /// so the cost grows with them
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, errors::GruPHstError, graphs::Graphs, vertex::Vertex};
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let mut frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &Vertex::new("Sam")), None).unwrap();
+    ///
+    /// let result = graphs.transaction(|tx| {
+    ///     tx.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("Bag End")), None)?;
+    ///     frodo.set_attr("ring bearer", true);
+    ///     tx.delete_edge_by_id("not an edge".to_string(), None)
+    /// });
+    /// assert_eq!(result, Err(GruPHstError::EdgeNotFound));
+    /// assert_eq!(graphs.len(), 1);
+    /// assert!(frodo.get_attr("ring bearer").is_err());
+    /// ```
+    pub fn transaction<T, F>(&mut self, changes: F) -> Result<T, GruPHstError>
+    where
+        F: FnOnce(&mut Graphs) -> Result<T, GruPHstError>,
+    {
+        let savepoint = self.savepoint();
+        match changes(self) {
+            Ok(result) => {
+                if let Err(err) = self.commit_wal(savepoint.outermost) {
+                    warn!("Transaction on Graphs {} rolled back: {}", self.label, err);
+                    self.rollback(savepoint);
+                    return Err(err);
+                }
+                self.release_savepoint();
+                self.compact_if_due();
+                self.release_auto_persist(None, !savepoint.outermost);
+                info!("Transaction on Graphs {} committed", self.label);
+                Ok(result)
+            }
+            Err(err) => {
+                warn!("Transaction on Graphs {} rolled back: {}", self.label, err);
+                self.rollback(savepoint);
+                Err(err)
+            }
+        }
+    }
+
+    fn savepoint(&mut self) -> Savepoint {
+        let staged = self.stage_wal();
+        let (pending, held) = self.hold_auto_persist();
+        self.transactions.0.push(HashMap::new());
+        begin_undo();
+        Savepoint {
+            label: self.label.clone(),
+            stats: self.stats.clone(),
+            memory: self.memory.clone(),
+            pending,
+            staged,
+            outermost: !held,
+        }
+    }
+
+    /// Keeps a vault as it is before the first change
+    /// of the running transaction on it
+    pub(crate) fn savepoint_vault(&mut self, vault_name: &str) {
+        match self.transactions.0.last() {
+            Some(changed) if !changed.contains_key(vault_name) => (),
+            _ => return,
+        }
+        let saved = self.vault.get(vault_name).map(|edges| VaultSavepoint {
+            edges: edges.clone(),
+            vertices: self.vertices.get(vault_name).cloned().unwrap_or_default(),
+            adjacency: self.adjacency.get(vault_name).cloned().unwrap_or_default(),
+            indexes: self.indexes.get(vault_name).cloned().unwrap_or_default(),
+            stale: self
+                .indexes
+                .get(vault_name)
+                .map_or_else(Vec::new, VaultIndexes::stale_vertices),
+            schema: self.schemas.get(vault_name).cloned(),
+        });
+        if let Some(changed) = self.transactions.0.last_mut() {
+            changed.insert(vault_name.to_string(), saved);
+        }
+    }
+
+    /// The vaults kept by a committed transaction
+    /// are kept by the outer one when there is one
+    fn release_savepoint(&mut self) {
+        let committed = self.transactions.0.pop().unwrap_or_default();
+        if let Some(outer) = self.transactions.0.last_mut() {
+            for (vault_name, saved) in committed {
+                outer.entry(vault_name).or_insert(saved);
+            }
+        }
+        commit_undo();
+    }
+
+    fn rollback(&mut self, savepoint: Savepoint) {
+        let changed = self.transactions.0.pop().unwrap_or_default();
+        for (vault_name, saved) in changed {
+            match saved {
+                Some(saved) => {
+                    self.vault.insert(vault_name.clone(), saved.edges);
+                    self.vertices.insert(vault_name.clone(), saved.vertices);
+                    self.adjacency.insert(vault_name.clone(), saved.adjacency);
+                    saved.indexes.mark_stale(saved.stale);
+                    self.indexes.insert(vault_name.clone(), saved.indexes);
+                    match saved.schema {
+                        Some(schema) => self.schemas.insert(vault_name, schema),
+                        None => self.schemas.remove(&vault_name),
+                    };
+                }
+                None => {
+                    self.vault.remove(&vault_name);
+                    self.vertices.remove(&vault_name);
+                    self.adjacency.remove(&vault_name);
+                    self.indexes.remove(&vault_name);
+                    self.schemas.remove(&vault_name);
+                }
+            }
+        }
+        // only the vertices of the Graphs are restored
+        for (vertex, data) in take_undo() {
+            let id = vertex.get_id();
+            if self
+                .vertices
+                .values()
+                .any(|vertices| vertices.contains_key(&id))
+            {
+                vertex.restore(data);
+            }
+        }
+        self.label = savepoint.label;
+        self.stats = savepoint.stats;
+        self.memory = savepoint.memory;
+        self.discard_wal(savepoint.staged, savepoint.outermost);
+        self.release_auto_persist(Some(savepoint.pending), !savepoint.outermost);
+    }
+}
@@ -0,0 +1,316 @@
+//! Traversal of the Graphs, breadth-first and depth-first,
+//! walking the edges from a starting vertex
+
+use std::collections::{HashSet, VecDeque};
+
+use log::warn;
+
+use crate::{edge::Edge, errors::GruPHstError, graphs::Graphs, vertex::Vertex};
+
+/// Direction to follow the edges while traversing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// from the "from" vertex to the "to" vertex
+    Out,
+    /// from the "to" vertex to the "from" vertex
+    In,
+    /// any of them
+    Both,
+}
+
+/// Order to visit the vertices while traversing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Breadth-first, all the vertices at a depth before the next depth
+    BreadthFirst,
+    /// Depth-first, as deep as possible before backtracking
+    DepthFirst,
+}
+
+/// Options for a traversal
+///
+/// # Examples
+/// ```rust
+/// use gruphst::graphs::traversal::{Direction, TraversalOptions};
+///
+/// let options = TraversalOptions::bfs()
+///     .direction(Direction::Both)
+///     .relation("friend of")
+///     .max_depth(2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TraversalOptions {
+    order: Order,
+    direction: Direction,
+    relations: Vec<String>,
+    max_depth: Option<usize>,
+}
+
+impl TraversalOptions {
+    /// Options for a breadth-first traversal following outgoing edges,
+    /// with any relation and without depth limit
+    pub fn bfs() -> Self {
+        TraversalOptions {
+            order: Order::BreadthFirst,
+            direction: Direction::Out,
+            relations: Vec::new(),
+            max_depth: None,
+        }
+    }
+
+    /// Options for a depth-first traversal following outgoing edges,
+    /// with any relation and without depth limit
+    pub fn dfs() -> Self {
+        TraversalOptions {
+            order: Order::DepthFirst,
+            ..TraversalOptions::bfs()
+        }
+    }
+
+    /// Sets the direction to follow the edges
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Adds a relation to follow, when no relation is added
+    /// any relation is followed
+    pub fn relation(mut self, relation: &str) -> Self {
+        self.relations.push(relation.to_string());
+        self
+    }
+
+    /// Sets the maximum depth to reach from the starting vertex
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Returns the order for the traversal
+    pub fn get_order(&self) -> Order {
+        self.order
+    }
+
+    /// Returns the direction to follow the edges
+    pub fn get_direction(&self) -> Direction {
+        self.direction
+    }
+
+    fn can_go_deeper(&self, depth: usize) -> bool {
+        match self.max_depth {
+            Some(max_depth) => depth < max_depth,
+            None => true,
+        }
+    }
+}
+
+impl Default for TraversalOptions {
+    fn default() -> Self {
+        TraversalOptions::bfs()
+    }
+}
+
+impl Graphs {
+    /// Traverses a vault from the vertex that matches the provided id,
+    /// returning every reached vertex only once, with its depth
+    /// and the edge used to reach it, in the order of the traversal.
+    /// For some provided vault_name or default when None
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    /// use gruphst::graphs::traversal::TraversalOptions;
+    ///
+    /// let mut graphs = Graphs::init("middle-earth");
+    /// let frodo = Vertex::new("Frodo");
+    /// let sam = Vertex::new("Sam");
+    /// graphs.add_edge(&Edge::create(&frodo, "friend of", &sam), None).unwrap();
+    /// graphs.add_edge(&Edge::create(&sam, "lives at", &Vertex::new("The Shire")), None).unwrap();
+    ///
+    /// let steps = graphs.traverse(&frodo.get_id(), &TraversalOptions::bfs(), None).unwrap();
+    /// assert_eq!(steps.len(), 2);
+    /// let (depth, vertex, edge) = &steps[1];
+    /// assert_eq!(*depth, 2);
+    /// assert_eq!(vertex.get_label(), "The Shire");
+    /// assert_eq!(edge.get_relation(), "lives at");
+    /// ```
+    pub fn traverse(
+        &self,
+        start_id: &str,
+        options: &TraversalOptions,
+        vault_name: Option<&str>,
+    ) -> Result<Vec<(usize, Vertex, Edge)>, GruPHstError> {
+        let mut steps = Vec::new();
+        self.traverse_with(start_id, options, vault_name, |depth, vertex, edge| {
+            steps.push((depth, vertex.clone(), edge.clone()));
+            true
+        })?;
+        Ok(steps)
+    }
+
+    /// Traverses all the vaults from the vertex that matches the provided id,
+    /// returning every reached vertex only once, with its depth
+    /// and the edge used to reach it, in the order of the traversal
+    pub fn traverse_in_graphs(
+        &self,
+        start_id: &str,
+        options: &TraversalOptions,
+    ) -> Result<Vec<(usize, Vertex, Edge)>, GruPHstError> {
+        let mut steps = Vec::new();
+        self.traverse_in_graphs_with(start_id, options, |depth, vertex, edge| {
+            steps.push((depth, vertex.clone(), edge.clone()));
+            true
+        })?;
+        Ok(steps)
+    }
+
+    /// Traverses a vault from the vertex that matches the provided id,
+    /// calling the visitor for every reached vertex with its depth
+    /// and the edge used to reach it; the traversal stops
+    /// when the visitor returns false.
+    /// For some provided vault_name or default when None
+    pub fn traverse_with<F>(
+        &self,
+        start_id: &str,
+        options: &TraversalOptions,
+        vault_name: Option<&str>,
+        visitor: F,
+    ) -> Result<(), GruPHstError>
+    where
+        F: FnMut(usize, &Vertex, &Edge) -> bool,
+    {
+        let vertices = self.select_vault_vertices(vault_name)?;
+        if !vertices.contains_key(start_id) {
+            warn!("Vertex with id: {} not found to traverse", start_id);
+            return Err(GruPHstError::VertexNotFound);
+        }
+        let vaults = vec![self.select_vault_label(vault_name)];
+        self.walk(start_id, options, &vaults, visitor);
+        Ok(())
+    }
+
+    /// Traverses all the vaults from the vertex that matches the provided id,
+    /// calling the visitor for every reached vertex with its depth
+    /// and the edge used to reach it; the traversal stops
+    /// when the visitor returns false
+    pub fn traverse_in_graphs_with<F>(
+        &self,
+        start_id: &str,
+        options: &TraversalOptions,
+        visitor: F,
+    ) -> Result<(), GruPHstError>
+    where
+        F: FnMut(usize, &Vertex, &Edge) -> bool,
+    {
+        let mut vaults: Vec<String> = Vec::new();
+        let mut found = false;
+        for vault in self.vaults_in_graphs() {
+            let (vault_name, vault) = vault?;
+            found |= vault.vertices.contains_key(start_id);
+            vaults.push(vault_name.clone());
+        }
+        if !found {
+            warn!(
+                "Vertex with id: {} not found in graphs to traverse",
+                start_id
+            );
+            return Err(GruPHstError::VertexNotFound);
+        }
+        vaults.sort();
+        self.walk(start_id, options, &vaults, visitor);
+        Ok(())
+    }
+
+    fn walk<F>(&self, start_id: &str, options: &TraversalOptions, vaults: &[String], mut visitor: F)
+    where
+        F: FnMut(usize, &Vertex, &Edge) -> bool,
+    {
+        let mut visited: HashSet<String> = HashSet::new();
+        visited.insert(start_id.to_string());
+        match options.order {
+            Order::BreadthFirst => {
+                let mut queue: VecDeque<(String, usize)> = VecDeque::new();
+                queue.push_back((start_id.to_string(), 0));
+                while let Some((id, depth)) = queue.pop_front() {
+                    if !options.can_go_deeper(depth) {
+                        continue;
+                    }
+                    for (edge, vertex) in
+                        self.adjacent(&id, options.direction, &options.relations, vaults)
+                    {
+                        if visited.insert(vertex.get_id()) {
+                            if !visitor(depth + 1, &vertex, &edge) {
+                                return;
+                            }
+                            queue.push_back((vertex.get_id(), depth + 1));
+                        }
+                    }
+                }
+            }
+            Order::DepthFirst => {
+                // every frame keeps the pending adjacent vertices to visit
+                let mut stack: Vec<(usize, Vec<(Edge, Vertex)>)> = Vec::new();
+                if options.can_go_deeper(0) {
+                    let mut adjacent =
+                        self.adjacent(start_id, options.direction, &options.relations, vaults);
+                    adjacent.reverse();
+                    stack.push((0, adjacent));
+                }
+                while let Some((depth, adjacent)) = stack.last_mut() {
+                    let depth = *depth;
+                    let Some((edge, vertex)) = adjacent.pop() else {
+                        stack.pop();
+                        continue;
+                    };
+                    if visited.insert(vertex.get_id()) {
+                        if !visitor(depth + 1, &vertex, &edge) {
+                            return;
+                        }
+                        if options.can_go_deeper(depth + 1) {
+                            let mut adjacent = self.adjacent(
+                                &vertex.get_id(),
+                                options.direction,
+                                &options.relations,
+                                vaults,
+                            );
+                            adjacent.reverse();
+                            stack.push((depth + 1, adjacent));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Retrieves the edges, and the vertex on the other side,
+    /// that follows the direction and any of the relations,
+    /// or every relation when empty, for the provided vaults
+    pub(crate) fn adjacent(
+        &self,
+        id: &str,
+        direction: Direction,
+        relations: &[String],
+        vaults: &[String],
+    ) -> Vec<(Edge, Vertex)> {
+        let relations: Vec<Option<&str>> = match relations.is_empty() {
+            true => vec![None],
+            false => relations.iter().map(|r| Some(r.as_str())).collect(),
+        };
+        let mut adjacent = Vec::new();
+        for vault in vaults.iter() {
+            for relation in relations.iter() {
+                if direction != Direction::In {
+                    for edge in self.edges_out(id, *relation, vault) {
+                        adjacent.push((edge.clone(), edge.get_to_vertex()));
+                    }
+                }
+                if direction != Direction::Out {
+                    for edge in self.edges_in(id, *relation, vault) {
+                        adjacent.push((edge.clone(), edge.get_from_vertex()));
+                    }
+                }
+            }
+        }
+        adjacent
+    }
+}
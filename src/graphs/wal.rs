@@ -0,0 +1,451 @@
+//! Write-ahead log for crash-safe persistence
+//!
+//! A Graphs opened on a directory appends every mutation to a log,
+//! synced to disk before the mutation is applied; when the record
+//! could not be written the mutation fails with WalWriteFailed.
+//! Opening the directory again loads the latest snapshot
+//! and replays the log on top of it.
+//!
+//! Every snapshot and log has a generation on its file name,
+//! `snapshot-<gen>.grphst` and `wal-<gen>.log`. Compaction writes
+//! the snapshot of the next generation, atomically renamed once complete,
+//! with an empty log; so a crash while compacting never replays
+//! a mutation already on the snapshot. The log is not encrypted,
+//! so a Graphs with the key of `save_encrypted` or `load_encrypted`
+//! is not compacted and fails with SnapshotEncryptionFailed.
+
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attributes::AttrValue,
+    config::{get_compression, get_wal_compact_after},
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{Graphs, IndexKind, IndexTarget, Schema},
+    vertex::Vertex,
+};
+
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+const SNAPSHOT_EXTENSION: &str = ".grphst";
+const LOG_PREFIX: &str = "wal-";
+const LOG_EXTENSION: &str = ".log";
+
+/// A mutation of the Graphs, with the name of the vault it was applied on
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum WalRecord {
+    Insert(String),
+    DeleteVault(String),
+    SetLabel(String),
+    AddEdges(String, Vec<Edge>),
+    UpdateEdge(String, Edge),
+    DeleteEdge(String, String),
+    AddVertex(String, Vertex),
+    RemoveVertex(String, String),
+    /// vault, vertex id, attribute key and value
+    SetVertexAttr(String, String, String, AttrValue),
+    /// vault, vertex id and attribute key
+    DeleteVertexAttr(String, String, String),
+    /// mutations of a committed transaction
+    Transaction(Vec<WalRecord>),
+    /// vault, attribute key, target and kind of the index
+    CreateIndex(String, String, IndexTarget, IndexKind),
+    /// vault, attribute key and target of the index
+    DropIndex(String, String, IndexTarget),
+    SetSchema(String, Schema),
+    RemoveSchema(String),
+}
+
+impl WalRecord {
+    /// Names of the vaults changed by the mutation
+    fn vaults(&self) -> Vec<&str> {
+        match self {
+            WalRecord::SetLabel(_) => Vec::new(),
+            WalRecord::Transaction(records) => records.iter().flat_map(WalRecord::vaults).collect(),
+            WalRecord::Insert(vault)
+            | WalRecord::DeleteVault(vault)
+            | WalRecord::AddEdges(vault, _)
+            | WalRecord::UpdateEdge(vault, _)
+            | WalRecord::DeleteEdge(vault, _)
+            | WalRecord::AddVertex(vault, _)
+            | WalRecord::RemoveVertex(vault, _)
+            | WalRecord::SetVertexAttr(vault, _, _, _)
+            | WalRecord::DeleteVertexAttr(vault, _, _)
+            | WalRecord::CreateIndex(vault, _, _, _)
+            | WalRecord::DropIndex(vault, _, _)
+            | WalRecord::SetSchema(vault, _)
+            | WalRecord::RemoveSchema(vault) => vec![vault.as_str()],
+        }
+    }
+}
+
+/// The log of a Graphs opened on a directory
+pub(crate) struct Wal {
+    dir: PathBuf,
+    generation: u64,
+    file: File,
+    /// records on the log since the last compaction
+    records: usize,
+    compact_after: usize,
+    /// records of a running transaction, appended once committed
+    staged: Option<Vec<WalRecord>>,
+}
+
+/// Keeps the log of a Graphs, a cloned Graphs is detached from the log
+#[derive(Default)]
+pub(crate) struct WalSlot(Option<Wal>);
+
+impl Clone for WalSlot {
+    fn clone(&self) -> Self {
+        WalSlot(None)
+    }
+}
+
+impl fmt::Debug for WalSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(wal) => write!(f, "Wal({})", wal.log_path().display()),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+fn snapshot_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("{SNAPSHOT_PREFIX}{generation}{SNAPSHOT_EXTENSION}"))
+}
+
+fn log_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("{LOG_PREFIX}{generation}{LOG_EXTENSION}"))
+}
+
+/// Generation of a snapshot or log file name
+fn generation_of(file_name: &str, prefix: &str, extension: &str) -> Option<u64> {
+    file_name
+        .strip_prefix(prefix)?
+        .strip_suffix(extension)?
+        .parse()
+        .ok()
+}
+
+impl Wal {
+    fn log_path(&self) -> PathBuf {
+        log_path(&self.dir, self.generation)
+    }
+
+    /// Appends a record, synced to disk
+    fn append(&mut self, record: &WalRecord) -> Result<(), Box<dyn Error>> {
+        let bytes = bincode::serialize(record)?;
+        let mut frame = Vec::with_capacity(bytes.len() + 4);
+        frame.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&bytes);
+        let len = self.file.metadata()?.len();
+        if let Err(err) = self
+            .file
+            .write_all(&frame)
+            .and_then(|_| self.file.sync_data())
+        {
+            // a partial record would end the log, hiding the next ones
+            let _ = self.file.set_len(len);
+            return Err(err.into());
+        }
+        self.records += 1;
+        Ok(())
+    }
+}
+
+/// Reads the records of a log, a record partially written by a crash
+/// ends the log and is truncated
+fn read_log(path: &Path) -> Result<Vec<WalRecord>, Box<dyn Error>> {
+    let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let Some(len) = bytes.get(offset..offset + 4) else {
+            break;
+        };
+        let len = u32::from_le_bytes(len.try_into()?) as usize;
+        let Some(body) = bytes.get(offset + 4..offset + 4 + len) else {
+            break;
+        };
+        match bincode::deserialize::<WalRecord>(body) {
+            Ok(record) => records.push(record),
+            Err(_) => break,
+        }
+        offset += 4 + len;
+    }
+    if offset < bytes.len() {
+        warn!(
+            "Truncating {} bytes of a partial record at the end of {}",
+            bytes.len() - offset,
+            path.display()
+        );
+        file.set_len(offset as u64)?;
+        file.sync_data()?;
+    }
+    Ok(records)
+}
+
+fn append_record(wal: &mut Wal, record: &WalRecord) -> Result<(), GruPHstError> {
+    wal.append(record).map_err(|err| {
+        error!("Mutation not written on write-ahead log: {}", err);
+        GruPHstError::WalWriteFailed(err.to_string())
+    })
+}
+
+impl Graphs {
+    /// Opens the Graphs persisted on a directory with a write-ahead log,
+    /// created when it does not exists, with the name of the directory
+    /// as label.
+    /// Loads the latest snapshot and replays the log, so the Graphs
+    /// is recovered as it was after the last mutation, even after a crash.
+    /// From then on, every mutation made through the Graphs is logged:
+    /// adding, updating and deleting edges, vertices and vaults,
+    /// changing the label and the attributes of vertices with
+    /// `set_vertex_attr` and `delete_vertex_attr`.
+    /// Changes made directly on a Vertex or an Edge are not logged.
+    /// The log is compacted into a fresh snapshot after
+    /// GRUPHST_WAL_COMPACT_AFTER records.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{edge::Edge, graphs::Graphs, vertex::Vertex};
+    ///
+    /// # let _ = std::fs::remove_dir_all("middle-earth-wal");
+    /// let mut graphs = Graphs::open("middle-earth-wal").unwrap();
+    /// let frodo = Vertex::new("Frodo");
+    /// graphs.add_edge(&Edge::create(&frodo, "lives at", &Vertex::new("The Shire")), None).unwrap();
+    /// graphs.set_vertex_attr(&frodo.get_id(), "years old", 50, None).unwrap();
+    /// drop(graphs);
+    ///
+    /// let graphs = Graphs::open("middle-earth-wal").unwrap();
+    /// let frodo = graphs.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    /// assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+    /// # std::fs::remove_dir_all("middle-earth-wal").unwrap();
+    /// ```
+    pub fn open(dir: &str) -> Result<Graphs, Box<dyn Error>> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+        let mut snapshots: Vec<u64> = Vec::new();
+        let mut logs: Vec<u64> = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let file_name = entry?.file_name().to_string_lossy().to_string();
+            if file_name.starts_with(SNAPSHOT_PREFIX) && file_name.ends_with(".tmp") {
+                // a snapshot not completed by a compaction
+                fs::remove_file(dir.join(&file_name))?;
+            } else if let Some(generation) =
+                generation_of(&file_name, SNAPSHOT_PREFIX, SNAPSHOT_EXTENSION)
+            {
+                snapshots.push(generation);
+            } else if let Some(generation) = generation_of(&file_name, LOG_PREFIX, LOG_EXTENSION) {
+                logs.push(generation);
+            }
+        }
+        let generation = snapshots.iter().max().copied().unwrap_or(0);
+        let mut graphs = match snapshots.is_empty() {
+            true => {
+                let label = dir.file_name().map_or("graphs".to_string(), |name| {
+                    name.to_string_lossy().to_string()
+                });
+                Graphs::init(&label)
+            }
+            false => Graphs::load(&snapshot_path(&dir, generation).to_string_lossy())?,
+        };
+        let path = log_path(&dir, generation);
+        let records = read_log(&path)?;
+        let replayed = records.len();
+        let persist = graphs.take_auto_persist();
+        for record in records {
+            graphs.apply(record)?;
+        }
+        graphs.restore_auto_persist(persist);
+        // files of previous generations, or of a compaction that did not finish
+        for stale in snapshots.iter().filter(|g| **g != generation) {
+            fs::remove_file(snapshot_path(&dir, *stale))?;
+        }
+        for stale in logs.iter().filter(|g| **g != generation) {
+            fs::remove_file(log_path(&dir, *stale))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        info!(
+            "Graphs opened at {} replaying {} records",
+            dir.display(),
+            replayed
+        );
+        graphs.wal = WalSlot(Some(Wal {
+            dir,
+            generation,
+            file,
+            records: replayed,
+            compact_after: get_wal_compact_after(),
+            staged: None,
+        }));
+        Ok(graphs)
+    }
+
+    /// Compacts the write-ahead log into a fresh snapshot,
+    /// only for a Graphs opened on a directory
+    pub fn compact(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(mut wal) = self.wal.0.take() else {
+            warn!("Graphs {} without write-ahead log to compact", self.label);
+            return Err(GruPHstError::NoWriteAheadLog.into());
+        };
+        let compacted = self.write_next_generation(&mut wal);
+        self.wal = WalSlot(Some(wal));
+        compacted
+    }
+
+    fn write_next_generation(&self, wal: &mut Wal) -> Result<(), Box<dyn Error>> {
+        if self.key.is_some() {
+            warn!("Graphs {} with a key not compacted", self.label);
+            return Err(GruPHstError::SnapshotEncryptionFailed.into());
+        }
+        let next = wal.generation + 1;
+        let next_log = log_path(&wal.dir, next);
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&next_log)?;
+        let snapshot = snapshot_path(&wal.dir, next);
+        let tmp = snapshot.with_extension("tmp");
+        let mut tmp_file = File::create(&tmp)?;
+        tmp_file.write_all(&self.encode(get_compression(), None)?)?;
+        tmp_file.sync_all()?;
+        // the snapshot of the next generation only exists once complete
+        fs::rename(&tmp, &snapshot)?;
+        let _ = fs::remove_file(snapshot_path(&wal.dir, wal.generation));
+        let _ = fs::remove_file(log_path(&wal.dir, wal.generation));
+        info!(
+            "Graphs {} compacted into {} after {} records",
+            self.label,
+            snapshot.display(),
+            wal.records
+        );
+        wal.generation = next;
+        wal.file = file;
+        wal.records = 0;
+        Ok(())
+    }
+
+    /// Writes a mutation on the log before it is applied, for a Graphs
+    /// opened on a directory, or stages it while a transaction is running;
+    /// fails with WalWriteFailed when it could not be synced to disk.
+    /// The vaults of the mutation are kept by the running transaction
+    pub(crate) fn log_mutation(&mut self, record: WalRecord) -> Result<(), GruPHstError> {
+        for vault_name in record.vaults() {
+            self.savepoint_vault(vault_name);
+        }
+        let Some(wal) = self.wal.0.as_mut() else {
+            return Ok(());
+        };
+        match wal.staged.as_mut() {
+            Some(staged) => staged.push(record),
+            None => append_record(wal, &record)?,
+        }
+        Ok(())
+    }
+
+    /// Compacts the log, once the logged mutations are applied,
+    /// when it reaches the configured number of records
+    pub(crate) fn compact_if_due(&mut self) {
+        let Some(mut wal) = self.wal.0.take() else {
+            return;
+        };
+        if wal.compact_after > 0 && wal.records >= wal.compact_after {
+            if let Err(err) = self.write_next_generation(&mut wal) {
+                error!("Write-ahead log not compacted: {}", err);
+            }
+        }
+        self.wal = WalSlot(Some(wal));
+    }
+
+    /// Starts staging the records of a transaction,
+    /// returns the records staged before it by an outer one
+    pub(crate) fn stage_wal(&mut self) -> usize {
+        match self.wal.0.as_mut() {
+            Some(wal) => wal.staged.get_or_insert_with(Vec::new).len(),
+            None => 0,
+        }
+    }
+
+    /// Commits a transaction, writing its records as a single one
+    /// when it is the outermost
+    pub(crate) fn commit_wal(&mut self, outermost: bool) -> Result<(), GruPHstError> {
+        let Some(wal) = self.wal.0.as_mut() else {
+            return Ok(());
+        };
+        if !outermost {
+            return Ok(());
+        }
+        let records = wal.staged.take().unwrap_or_default();
+        if !records.is_empty() {
+            append_record(wal, &WalRecord::Transaction(records))?;
+        }
+        Ok(())
+    }
+
+    /// Rolls back a transaction, discarding its staged records
+    pub(crate) fn discard_wal(&mut self, staged: usize, outermost: bool) {
+        let Some(wal) = self.wal.0.as_mut() else {
+            return;
+        };
+        match outermost {
+            true => wal.staged = None,
+            false => {
+                if let Some(records) = wal.staged.as_mut() {
+                    records.truncate(staged);
+                }
+            }
+        }
+    }
+
+    /// Applies a mutation replayed from the log
+    fn apply(&mut self, record: WalRecord) -> Result<(), GruPHstError> {
+        match record {
+            WalRecord::Insert(name) => self.insert(&name)?,
+            WalRecord::DeleteVault(name) => self.delete_vault(&name)?,
+            WalRecord::SetLabel(label) => self.set_label(&label),
+            WalRecord::AddEdges(vault, mut edges) => self.add_edges(&mut edges, Some(&vault))?,
+            WalRecord::UpdateEdge(vault, edge) => self.update_graph(&edge, Some(&vault))?,
+            WalRecord::DeleteEdge(vault, id) => self.delete_edge_by_id(id, Some(&vault))?,
+            WalRecord::AddVertex(vault, vertex) => self.add_vertex(&vertex, Some(&vault))?,
+            WalRecord::RemoveVertex(vault, id) => self.remove_vertex(&id, Some(&vault))?,
+            WalRecord::SetVertexAttr(vault, id, attr_k, attr_v) => {
+                self.set_vertex_attr(&id, &attr_k, attr_v, Some(&vault))?
+            }
+            WalRecord::DeleteVertexAttr(vault, id, attr_k) => {
+                self.delete_vertex_attr(&id, &attr_k, Some(&vault))?
+            }
+            WalRecord::Transaction(records) => {
+                for record in records {
+                    self.apply(record)?;
+                }
+            }
+            WalRecord::CreateIndex(vault, attr_k, target, kind) => {
+                self.create_index(&attr_k, target, kind, Some(&vault))?
+            }
+            WalRecord::DropIndex(vault, attr_k, target) => {
+                self.drop_index(&attr_k, target, Some(&vault))?
+            }
+            WalRecord::SetSchema(vault, schema) => self.set_schema(schema, Some(&vault))?,
+            WalRecord::RemoveSchema(vault) => {
+                self.remove_schema(Some(&vault))?;
+            }
+        }
+        Ok(())
+    }
+}
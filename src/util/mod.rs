@@ -1,36 +1,63 @@
 use std::path::Path;
 
 use log::{debug, error, trace, warn};
+use serde::Serialize;
 
-use crate::{errors::GruPHstError, graphs::Graphs};
+use crate::{
+    errors::GruPHstError,
+    graphs::{Graphs, MemoryPolicy},
+};
 
 /// Watches the memory that is in use for Graphs
 ///
-/// Triggered on a new Graph addition or update.
+/// Triggered before a write that grows the Graphs,
+/// with the additional bytes of the write.
 /// The limit is set on .env file or as environmental variable
 /// with GRUPHST_MAX_MEM_USAGE in MB.
+/// The running estimate of the memory is used,
+/// checked against the exact memory only when it is critical.
 /// In case that the memory is close to the configured max value,
-/// the data will be persisted on fs, and the process will exit.
-pub(crate) fn graphs_memory_watcher(graphs: &Graphs) {
-    let mem = graphs.get_mem().unwrap();
+/// the write is rejected with MemoryLimitExceeded,
+/// or with the panic memory policy the data will be persisted on fs,
+/// and the process will exit.
+pub(crate) fn graphs_memory_watcher(
+    graphs: &mut Graphs,
+    additional: usize,
+) -> Result<(), GruPHstError> {
     let max_mem = graphs.get_graphs_stats().get_max_mem();
-    let mem_prss = (mem as f32 * 100_f32) / max_mem as f32;
+    let pressure = |mem: usize| ((mem + additional) as f32 * 100_f32) / max_mem as f32;
+    let mut mem_prss = pressure(graphs.get_mem_estimate());
+    if mem_prss >= 99_f32 {
+        mem_prss = pressure(graphs.sync_mem_estimate());
+    }
     trace!("memory preassure: {:.2}", mem_prss);
     match mem_prss {
         mem_prss if (95_f32..99_f32).contains(&mem_prss) => {
             warn!("memory close to the limit: {:.2}", mem_prss);
+            Ok(())
         }
         mem_prss if mem_prss >= 99_f32 => {
             error!("memory usage critical: {:.2}", mem_prss);
+            if graphs.get_memory_policy() != MemoryPolicy::Panic {
+                return Err(GruPHstError::MemoryLimitExceeded);
+            }
             #[rustfmt::skip]
             error!("auto persisting current graphs: {}, then panicking", graphs.get_label());
             let _ = graphs.save(None);
             panic!("memory usage critical, auto-persisted current graphs");
         }
-        _ => debug!("memory ok: {:.2}", mem_prss),
+        _ => {
+            debug!("memory ok: {:.2}", mem_prss);
+            Ok(())
+        }
     }
 }
 
+/// Size of a value once serialized, to estimate the memory of a write
+pub(crate) fn serialized_size<T: Serialize>(value: &T) -> usize {
+    bincode::serialized_size(value).unwrap_or_default() as usize
+}
+
 /// Returns the filename or error
 pub(crate) fn get_file_name_from_path(file_path: &str) -> Result<String, GruPHstError> {
     let path = Path::new(file_path);
@@ -1,17 +1,23 @@
 //! Vertex modules
 
+use crate::attributes::{typed_attr, AttrValue};
 use crate::edge::Edge;
 use crate::errors::GruPHstError;
 use log::warn;
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
 use std::vec;
 use uuid::Uuid;
 
 mod cryptography;
 mod query;
+mod shared;
+
+use shared::Watchers;
+pub(crate) use shared::{
+    begin_undo, commit_undo, mark_stale_ids, stale_ids, take_stale_ids, take_undo, StaleIds,
+    VertexRef,
+};
 
 /// Representation of a vertex.
 /// A vertex or node, vertices in plural,
@@ -24,20 +30,32 @@ pub(crate) struct Vertex_ {
     /// And a name
     label: String,
     /// The attributes for a vertex
-    attr: HashMap<String, String>,
-    attr_vec_u8: HashMap<String, Vec<u8>>,
+    attr: HashMap<String, AttrValue>,
+    /// The indexes watching the vertex
+    #[serde(skip)]
+    watchers: Watchers,
 }
 
 impl Vertex_ {
     /// Creates a vertex with the given label, the id is generated
-    fn new(label: &str) -> Rc<RefCell<Vertex_>> {
+    fn new(label: &str) -> VertexRef {
         let edge = Vertex_ {
             label: String::from(label),
             id: Uuid::new_v4().to_string(),
             attr: HashMap::new(),
-            attr_vec_u8: HashMap::new(),
+            watchers: Watchers::default(),
         };
-        Rc::new(RefCell::new(edge))
+        edge.into_ref()
+    }
+
+    /// Creates a vertex with the given id, label and attributes
+    pub(crate) fn from_parts(id: String, label: String, attr: HashMap<String, AttrValue>) -> Self {
+        Vertex_ {
+            id,
+            label,
+            attr,
+            watchers: Watchers::default(),
+        }
     }
 }
 
@@ -45,9 +63,15 @@ impl Vertex_ {
 /// A vertex or node, vertices in plural,
 /// is the fundamental unit of a graph.
 // wrapper for Edge_
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vertex {
-    pub(crate) vrtx: Rc<RefCell<Vertex_>>,
+    pub(crate) vrtx: VertexRef,
+}
+
+impl PartialEq for Vertex {
+    fn eq(&self, other: &Self) -> bool {
+        *self.read() == *other.read()
+    }
 }
 
 impl Vertex {
@@ -64,7 +88,7 @@ impl Vertex {
         }
     }
 
-    pub(crate) fn create(label: &str) -> Rc<RefCell<Vertex_>> {
+    pub(crate) fn create(label: &str) -> VertexRef {
         Vertex_::new(label)
     }
 
@@ -77,7 +101,7 @@ impl Vertex {
     /// let _id: String = vertex.get_id();
     /// ```
     pub fn get_id(&self) -> String {
-        self.vrtx.borrow().id.clone()
+        self.read().id.clone()
     }
 
     /// Retrieves the label for a vertex
@@ -90,7 +114,7 @@ impl Vertex {
     /// assert_eq!(label, "Gandalf");
     /// ```
     pub fn get_label(&self) -> String {
-        self.vrtx.borrow().label.clone()
+        self.read().label.clone()
     }
 
     /// Sets the label for a vertex
@@ -106,10 +130,10 @@ impl Vertex {
     /// assert_eq!(label, "Gandalf the Gray");
     /// ```
     pub fn set_label(&mut self, label: &str) {
-        self.vrtx.borrow_mut().label = label.to_string();
+        self.write().label = label.to_string();
     }
 
-    /// Set attributes for a vertex
+    /// Set attributes for a vertex, the value keeps its type
     /// # Examples
     /// ```rust
     /// use gruphst::vertex::Vertex;
@@ -120,20 +144,19 @@ impl Vertex {
     /// ```
     pub fn set_attr<T>(&mut self, attr_k: &str, attr_v: T)
     where
-        T: std::fmt::Display,
+        T: Into<AttrValue>,
     {
-        #[rustfmt::skip]
-        self.vrtx.borrow_mut().attr.insert(attr_k.to_string(), attr_v.to_string());
+        self.write().attr.insert(attr_k.to_string(), attr_v.into());
+        self.changed();
     }
 
+    /// Set attribute of type Vec<u8> for a vertex
     pub fn set_attr_vec_u8(&mut self, attr_k: &str, attr_v: &[u8]) {
-        self.vrtx
-            .borrow_mut()
-            .attr_vec_u8
-            .insert(attr_k.to_string(), attr_v.to_owned());
+        self.set_attr(attr_k, attr_v);
     }
 
-    /// Get attribute for a vertex
+    /// Get attribute for a vertex, as text,
+    /// fails for Vec<u8> attributes
     /// # Examples
     /// ```rust
     /// use gruphst::vertex::Vertex;
@@ -146,28 +169,100 @@ impl Vertex {
     /// assert_eq!(gandalf_years, "24000");
     /// ```
     pub fn get_attr(&self, attr_k: &str) -> Result<String, GruPHstError> {
-        let binding = self.vrtx.borrow();
-        let res = binding.attr.get(attr_k);
-        match res {
-            Some(resp) => Ok(resp.clone()),
-            None => {
-                warn!("attribute '{}' not found", attr_k);
-                Err(GruPHstError::AttributeNotFound)
-            }
+        let binding = self.read();
+        typed_attr(attr_k, binding.attr.get(attr_k), "string", |v| {
+            (!v.is_bytes()).then(|| v.to_string())
+        })
+    }
+
+    /// Get the typed value of an attribute for a vertex
+    /// # Examples
+    /// ```rust
+    /// use gruphst::{attributes::AttrValue, vertex::Vertex};
+    ///
+    /// let mut gandalf = Vertex::new("Gandalf");
+    /// gandalf.set_attr("years old", 24000);
+    ///
+    /// assert_eq!(gandalf.get_attr_value("years old").unwrap(), AttrValue::Int(24000));
+    /// ```
+    pub fn get_attr_value(&self, attr_k: &str) -> Result<AttrValue, GruPHstError> {
+        let binding = self.read();
+        typed_attr(attr_k, binding.attr.get(attr_k), "any", |v| Some(v.clone()))
+    }
+
+    /// Value of an attribute, None when missing
+    pub(crate) fn attr_value(&self, attr_k: &str) -> Option<AttrValue> {
+        self.read().attr.get(attr_k).cloned()
+    }
+
+    /// Copy of the vertex, with the same id, not shared with it
+    pub(crate) fn detached(&self) -> Vertex {
+        let data = self.read();
+        let copy = Vertex_::from_parts(data.id.clone(), data.label.clone(), data.attr.clone());
+        Vertex {
+            vrtx: copy.into_ref(),
         }
     }
 
-    /// Get attribute of type Vev<u8>
+    /// Get attribute of type integer
+    /// # Examples
+    /// ```rust
+    /// use gruphst::vertex::Vertex;
+    ///
+    /// let mut gandalf = Vertex::new("Gandalf");
+    /// gandalf.set_attr("years old", 24000);
+    /// gandalf.set_attr("known as", "The Gray");
+    ///
+    /// assert_eq!(gandalf.get_attr_i64("years old").unwrap(), 24000);
+    /// assert!(gandalf.get_attr_i64("known as").is_err());
+    /// ```
+    pub fn get_attr_i64(&self, attr_k: &str) -> Result<i64, GruPHstError> {
+        let binding = self.read();
+        typed_attr(
+            attr_k,
+            binding.attr.get(attr_k),
+            "integer",
+            AttrValue::as_i64,
+        )
+    }
+
+    /// Get attribute of type float, integers are converted
+    pub fn get_attr_f64(&self, attr_k: &str) -> Result<f64, GruPHstError> {
+        let binding = self.read();
+        typed_attr(attr_k, binding.attr.get(attr_k), "float", AttrValue::as_f64)
+    }
+
+    /// Get attribute of type bool
+    pub fn get_attr_bool(&self, attr_k: &str) -> Result<bool, GruPHstError> {
+        let binding = self.read();
+        typed_attr(attr_k, binding.attr.get(attr_k), "bool", AttrValue::as_bool)
+    }
+
+    /// Get attribute of type timestamp, as milliseconds since the Unix epoch
+    pub fn get_attr_timestamp(&self, attr_k: &str) -> Result<i64, GruPHstError> {
+        let binding = self.read();
+        typed_attr(
+            attr_k,
+            binding.attr.get(attr_k),
+            "timestamp",
+            AttrValue::as_timestamp,
+        )
+    }
+
+    /// Get attribute of type list
+    pub fn get_attr_list(&self, attr_k: &str) -> Result<Vec<AttrValue>, GruPHstError> {
+        let binding = self.read();
+        typed_attr(attr_k, binding.attr.get(attr_k), "list", |v| {
+            v.as_list().cloned()
+        })
+    }
+
+    /// Get attribute of type Vec<u8>
     pub fn get_attr_vec_u8(&self, attr_k: &str) -> Result<Vec<u8>, GruPHstError> {
-        let binding = self.vrtx.borrow();
-        let res = binding.attr_vec_u8.get(attr_k);
-        match res {
-            Some(resp) => Ok(resp.clone()),
-            None => {
-                warn!("attribute '{}' not found", attr_k);
-                Err(GruPHstError::AttributeNotFound)
-            }
-        }
+        let binding = self.read();
+        typed_attr(attr_k, binding.attr.get(attr_k), "bytes", |v| {
+            v.as_bytes().cloned()
+        })
     }
 
     /// Updates the value of an attribute
@@ -188,33 +283,33 @@ impl Vertex {
     /// ```
     pub fn update_attr<T>(&mut self, attr_k: &str, attr_v: T) -> Result<(), GruPHstError>
     where
-        T: std::fmt::Display,
+        T: Into<AttrValue>,
     {
-        if let Some(attr) = self.vrtx.borrow_mut().attr.get_mut(attr_k) {
-            *attr = attr_v.to_string();
-            return Ok(());
+        if let Some(attr) = self.write().attr.get_mut(attr_k) {
+            *attr = attr_v.into();
+        } else {
+            return Err(GruPHstError::AttributeNotFound);
         }
-        Err(GruPHstError::AttributeNotFound)
+        self.changed();
+        Ok(())
     }
 
     /// Updates the value of an attribute or creates a new one if attribute key does not exists
     pub fn upsert_attr<T>(&mut self, attr_k: &str, attr_v: T)
     where
-        T: std::fmt::Display,
+        T: Into<AttrValue>,
     {
-        let mut binding = self.vrtx.borrow_mut();
-        if let Some(attr) = binding.attr.get_mut(attr_k) {
-            *attr = attr_v.to_string();
-        } else {
-            binding.attr.insert(attr_k.to_string(), attr_v.to_string());
-        }
+        self.set_attr(attr_k, attr_v);
     }
 
     /// Deletes an attribute
     pub fn del_attr(&mut self, v: &str) -> Result<(), GruPHstError> {
-        let res = self.vrtx.borrow_mut().attr.remove(v);
+        let res = self.write().attr.remove(v);
         match res {
-            Some(_) => Ok(()),
+            Some(_) => {
+                self.changed();
+                Ok(())
+            }
             None => {
                 warn!("attribute {} not found for remove", v);
                 Err(GruPHstError::AttributeNotFound)
@@ -223,25 +318,27 @@ impl Vertex {
     }
 
     /// Returns an collection containing all attribute keys
-    /// of String attributes
+    /// of String attributes, any type but Vec<u8>
     pub fn get_attr_str_keys(&self) -> Vec<String> {
-        let binding = self.vrtx.borrow();
-        // FIXME: clippy has a warning here:
-        // let kv: Vec<String> = binding.attr.iter().map(|(k,_v)| k.clone()).collect();
-        // |                     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ help: try: `binding.attr.keys().map(|k| k.clone())`
-
-        let kv: Vec<String> = binding.attr.iter().map(|(k, _v)| k.clone()).collect();
+        let binding = self.read();
+        let kv: Vec<String> = binding
+            .attr
+            .iter()
+            .filter(|(_, v)| !v.is_bytes())
+            .map(|(k, _)| k.clone())
+            .collect();
         kv
     }
 
     /// Returns an collection containing all attribute keys
     /// of Vec<u8> attributes
     pub fn get_attr_vec_u8_keys(&self) -> Vec<String> {
-        let binding = self.vrtx.borrow();
+        let binding = self.read();
         let kv: Vec<String> = binding
-            .attr_vec_u8
+            .attr
             .iter()
-            .map(|(k, _v)| k.clone())
+            .filter(|(_, v)| v.is_bytes())
+            .map(|(k, _)| k.clone())
             .collect();
         kv
     }
@@ -249,13 +346,8 @@ impl Vertex {
     /// Returns an collection containing all attribute keys
     /// of any type of attributes
     pub fn get_attr_keys(&self) -> Vec<String> {
-        let binding = self.vrtx.borrow();
-        let mut kv_attr: Vec<String> = binding.attr.iter().map(|(k, _v)| k.clone()).collect();
-        let mut kv_attr_vec_u8: Vec<String> = binding
-            .attr_vec_u8
-            .iter()
-            .map(|(k, _v)| k.clone())
-            .collect();
+        let mut kv_attr = self.get_attr_str_keys();
+        let mut kv_attr_vec_u8 = self.get_attr_vec_u8_keys();
         kv_attr.append(&mut kv_attr_vec_u8);
         kv_attr
     }
@@ -1,3 +1,7 @@
+use std::ops::RangeBounds;
+
+use crate::attributes::{attr_range, AttrRange, AttrValue};
+
 use super::Vertex;
 
 impl Vertex {
@@ -15,7 +19,7 @@ impl Vertex {
     /// assert!(!vertex.has_attr_str_key_equals_to("age"));
     /// ```
     pub fn has_attr_str_key_equals_to(&self, attr_k: &str) -> bool {
-        self.vrtx.borrow().attr.contains_key(attr_k)
+        self.read().attr.get(attr_k).is_some_and(|v| !v.is_bytes())
     }
 
     /// Checks if an Vec<u8> attribute key exists
@@ -34,7 +38,7 @@ impl Vertex {
     /// assert!(!vertex.has_attr_vec_u8_key_equals_to("edoc"));
     /// ```
     pub fn has_attr_vec_u8_key_equals_to(&self, attr_k: &str) -> bool {
-        self.vrtx.borrow().attr_vec_u8.contains_key(attr_k)
+        self.read().attr.get(attr_k).is_some_and(|v| v.is_bytes())
     }
 
     /// Checks if an attribute key exists
@@ -55,8 +59,7 @@ impl Vertex {
     /// assert!(!vertex.has_attr_key("age"));
     /// ```
     pub fn has_attr_key(&self, attr_k: &str) -> bool {
-        self.vrtx.borrow().attr.contains_key(attr_k)
-            || self.vrtx.borrow().attr_vec_u8.contains_key(attr_k)
+        self.read().attr.contains_key(attr_k)
     }
 
     /// Checks if an attribute values is like on a vertex
@@ -75,13 +78,12 @@ impl Vertex {
     where
         T: std::fmt::Display + std::clone::Clone,
     {
-        for (_key, val) in self.vrtx.borrow().attr.clone().into_iter() {
-            let v = attr_v.to_string().to_lowercase();
-            if val.to_lowercase().contains(&v) {
-                return true;
-            }
-        }
-        false
+        let v = attr_v.to_string().to_lowercase();
+        self.read()
+            .attr
+            .values()
+            .filter(|val| !val.is_bytes())
+            .any(|val| val.to_string().to_lowercase().contains(&v))
     }
 
     /// Checks if an String attribute key is like on a vertex
@@ -97,12 +99,11 @@ impl Vertex {
     /// assert!(!vertex.has_attr_str_key_like("ag"));
     /// ```
     pub fn has_attr_str_key_like(&self, attr_k: &str) -> bool {
-        for key in self.vrtx.borrow().attr.keys() {
-            if key.to_lowercase().contains(&attr_k.to_lowercase()) {
-                return true;
-            }
-        }
-        false
+        self.read()
+            .attr
+            .iter()
+            .filter(|(_, val)| !val.is_bytes())
+            .any(|(key, _)| key.to_lowercase().contains(&attr_k.to_lowercase()))
     }
 
     /// Checks if an Vec<u8> attribute key is like on a vertex
@@ -121,12 +122,11 @@ impl Vertex {
     /// assert!(!vertex.has_attr_vec_u8_key_like("dOC"));
     /// ```
     pub fn has_attr_vec_u8_key_like(&self, attr_k: &str) -> bool {
-        for key in self.vrtx.borrow().attr_vec_u8.keys() {
-            if key.to_lowercase().contains(&attr_k.to_lowercase()) {
-                return true;
-            }
-        }
-        false
+        self.read()
+            .attr
+            .iter()
+            .filter(|(_, val)| val.is_bytes())
+            .any(|(key, _)| key.to_lowercase().contains(&attr_k.to_lowercase()))
     }
 
     /// Checks if any attribute key is like on a vertex
@@ -166,12 +166,9 @@ impl Vertex {
     where
         T: std::fmt::Display + std::clone::Clone,
     {
-        match self.vrtx.borrow().attr.get(attr_k) {
-            Some(val) => {
-                let v = attr_v.clone();
-                *val == v.to_string()
-            }
-            None => false,
+        match self.read().attr.get(attr_k) {
+            Some(val) if !val.is_bytes() => val.to_string() == attr_v.to_string(),
+            _ => false,
         }
     }
 
@@ -193,11 +190,47 @@ impl Vertex {
     /// assert!(!vertex.has_attr_vec_u8_equals_to("edoc", &vu8));
     /// ```
     pub fn has_attr_vec_u8_equals_to(&self, attr_k: &str, attr_v: &Vec<u8>) -> bool {
-        match self.vrtx.borrow().attr_vec_u8.get(attr_k) {
-            Some(val) => {
-                // let v = attr_v.clone();
-                *val == *attr_v
-            }
+        match self.read().attr.get(attr_k) {
+            Some(val) => val.as_bytes() == Some(attr_v),
+            None => false,
+        }
+    }
+
+    /// Checks if an attribute value is inside of a range,
+    /// integers and floats are compared as numbers,
+    /// other types only with bounds of the same type
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ops::Bound;
+    /// use gruphst::vertex::Vertex;
+    ///
+    /// let mut vertex = Vertex::new("Gandalf");
+    /// vertex.set_attr("years old", 24000);
+    ///
+    /// assert!(vertex.has_attr_in_range("years old", 1000..));
+    /// assert!(vertex.has_attr_in_range("years old", 10.5..=24000.0));
+    /// assert!(!vertex.has_attr_in_range("years old", (Bound::Excluded(24000), Bound::Unbounded)));
+    /// assert!(!vertex.has_attr_in_range("height", 1..2));
+    /// ```
+    pub fn has_attr_in_range<R, T>(&self, attr_k: &str, range: R) -> bool
+    where
+        R: RangeBounds<T>,
+        T: Into<AttrValue> + Clone,
+    {
+        self.has_attr_within(attr_k, &attr_range(&range))
+    }
+
+    pub(crate) fn has_attr_within(&self, attr_k: &str, range: &AttrRange) -> bool {
+        match self.read().attr.get(attr_k) {
+            Some(val) => val.is_within(range),
+            None => false,
+        }
+    }
+
+    pub(crate) fn has_attr_equal(&self, attr_k: &str, attr_v: &AttrValue) -> bool {
+        match self.read().attr.get(attr_k) {
+            Some(val) => val.equals(attr_v),
             None => false,
         }
     }
@@ -216,9 +249,7 @@ impl Vertex {
     /// assert_eq!(vertex.attrs_len(), 3);
     /// ```
     pub fn attrs_len(&self) -> usize {
-        let mut c = self.vrtx.borrow().attr.len();
-        c += self.vrtx.borrow().attr_vec_u8.len();
-        c
+        self.read().attr.len()
     }
 
     /// Checks if attributes for a vertex is empty
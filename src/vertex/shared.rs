@@ -0,0 +1,219 @@
+//! Shared pointer to the vertex data
+//!
+//! By default vertices are shared between edges with `Rc<RefCell<Vertex_>>`,
+//! with the "sync" feature enabled `Arc<RwLock<Vertex_>>` is used instead,
+//! so Vertex, Edge and Graphs can be sent and shared between threads.
+//!
+//! The indexes of a vault watch the vertices they index, a vertex changed
+//! through any of its handles adds its id to the stale ids of every index
+//! watching it, so they are looked up until indexed again.
+//!
+//! While a transaction is running on a thread, the data of every vertex
+//! is recorded on its undo log before the first change to it,
+//! so a rollback restores it.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "sync")]
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+#[cfg(not(feature = "sync"))]
+use std::{
+    cell::{Ref, RefMut},
+    rc::{Rc, Weak},
+};
+
+use super::{Vertex, Vertex_};
+
+/// Pointer to the data of a vertex shared by edges and vaults
+#[cfg(not(feature = "sync"))]
+pub(crate) type VertexRef = Rc<RefCell<Vertex_>>;
+
+/// Pointer to the data of a vertex shared by edges, vaults and threads
+#[cfg(feature = "sync")]
+pub(crate) type VertexRef = Arc<RwLock<Vertex_>>;
+
+/// Ids of the vertices changed out of the indexes that watch them
+#[cfg(not(feature = "sync"))]
+pub(crate) type StaleIds = Rc<RefCell<HashSet<String>>>;
+
+/// Ids of the vertices changed out of the indexes that watch them,
+/// from any thread
+#[cfg(feature = "sync")]
+pub(crate) type StaleIds = Arc<Mutex<HashSet<String>>>;
+
+#[cfg(not(feature = "sync"))]
+type StaleRef = Weak<RefCell<HashSet<String>>>;
+
+#[cfg(not(feature = "sync"))]
+type VertexMut<'a> = RefMut<'a, Vertex_>;
+
+#[cfg(feature = "sync")]
+type VertexMut<'a> = RwLockWriteGuard<'a, Vertex_>;
+
+#[cfg(feature = "sync")]
+type StaleRef = Weak<Mutex<HashSet<String>>>;
+
+#[cfg(not(feature = "sync"))]
+fn stale_ids_mut(stale: &StaleIds) -> RefMut<'_, HashSet<String>> {
+    stale.borrow_mut()
+}
+
+#[cfg(feature = "sync")]
+fn stale_ids_mut(stale: &StaleIds) -> std::sync::MutexGuard<'_, HashSet<String>> {
+    stale.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(not(feature = "sync"))]
+fn downgrade(stale: &StaleIds) -> StaleRef {
+    Rc::downgrade(stale)
+}
+
+#[cfg(feature = "sync")]
+fn downgrade(stale: &StaleIds) -> StaleRef {
+    Arc::downgrade(stale)
+}
+
+/// Takes the ids of the stale vertices
+pub(crate) fn take_stale_ids(stale: &StaleIds) -> HashSet<String> {
+    std::mem::take(&mut *stale_ids_mut(stale))
+}
+
+/// Retrieves the ids of the stale vertices
+pub(crate) fn stale_ids(stale: &StaleIds) -> Vec<String> {
+    stale_ids_mut(stale).iter().cloned().collect()
+}
+
+/// Adds the ids to the stale vertices
+pub(crate) fn mark_stale_ids(stale: &StaleIds, ids: Vec<String>) {
+    stale_ids_mut(stale).extend(ids);
+}
+
+/// Data of the vertices before their first change, by their address,
+/// for every transaction running on the thread, the outermost first
+type UndoLog = Vec<HashMap<usize, (Vertex, Vertex_)>>;
+
+thread_local! {
+    static UNDO_LOG: RefCell<UndoLog> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starts recording the data of the vertices for a transaction
+pub(crate) fn begin_undo() {
+    UNDO_LOG.with(|log| log.borrow_mut().push(HashMap::new()));
+}
+
+/// Stops recording for a committed transaction, its data is kept
+/// by the outer transaction when there is one
+pub(crate) fn commit_undo() {
+    UNDO_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let committed = log.pop().unwrap_or_default();
+        if let Some(outer) = log.last_mut() {
+            for (address, recorded) in committed {
+                outer.entry(address).or_insert(recorded);
+            }
+        }
+    });
+}
+
+/// Stops recording for a rolled back transaction,
+/// retrieving the data of the vertices before their first change
+pub(crate) fn take_undo() -> Vec<(Vertex, Vertex_)> {
+    UNDO_LOG
+        .with(|log| log.borrow_mut().pop().unwrap_or_default())
+        .into_values()
+        .collect()
+}
+
+/// Indexes watching a vertex, never serialized nor compared
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Watchers(Vec<StaleRef>);
+
+impl PartialEq for Watchers {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(not(feature = "sync"))]
+impl Vertex_ {
+    pub(crate) fn into_ref(self) -> VertexRef {
+        Rc::new(RefCell::new(self))
+    }
+}
+
+#[cfg(feature = "sync")]
+impl Vertex_ {
+    pub(crate) fn into_ref(self) -> VertexRef {
+        Arc::new(RwLock::new(self))
+    }
+}
+
+impl Vertex {
+    /// Adds the stale ids of an index to the ones watching the vertex
+    pub(crate) fn watch(&self, stale: &StaleIds) {
+        let mut data = self.write();
+        let watchers = &mut data.watchers.0;
+        watchers.retain(|watcher| watcher.strong_count() > 0);
+        let stale = downgrade(stale);
+        if !watchers.iter().any(|watcher| watcher.ptr_eq(&stale)) {
+            watchers.push(stale);
+        }
+    }
+
+    /// Mutable access to the vertex data, recorded on the undo log
+    /// of a running transaction before the first change
+    pub(crate) fn write(&self) -> VertexMut<'_> {
+        let address = &*self.vrtx as *const _ as usize;
+        UNDO_LOG.with(|log| {
+            if let Some(changed) = log.borrow_mut().last_mut() {
+                changed
+                    .entry(address)
+                    .or_insert_with(|| (self.clone(), self.read().clone()));
+            }
+        });
+        self.data_mut()
+    }
+
+    /// Restores the vertex data recorded by a rolled back transaction,
+    /// marking it as stale on every index watching it
+    pub(crate) fn restore(&self, data: Vertex_) {
+        *self.data_mut() = data;
+        self.changed();
+    }
+
+    /// Marks the vertex as stale on every index watching it,
+    /// once its attributes are changed
+    pub(crate) fn changed(&self) {
+        let (id, watchers) = {
+            let data = self.read();
+            (data.id.clone(), data.watchers.0.clone())
+        };
+        for stale in watchers.iter().filter_map(Weak::upgrade) {
+            mark_stale_ids(&stale, vec![id.clone()]);
+        }
+    }
+}
+
+#[cfg(not(feature = "sync"))]
+impl Vertex {
+    /// Immutable access to the vertex data
+    pub(crate) fn read(&self) -> Ref<'_, Vertex_> {
+        self.vrtx.borrow()
+    }
+
+    fn data_mut(&self) -> RefMut<'_, Vertex_> {
+        self.vrtx.borrow_mut()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl Vertex {
+    /// Immutable access to the vertex data
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Vertex_> {
+        self.vrtx.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn data_mut(&self) -> RwLockWriteGuard<'_, Vertex_> {
+        self.vrtx.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
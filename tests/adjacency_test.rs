@@ -0,0 +1,164 @@
+use gruphst::{edge::Edge, errors::GruPHstError, graphs::Graphs, vertex::Vertex};
+
+fn prepare_adjacency_test() -> (Graphs, Vertex, Vertex, Vertex, Vertex) {
+    let mut graphs = Graphs::init("middle-earth");
+    let frodo = Vertex::new("Frodo");
+    let sam = Vertex::new("Sam");
+    let gandalf = Vertex::new("Gandalf");
+    let the_shire = Vertex::new("The Shire");
+
+    graphs
+        .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&sam, "friend of", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&gandalf, "friend of", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&frodo, "lives at", &the_shire), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&sam, "lives at", &the_shire), None)
+        .unwrap();
+
+    (graphs, frodo, sam, gandalf, the_shire)
+}
+
+fn labels(vertices: Vec<Vertex>) -> Vec<String> {
+    let mut labels: Vec<String> = vertices.iter().map(|v| v.get_label()).collect();
+    labels.sort();
+    labels
+}
+
+#[test]
+fn should_retrieve_neighbors_out_by_relation() {
+    let (graphs, frodo, _sam, _gandalf, _the_shire) = prepare_adjacency_test();
+    let friends = graphs
+        .neighbors_out(&frodo.get_id(), Some("friend of"), None)
+        .unwrap();
+    assert_eq!(labels(friends), vec!["Sam"]);
+    let all = graphs.neighbors_out(&frodo.get_id(), None, None).unwrap();
+    assert_eq!(labels(all), vec!["Sam", "The Shire"]);
+}
+
+#[test]
+fn should_retrieve_neighbors_in_by_relation() {
+    let (graphs, frodo, _sam, _gandalf, the_shire) = prepare_adjacency_test();
+    let friends = graphs
+        .neighbors_in(&frodo.get_id(), Some("friend of"), None)
+        .unwrap();
+    assert_eq!(labels(friends), vec!["Gandalf", "Sam"]);
+    let inhabitants = graphs
+        .neighbors_in(&the_shire.get_id(), Some("lives at"), None)
+        .unwrap();
+    assert_eq!(labels(inhabitants), vec!["Frodo", "Sam"]);
+}
+
+#[test]
+fn should_not_retrieve_neighbors_without_relations() {
+    let (graphs, _frodo, _sam, gandalf, the_shire) = prepare_adjacency_test();
+    assert_eq!(
+        graphs.neighbors_in(&gandalf.get_id(), None, None),
+        Err(GruPHstError::EdgeNoRelations(String::from("in")))
+    );
+    assert_eq!(
+        graphs.neighbors_out(&the_shire.get_id(), None, None),
+        Err(GruPHstError::EdgeNoRelations(String::from("out")))
+    );
+    assert_eq!(
+        graphs.neighbors_out(&gandalf.get_id(), None, Some("!exists")),
+        Err(GruPHstError::VaultNotExists(String::from("!exists")))
+    );
+}
+
+#[test]
+fn should_keep_neighbors_after_deleting_edges() {
+    let (mut graphs, frodo, sam, _gandalf, the_shire) = prepare_adjacency_test();
+    let edge_id = graphs.get_edges(None).unwrap()[0].get_id();
+    graphs.delete_edge_by_id(edge_id, None).unwrap();
+    // the edges keep their order
+    let edges: Vec<(String, String)> = graphs
+        .get_edges(None)
+        .unwrap()
+        .iter()
+        .map(|edge| (edge.get_from_vertex().get_label(), edge.get_relation()))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("Sam".to_string(), "friend of".to_string()),
+            ("Gandalf".to_string(), "friend of".to_string()),
+            ("Frodo".to_string(), "lives at".to_string()),
+            ("Sam".to_string(), "lives at".to_string()),
+        ]
+    );
+    assert_eq!(
+        graphs.neighbors_out(&frodo.get_id(), Some("friend of"), None),
+        Err(GruPHstError::EdgeNoRelations(String::from("out")))
+    );
+    let inhabitants = graphs
+        .neighbors_in(&the_shire.get_id(), Some("lives at"), None)
+        .unwrap();
+    assert_eq!(labels(inhabitants), vec!["Frodo", "Sam"]);
+    let friends = graphs
+        .neighbors_out(&sam.get_id(), Some("friend of"), None)
+        .unwrap();
+    assert_eq!(labels(friends), vec!["Frodo"]);
+    let friendly = graphs
+        .find_vertices_with_relation_out("friend of", None)
+        .unwrap();
+    assert_eq!(labels(friendly), vec!["Gandalf", "Sam"]);
+    let befriended = graphs
+        .find_vertices_with_relation_in("friend of", None)
+        .unwrap();
+    assert_eq!(labels(befriended), vec!["Frodo"]);
+}
+
+#[test]
+fn should_keep_neighbors_after_updating_edges() {
+    let (mut graphs, frodo, sam, gandalf, _the_shire) = prepare_adjacency_test();
+    let mut edge = graphs.get_edges(None).unwrap()[0].clone();
+    edge.update_to(&gandalf);
+    edge.update_relation("follows");
+    graphs.update_graph(&edge, None).unwrap();
+    let followed = graphs
+        .neighbors_out(&frodo.get_id(), Some("follows"), None)
+        .unwrap();
+    assert_eq!(labels(followed), vec!["Gandalf"]);
+    assert!(graphs
+        .neighbors_in(&sam.get_id(), Some("friend of"), None)
+        .is_err());
+    let all = graphs.neighbors_out(&frodo.get_id(), None, None).unwrap();
+    assert_eq!(labels(all), vec!["Gandalf", "The Shire"]);
+}
+
+#[test]
+fn should_index_a_collection_of_edges() {
+    let (mut graphs, frodo, _sam, _gandalf, _the_shire) = prepare_adjacency_test();
+    let merry = Vertex::new("Merry");
+    let pippin = Vertex::new("Pippin");
+    let mut edges = vec![
+        Edge::create(&frodo, "cousin of", &merry),
+        Edge::create(&frodo, "cousin of", &pippin),
+    ];
+    graphs.add_edges(&mut edges, Some("hobbits")).unwrap();
+    let cousins = graphs
+        .neighbors_out(&frodo.get_id(), Some("cousin of"), Some("hobbits"))
+        .unwrap();
+    assert_eq!(labels(cousins), vec!["Merry", "Pippin"]);
+    assert!(graphs
+        .neighbors_out(&frodo.get_id(), Some("cousin of"), Some("middle-earth"))
+        .is_err());
+}
+
+#[test]
+fn should_rebuild_neighbors_after_load() {
+    let (graphs, frodo, _sam, _gandalf, _the_shire) = prepare_adjacency_test();
+    let path = "./tests/data/";
+    graphs.save(Some(path)).unwrap();
+    let loaded = Graphs::load(&format!("{}{}.grphst", path, graphs.get_label())).unwrap();
+    let all = loaded.neighbors_out(&frodo.get_id(), None, None).unwrap();
+    assert_eq!(labels(all), vec!["Sam", "The Shire"]);
+}
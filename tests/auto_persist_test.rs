@@ -0,0 +1,148 @@
+use std::{fs, path::Path, thread, time::Duration};
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{AutoPersist, EncryptionKey, Graphs},
+    vertex::Vertex,
+};
+
+const PATH: &str = "./tests/data/";
+
+fn file_name(label: &str) -> String {
+    format!("{}{}.grphst", PATH, label)
+}
+
+fn prepare_auto_persist_test(label: &str, policy: AutoPersist) -> Graphs {
+    let _ = fs::remove_file(file_name(label));
+    let mut graphs = Graphs::init(label);
+    assert_eq!(graphs.get_auto_persist(), AutoPersist::Off);
+    graphs.set_auto_persist(policy, Some(PATH));
+    assert_eq!(graphs.get_auto_persist(), policy);
+    graphs
+}
+
+fn saved_len(label: &str) -> Option<usize> {
+    Graphs::load(&file_name(label))
+        .ok()
+        .map(|graphs| graphs.len())
+}
+
+fn add_friend(graphs: &mut Graphs) {
+    let edge = Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam"));
+    graphs.add_edge(&edge, None).unwrap();
+}
+
+#[test]
+fn should_auto_persist_every_mutations() {
+    let label = "auto-persist-mutations";
+    let mut graphs = prepare_auto_persist_test(label, AutoPersist::EveryMutations(3));
+    add_friend(&mut graphs);
+    add_friend(&mut graphs);
+    assert_eq!(saved_len(label), None);
+    add_friend(&mut graphs);
+    assert_eq!(saved_len(label), Some(3));
+    add_friend(&mut graphs);
+    assert_eq!(saved_len(label), Some(3));
+
+    // the mutations not saved yet are flushed on drop
+    drop(graphs);
+    assert_eq!(saved_len(label), Some(4));
+    fs::remove_file(file_name(label)).unwrap();
+}
+
+#[test]
+fn should_auto_persist_on_write() {
+    let label = "auto-persist-write";
+    let mut graphs = prepare_auto_persist_test(label, AutoPersist::OnWrite);
+    let frodo = Vertex::new("Frodo");
+    graphs.add_vertex(&frodo, None).unwrap();
+    graphs
+        .set_vertex_attr(&frodo.get_id(), "years old", 50, None)
+        .unwrap();
+    let saved = Graphs::load(&file_name(label)).unwrap();
+    let saved_frodo = saved.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    assert_eq!(saved_frodo.get_attr_i64("years old").unwrap(), 50);
+
+    let mut edges = vec![
+        Edge::create(&frodo, "friend of", &Vertex::new("Sam")),
+        Edge::create(&frodo, "lives at", &Vertex::new("The Shire")),
+    ];
+    graphs.add_edges(&mut edges, None).unwrap();
+    assert_eq!(saved_len(label), Some(2));
+
+    graphs.set_auto_persist(AutoPersist::Off, Some(PATH));
+    add_friend(&mut graphs);
+    drop(graphs);
+    assert_eq!(saved_len(label), Some(2));
+    fs::remove_file(file_name(label)).unwrap();
+}
+
+#[test]
+fn should_auto_persist_after_an_interval() {
+    let label = "auto-persist-interval";
+    let mut graphs =
+        prepare_auto_persist_test(label, AutoPersist::Interval(Duration::from_millis(50)));
+    add_friend(&mut graphs);
+    assert_eq!(saved_len(label), None);
+    thread::sleep(Duration::from_millis(60));
+    add_friend(&mut graphs);
+    assert_eq!(saved_len(label), Some(2));
+
+    graphs.flush().unwrap();
+    drop(graphs);
+    assert_eq!(saved_len(label), Some(2));
+    fs::remove_file(file_name(label)).unwrap();
+}
+
+#[test]
+fn should_not_auto_persist_a_cloned_graphs() {
+    let label = "auto-persist-cloned";
+    let mut graphs = prepare_auto_persist_test(label, AutoPersist::EveryMutations(10));
+    add_friend(&mut graphs);
+    let mut cloned = graphs.clone();
+    assert_eq!(cloned.get_auto_persist(), AutoPersist::Off);
+    add_friend(&mut cloned);
+    drop(cloned);
+    assert!(!Path::new(&file_name(label)).exists());
+
+    drop(graphs);
+    assert_eq!(saved_len(label), Some(1));
+    fs::remove_file(file_name(label)).unwrap();
+}
+
+#[test]
+fn should_auto_persist_encrypted_after_saved_encrypted() {
+    let label = "auto-persist-encrypted";
+    let mut graphs = prepare_auto_persist_test(label, AutoPersist::OnWrite);
+    let key = EncryptionKey::new([42; 32]);
+    graphs.save_encrypted(Some(PATH), &key).unwrap();
+    add_friend(&mut graphs);
+
+    let load_error = |key: Option<&EncryptionKey>| {
+        let err = match key {
+            Some(key) => Graphs::load_encrypted(&file_name(label), key).unwrap_err(),
+            None => Graphs::load(&file_name(label)).unwrap_err(),
+        };
+        err.downcast::<GruPHstError>().map(|err| *err).unwrap()
+    };
+    assert_eq!(load_error(None), GruPHstError::SnapshotEncrypted);
+    assert_eq!(
+        load_error(Some(&EncryptionKey::new([7; 32]))),
+        GruPHstError::SnapshotWrongKey
+    );
+    let loaded = Graphs::load_encrypted(&file_name(label), &key).unwrap();
+    assert_eq!(loaded.len(), 1);
+
+    // the loaded Graphs keeps the key, also flushing on drop
+    let mut loaded = loaded;
+    loaded.set_auto_persist(AutoPersist::EveryMutations(10), Some(PATH));
+    add_friend(&mut loaded);
+    drop(loaded);
+    assert_eq!(load_error(None), GruPHstError::SnapshotEncrypted);
+    let loaded = Graphs::load_encrypted(&file_name(label), &key).unwrap();
+    assert_eq!(loaded.len(), 2);
+    drop(loaded);
+    drop(graphs);
+    fs::remove_file(file_name(label)).unwrap();
+}
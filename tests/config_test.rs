@@ -1,4 +1,5 @@
 use gruphst::config::*;
+use gruphst::graphs::{AutoPersist, Compression, MemoryPolicy};
 
 #[test]
 fn should_retrieve_max_memory_configuration() {
@@ -18,3 +19,24 @@ fn should_retrieve_csv_delimiter_character_configuration() {
     let csv_delimiter: u8 = get_csv_delimiter();
     assert_eq!(csv_delimiter, b';');
 }
+
+#[test]
+fn should_retrieve_compression_configuration() {
+    // lz4 is not a valid value without the lz4 feature
+    #[cfg(feature = "lz4")]
+    assert_eq!(get_compression(), Compression::Lz4);
+    #[cfg(not(feature = "lz4"))]
+    assert_eq!(get_compression(), Compression::None);
+}
+
+#[test]
+fn should_retrieve_auto_persist_configuration() {
+    assert_eq!(get_auto_persist(), AutoPersist::Off);
+    assert_eq!(get_auto_persist_path(), None);
+}
+
+#[test]
+fn should_retrieve_memory_policy_configuration() {
+    assert_eq!(get_memory_policy(), MemoryPolicy::Panic);
+    assert_eq!(get_eviction_path(), None);
+}
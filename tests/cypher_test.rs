@@ -0,0 +1,247 @@
+use gruphst::{
+    attributes::AttrValue,
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::cypher::{QueryResult, QueryValue},
+    graphs::Graphs,
+    vertex::Vertex,
+};
+
+fn prepare_cypher_test() -> Graphs {
+    let mut graphs = Graphs::init("middle-earth");
+    let mut gandalf = Vertex::new("Gandalf");
+    gandalf.set_attr("name", "Gandalf");
+    gandalf.set_attr("years old", 24000);
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("name", "Frodo Baggins");
+    frodo.set_attr("years old", 50);
+    let mut sam = Vertex::new("Sam");
+    sam.set_attr("name", "Samwise Gamgee");
+    sam.set_attr("years old", 38);
+    let mut the_shire = Vertex::new("The Shire");
+    the_shire.set_attr("type", "region");
+
+    let mut edge = Edge::create(&gandalf, "friend of", &frodo);
+    edge.set_attr("since", 2941);
+    graphs.add_edge(&edge, None).unwrap();
+    let mut edge = Edge::create(&sam, "friend of", &frodo);
+    edge.set_attr("since", 2980);
+    graphs.add_edge(&edge, None).unwrap();
+    graphs
+        .add_edge(&Edge::create(&frodo, "lives at", &the_shire), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&sam, "lives at", &the_shire), None)
+        .unwrap();
+
+    graphs
+}
+
+fn labels(result: &QueryResult, column: &str) -> Vec<String> {
+    result
+        .get_column(column)
+        .unwrap()
+        .iter()
+        .map(|value| match value {
+            QueryValue::Vertex(vertex) => vertex.get_label(),
+            QueryValue::Edge(edge) => edge.get_relation(),
+            QueryValue::Edges(edges) => format!("{} edges", edges.len()),
+            QueryValue::Value(value) => value.to_string(),
+            QueryValue::Null => "null".to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn should_match_relation_with_where_clause() {
+    let graphs = prepare_cypher_test();
+    let result = graphs
+        .query(
+            r#"MATCH (a)-[:"friend of"]->(b) WHERE a.name = "Gandalf" RETURN b"#,
+            None,
+        )
+        .unwrap();
+    assert_eq!(result.get_columns(), vec!["b"]);
+    assert_eq!(labels(&result, "b"), vec!["Frodo"]);
+
+    let result = graphs
+        .query(
+            r#"match (a:Sam)-[r]->(b) return type(r) as rel, label(b) order"#,
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        result,
+        GruPHstError::QuerySyntax(55, "expected end of query, found 'order'".to_string())
+    );
+
+    let result = graphs
+        .query(
+            "match (a:Sam)-[r]->(b) return type(r) as rel, label(b)",
+            Some("middle-earth"),
+        )
+        .unwrap();
+    assert_eq!(result.get_columns(), vec!["rel", "label(b)"]);
+    assert_eq!(labels(&result, "rel"), vec!["friend of", "lives at"]);
+    assert_eq!(labels(&result, "label(b)"), vec!["Frodo", "The Shire"]);
+}
+
+#[test]
+fn should_match_directions_and_properties() {
+    let graphs = prepare_cypher_test();
+    let result = graphs
+        .query(
+            r#"MATCH (p)-[:"lives at"]->(:"The Shire" {type: "region"}) RETURN p.name"#,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        labels(&result, "p.name"),
+        vec!["Frodo Baggins", "Samwise Gamgee"]
+    );
+
+    let result = graphs
+        .query(
+            r#"MATCH (f:Frodo)<-[r:"friend of" {since: 2941}]-(who) RETURN who, r"#,
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(&result, "who"), vec!["Gandalf"]);
+    assert_eq!(labels(&result, "r"), vec!["friend of"]);
+
+    let result = graphs
+        .query("MATCH (f:Frodo)-[]-(n) RETURN label(n) AS neighbor", None)
+        .unwrap();
+    let mut neighbors = labels(&result, "neighbor");
+    neighbors.sort();
+    assert_eq!(neighbors, vec!["Gandalf", "Sam", "The Shire"]);
+}
+
+#[test]
+fn should_filter_with_boolean_conditions() {
+    let graphs = prepare_cypher_test();
+    let result = graphs
+        .query(
+            "MATCH (a)-[r]->(b) \
+             WHERE (a.`years old` > 100 OR r.since >= 2980) AND NOT b.name CONTAINS \"Shire\" \
+             RETURN a.name, r.since",
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(&result, "a.name"), vec!["Gandalf", "Samwise Gamgee"]);
+    assert_eq!(
+        result.get_column("r.since").unwrap(),
+        vec![
+            QueryValue::Value(AttrValue::Int(2941)),
+            QueryValue::Value(AttrValue::Int(2980))
+        ]
+    );
+
+    let result = graphs
+        .query(
+            "MATCH (a)-[r]->(b) WHERE a.`years old` < 40.5 RETURN r.since, b.type",
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(&result, "r.since"), vec!["2980", "null"]);
+    assert_eq!(labels(&result, "b.type"), vec!["null", "region"]);
+}
+
+#[test]
+fn should_not_match_null_comparisons_under_not() {
+    let graphs = prepare_cypher_test();
+    let result = graphs
+        .query("MATCH (n) WHERE NOT n.`years old` > 40 RETURN n", None)
+        .unwrap();
+    assert_eq!(labels(&result, "n"), vec!["Sam"]);
+
+    let result = graphs
+        .query(
+            "MATCH (n) WHERE NOT (n.type = \"region\" AND n.`years old` > 40) RETURN n",
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(&result, "n"), vec!["Sam"]);
+}
+
+#[test]
+fn should_compare_with_negative_numbers() {
+    let mut graphs = prepare_cypher_test();
+    let mut mordor = Vertex::new("Mordor");
+    mordor.set_attr("temperature", -5);
+    let mut rivendell = Vertex::new("Rivendell");
+    rivendell.set_attr("temperature", 12.5);
+    graphs
+        .add_edge(&Edge::create(&mordor, "far from", &rivendell), None)
+        .unwrap();
+
+    let result = graphs
+        .query("MATCH (n) WHERE n.temperature < -2.5 RETURN n", None)
+        .unwrap();
+    assert_eq!(labels(&result, "n"), vec!["Mordor"]);
+
+    let result = graphs
+        .query(
+            "MATCH (n) WHERE -5 = n.temperature OR n.temperature > -1 RETURN n",
+            None,
+        )
+        .unwrap();
+    let mut places = labels(&result, "n");
+    places.sort();
+    assert_eq!(places, vec!["Mordor", "Rivendell"]);
+
+    let result = graphs
+        .query("MATCH (n {temperature: -5})-[]->(m) RETURN m", None)
+        .unwrap();
+    assert_eq!(labels(&result, "m"), vec!["Rivendell"]);
+}
+
+#[test]
+fn should_return_distinct_and_limited_rows() {
+    let graphs = prepare_cypher_test();
+    let result = graphs
+        .query("MATCH (a)-[:`friend of`]->(b) RETURN DISTINCT b", None)
+        .unwrap();
+    assert_eq!(labels(&result, "b"), vec!["Frodo"]);
+
+    let result = graphs.query("MATCH (n) RETURN n LIMIT 2", None).unwrap();
+    assert_eq!(labels(&result, "n"), vec!["Frodo", "Gandalf"]);
+
+    let result = graphs.query("MATCH (n:Saruman) RETURN n", None).unwrap();
+    assert!(result.is_empty());
+    assert!(result.get_column("m").is_none());
+}
+
+#[test]
+fn should_fail_on_not_valid_queries() {
+    let graphs = prepare_cypher_test();
+    assert_eq!(
+        graphs.query("MATCH (a)-[:knows]->(b) RETURN c", None),
+        Err(GruPHstError::QueryUnknownVariable(31, "c".to_string()))
+    );
+    assert_eq!(
+        graphs.query("MATCH (a RETURN a", None),
+        Err(GruPHstError::QuerySyntax(
+            9,
+            "expected ')' to close a node, found 'RETURN'".to_string()
+        ))
+    );
+    assert_eq!(
+        graphs.query("MATCH (a) WHERE a.name = \"Gandalf RETURN a", None),
+        Err(GruPHstError::QuerySyntax(
+            25,
+            "unterminated quoted text".to_string()
+        ))
+    );
+    assert_eq!(
+        graphs.query("MATCH (a)<-[]->(b) RETURN a", None),
+        Err(GruPHstError::QuerySyntax(
+            13,
+            "a relationship can not point to both sides, found '->'".to_string()
+        ))
+    );
+    assert_eq!(
+        graphs.query("MATCH (a) RETURN a", Some("moria")),
+        Err(GruPHstError::VaultNotExists("moria".to_string()))
+    );
+}
@@ -1,4 +1,5 @@
 use gruphst::config::*;
+use gruphst::graphs::{AutoPersist, Compression, MemoryPolicy};
 use std::fs;
 
 #[test]
@@ -15,5 +16,13 @@ fn should_retrieve_default_max_memory_and_logging_level_configuration() {
     let config_csv_delimiter: u8 = get_csv_delimiter();
     assert_eq!(config_csv_delimiter, b';');
 
+    assert_eq!(get_compression(), Compression::None);
+
+    assert_eq!(get_auto_persist(), AutoPersist::Off);
+    assert_eq!(get_auto_persist_path(), None);
+
+    assert_eq!(get_memory_policy(), MemoryPolicy::Panic);
+    assert_eq!(get_eviction_path(), None);
+
     fs::rename("env", ".env").unwrap();
 }
@@ -0,0 +1,180 @@
+use std::fs;
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{DeleteMode, Graphs, IndexKind, IndexTarget},
+    vertex::Vertex,
+};
+
+fn prepare_delete_test() -> (Graphs, Vertex) {
+    let mut graphs = Graphs::init("fellowship");
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("race", "hobbit");
+    let mut sam = Vertex::new("Sam");
+    sam.set_attr("race", "hobbit");
+    let gandalf = Vertex::new("Gandalf");
+    graphs
+        .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&gandalf, "guide of", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&gandalf, "friend of", &sam), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&frodo, "talks to", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+            Some("shire"),
+        )
+        .unwrap();
+    graphs.set_label("fellowship");
+    (graphs, frodo)
+}
+
+fn relations(graphs: &Graphs, vault_name: Option<&str>) -> Vec<String> {
+    let mut relations: Vec<String> = graphs
+        .get_edges(vault_name)
+        .unwrap()
+        .iter()
+        .map(|edge| edge.get_relation())
+        .collect();
+    relations.sort();
+    relations
+}
+
+#[test]
+fn should_delete_a_vertex_with_its_edges() {
+    let (mut graphs, frodo) = prepare_delete_test();
+    graphs
+        .create_index("race", IndexTarget::Vertex, IndexKind::Hash, None)
+        .unwrap();
+    let id = frodo.get_id();
+    assert_eq!(
+        graphs.delete_vertex(&id, DeleteMode::Restrict, None),
+        Err(GruPHstError::VertexHasEdges(id.clone()))
+    );
+    assert_eq!(graphs.len(), 5);
+
+    graphs.delete_vertex(&id, DeleteMode::Detach, None).unwrap();
+    assert_eq!(relations(&graphs, None), vec!["friend of"]);
+    assert_eq!(
+        graphs.find_vertex_by_id(&id, None),
+        Err(GruPHstError::VertexNotFound)
+    );
+    let hobbits = graphs.find_vertices_with_attr_equals_to("race", "hobbit", None);
+    assert_eq!(hobbits.unwrap()[0].get_label(), "Sam");
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+    // the other vaults keep the vertex
+    assert_eq!(relations(&graphs, Some("shire")), vec!["lives at"]);
+
+    assert_eq!(
+        graphs.delete_vertex(&id, DeleteMode::Detach, None),
+        Err(GruPHstError::VertexNotFound)
+    );
+    assert_eq!(
+        graphs.delete_vertex(&id, DeleteMode::Detach, Some("mordor")),
+        Err(GruPHstError::VaultNotExists(String::from("mordor")))
+    );
+
+    // a vertex without edges is deleted on either mode
+    let bilbo = Vertex::new("Bilbo");
+    graphs.add_vertex(&bilbo, None).unwrap();
+    graphs
+        .delete_vertex(&bilbo.get_id(), DeleteMode::Restrict, None)
+        .unwrap();
+    assert_eq!(graphs.get_vertices(None).unwrap().len(), 2);
+}
+
+#[test]
+fn should_delete_a_vertex_from_every_vault() {
+    let (mut graphs, frodo) = prepare_delete_test();
+    let id = frodo.get_id();
+    assert_eq!(
+        graphs.delete_vertex_in_graphs(&id, DeleteMode::Restrict),
+        Err(GruPHstError::VertexHasEdges(id.clone()))
+    );
+    assert_eq!(relations(&graphs, Some("shire")), vec!["lives at"]);
+    assert_eq!(graphs.len(), 5);
+
+    graphs
+        .delete_vertex_in_graphs(&id, DeleteMode::Detach)
+        .unwrap();
+    assert_eq!(graphs.len(), 1);
+    assert_eq!(
+        graphs.find_vertex_by_id_in_graphs(&id),
+        Err(GruPHstError::VertexNotFound)
+    );
+    assert_eq!(graphs.get_vertices(Some("shire")).unwrap().len(), 1);
+    assert_eq!(
+        graphs.delete_vertex_in_graphs(&id, DeleteMode::Detach),
+        Err(GruPHstError::VertexNotFound)
+    );
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+}
+
+#[test]
+fn should_persist_the_deleted_vertices() {
+    let dir = "tests/data/wal-delete";
+    let _ = fs::remove_dir_all(dir);
+    let (graphs, frodo) = prepare_delete_test();
+    {
+        let mut opened = Graphs::open(dir).unwrap();
+        for vault_name in ["fellowship", "shire"] {
+            opened
+                .add_edges(
+                    &mut graphs.get_edges(Some(vault_name)).unwrap(),
+                    Some(vault_name),
+                )
+                .unwrap();
+        }
+        opened
+            .delete_vertex(&frodo.get_id(), DeleteMode::Detach, Some("fellowship"))
+            .unwrap();
+    }
+    let mut opened = Graphs::open(dir).unwrap();
+    assert_eq!(relations(&opened, Some("fellowship")), vec!["friend of"]);
+    assert_eq!(relations(&opened, Some("shire")), vec!["lives at"]);
+    opened
+        .delete_vertex_in_graphs(&frodo.get_id(), DeleteMode::Detach)
+        .unwrap();
+    drop(opened);
+    let opened = Graphs::open(dir).unwrap();
+    assert_eq!(
+        opened.find_vertex_by_id_in_graphs(&frodo.get_id()),
+        Err(GruPHstError::VertexNotFound)
+    );
+    drop(opened);
+    fs::remove_dir_all(dir).unwrap();
+
+    // reaches the vaults of a store not loaded yet
+    let dir = "./tests/data/store-delete";
+    let (mut graphs, frodo) = prepare_delete_test();
+    graphs.save_store(dir).unwrap();
+    let mut stored = Graphs::open_store(dir).unwrap();
+    stored
+        .delete_vertex_in_graphs(&frodo.get_id(), DeleteMode::Detach)
+        .unwrap();
+    assert_eq!(relations(&stored, Some("fellowship")), vec!["friend of"]);
+    assert!(stored.get_edges(Some("shire")).is_err());
+
+    // not deleted from any vault when one of them could not be loaded
+    let mut stored = Graphs::open_store(dir).unwrap();
+    let before = relations(&stored, Some("fellowship"));
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.to_string_lossy().ends_with(".vault.grphst") {
+            fs::write(path, b"not a vault").unwrap();
+        }
+    }
+    assert!(matches!(
+        stored.delete_vertex_in_graphs(&frodo.get_id(), DeleteMode::Detach),
+        Err(GruPHstError::VaultEvicted(_))
+    ));
+    assert_eq!(relations(&stored, Some("fellowship")), before);
+    fs::remove_dir_all(dir).unwrap();
+}
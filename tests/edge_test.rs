@@ -1,4 +1,6 @@
-use gruphst::{edge::Edge, errors::GruPHstError, vertex::Vertex};
+#![allow(clippy::needless_borrow)]
+
+use gruphst::{attributes::AttrValue, edge::Edge, errors::GruPHstError, vertex::Vertex};
 
 fn prepare_edge_test() -> (Edge, String) {
     let mut alice = Vertex::new("alice");
@@ -266,3 +268,26 @@ fn should_not_find_vertex_by_id() {
     let (edge, _id) = prepare_edge_test();
     assert!(edge.find_vertex_by_id("foobar").is_err());
 }
+
+#[test]
+fn should_get_typed_edge_attributes() {
+    let (mut edge, _id) = prepare_edge_test();
+    edge.set_attr("km", 300.5);
+    edge.set_attr("since", AttrValue::now());
+    edge.set_attr_vec_u8("code", &[1, 0, 1]);
+
+    assert_eq!(edge.get_attr_i64("value").unwrap(), 2);
+    assert_eq!(edge.get_attr_f64("km").unwrap(), 300.5);
+    assert!(edge.get_attr_timestamp("since").unwrap() > 0);
+    assert_eq!(edge.get_attr_vec_u8("code").unwrap(), vec![1, 0, 1]);
+    assert!(edge.has_attr_equals_to("value", 2));
+    assert_eq!(
+        edge.get_attr_f64("type"),
+        Err(GruPHstError::AttributeTypeMismatch(
+            String::from("type"),
+            String::from("float")
+        ))
+    );
+    edge.update_attr("value", 3.5).unwrap();
+    assert_eq!(edge.get_attr_value("value").unwrap(), AttrValue::Float(3.5));
+}
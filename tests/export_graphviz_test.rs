@@ -1,3 +1,5 @@
+#![allow(clippy::vec_init_then_push, clippy::explicit_counter_loop, clippy::bool_assert_comparison)]
+
 use gruphst::graphs::Graphs;
 use gruphst::vertex::Vertex;
 use gruphst::{edge::Edge, exporter_importer::graphviz::export_to_graphviz_format};
@@ -16,16 +18,17 @@ fn prepare_export_graphviz_test() -> Graphs {
 
     let edge = Edge::create(&gandalf, "friend of", &frodo);
 
-    gru.add_edge(&edge, None);
-    gru.add_edge(&Edge::create(&frodo, "friend of", &gandalf), None);
+    gru.add_edge(&edge, None).unwrap();
+    gru.add_edge(&Edge::create(&frodo, "friend of", &gandalf), None)
+        .unwrap();
 
     let mut sam_v = Vertex::new("sam");
     sam_v.set_attr("surname", "Gamgee");
 
     let edge2 = Edge::create(&sam_v, "best friend of", &frodo);
-    gru.add_edge(&edge2, None);
+    gru.add_edge(&edge2, None).unwrap();
 
-    gru.insert("middle-earth-enemies");
+    gru.insert("middle-earth-enemies").unwrap();
     let mut saruman = Vertex::new("saruman");
     saruman.set_attr("known as", "Saruman of Many Colours");
     let mut sauron = Vertex::new("sauron");
@@ -34,11 +37,14 @@ fn prepare_export_graphviz_test() -> Graphs {
     let edge3 = Edge::create(&saruman, "ally of", &sauron);
     let edge4 = Edge::create(&sauron, "lord of", &saruman);
 
-    gru.add_edges(&mut vec![edge3, edge4], None);
+    gru.add_edges(&mut vec![edge3, edge4], None).unwrap();
 
-    gru.add_edge(&Edge::create(&gandalf, "enemy of", &sauron), None);
-    gru.add_edge(&Edge::create(&gandalf, "enemy of", &saruman), None);
-    gru.add_edge(&Edge::create(&sauron, "wants to catch", &frodo), None);
+    gru.add_edge(&Edge::create(&gandalf, "enemy of", &sauron), None)
+        .unwrap();
+    gru.add_edge(&Edge::create(&gandalf, "enemy of", &saruman), None)
+        .unwrap();
+    gru.add_edge(&Edge::create(&sauron, "wants to catch", &frodo), None)
+        .unwrap();
 
     gru
 }
@@ -1,3 +1,5 @@
+#![allow(clippy::op_ref)]
+
 use gruphst::edge::Edge;
 use gruphst::exporter_importer::csv::*;
 use gruphst::graphs::Graphs;
@@ -16,15 +18,15 @@ fn prepare_export_import_csv_test() -> Graphs {
 
     let edge = Edge::create(&gandalf_v, "friend of", &frodo_v);
 
-    gru.add_edge(&edge, None);
+    gru.add_edge(&edge, None).unwrap();
 
     let mut sam_v = Vertex::new("sam");
     sam_v.set_attr("surname", "Gamgee");
 
     let edge2 = Edge::create(&sam_v, "best friend of", &frodo_v);
-    gru.add_edge(&edge2, None);
+    gru.add_edge(&edge2, None).unwrap();
 
-    gru.insert("middle-earth-enemies");
+    gru.insert("middle-earth-enemies").unwrap();
     let mut saruman = Vertex::new("saruman");
     saruman.set_attr("known as", "Saruman of Many Colours");
     let mut sauron = Vertex::new("sauron");
@@ -33,7 +35,7 @@ fn prepare_export_import_csv_test() -> Graphs {
     let edge3 = Edge::create(&saruman, "ally of", &sauron);
     let edge4 = Edge::create(&sauron, "lord of", &saruman);
 
-    gru.add_edges(&mut vec![edge3, edge4], None);
+    gru.add_edges(&mut vec![edge3, edge4], None).unwrap();
 
     gru
 }
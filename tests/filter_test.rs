@@ -0,0 +1,162 @@
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::filter::{Filter, Side},
+    graphs::Graphs,
+    vertex::Vertex,
+};
+
+fn prepare_filter_test() -> Graphs {
+    let mut graphs = Graphs::init("middle-earth");
+    let mut gandalf = Vertex::new("Gandalf");
+    gandalf.set_attr("years old", 24000);
+    gandalf.set_attr("known as", "The Gray");
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("years old", 50);
+    frodo.set_attr("surname", "Baggins");
+    let mut sam = Vertex::new("Sam");
+    sam.set_attr("surname", "Gamgee");
+    let the_shire = Vertex::new("The Shire");
+
+    let mut edge = Edge::create(&gandalf, "friend of", &frodo);
+    edge.set_attr("duration in years", 42);
+    graphs.add_edge(&edge, None).unwrap();
+    let mut edge = Edge::create(&sam, "friend of", &frodo);
+    edge.set_attr("duration in years", 33);
+    edge.set_attr("type", "best friendship");
+    graphs.add_edge(&edge, None).unwrap();
+    graphs
+        .add_edge(&Edge::create(&frodo, "lives at", &the_shire), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&sam, "lives at", &the_shire), None)
+        .unwrap();
+
+    let sauron = Vertex::new("Sauron");
+    graphs
+        .add_edge(
+            &Edge::create(&sauron, "enemy of", &gandalf),
+            Some("enemies"),
+        )
+        .unwrap();
+
+    graphs
+}
+
+fn describe(edges: Vec<&Edge>) -> Vec<String> {
+    edges
+        .iter()
+        .map(|e| {
+            format!(
+                "{} {} {}",
+                e.get_from_vertex().get_label(),
+                e.get_relation(),
+                e.get_to_vertex().get_label()
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn should_query_edges_by_relation_and_labels() {
+    let graphs = prepare_filter_test();
+    let filter = Filter::relation("friend of").and(Filter::label(Side::From, "Sam"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(describe(edges), vec!["Sam friend of Frodo"]);
+
+    let filter = Filter::relation("lives at").or(Filter::label_like(Side::To, "FRO"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(edges.len(), 4);
+
+    let filter = Filter::label(Side::Any, "Frodo").and(!Filter::relation("lives at"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(
+        describe(edges),
+        vec!["Gandalf friend of Frodo", "Sam friend of Frodo"]
+    );
+}
+
+#[test]
+fn should_query_edges_by_vertex_attributes_on_sides() {
+    let graphs = prepare_filter_test();
+    let filter = Filter::vertex_attr_key(Side::Both, "years old");
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(describe(edges), vec!["Gandalf friend of Frodo"]);
+
+    let filter = Filter::vertex_attr_equals(Side::To, "years old", 50.0)
+        .and(Filter::vertex_attr_like(Side::From, "surname", "gam"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(describe(edges), vec!["Sam friend of Frodo"]);
+
+    let filter = Filter::vertex_attr_in_range(Side::From, "years old", 1000..)
+        .or(Filter::vertex_attr_key_like(Side::From, "SURN"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(edges.len(), 4);
+
+    let filter = Filter::vertex_id(
+        Side::From,
+        &graphs
+            .find_vertices_with_relation_out("lives at", Some("middle-earth"))
+            .unwrap()[0]
+            .get_id(),
+    );
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(describe(edges), vec!["Frodo lives at The Shire"]);
+}
+
+#[test]
+fn should_query_edges_by_edge_attributes() {
+    let graphs = prepare_filter_test();
+    let filter =
+        Filter::attr_in_range("duration in years", 40..50).or(Filter::attr_like("type", "BEST"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(edges.len(), 2);
+
+    let filter = Filter::attr_equals("duration in years", 33).and(Filter::attr_key("type"));
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(describe(edges), vec!["Sam friend of Frodo"]);
+
+    let filter = !Filter::attr_key_like("DURATION");
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(edges.len(), 2);
+}
+
+#[test]
+fn should_combine_many_filters() {
+    let graphs = prepare_filter_test();
+    let filter = Filter::all(vec![
+        Filter::relation("friend of"),
+        Filter::label(Side::To, "Frodo"),
+        !!Filter::attr_key("type"),
+    ]);
+    let edges = graphs.query_edges(&filter, Some("middle-earth")).unwrap();
+    assert_eq!(edges.len(), 1);
+    let filter = Filter::any(vec![
+        Filter::relation("enemy of"),
+        Filter::relation("lives at"),
+    ]);
+    assert_eq!(graphs.query_edges_in_graphs(&filter).unwrap().len(), 3);
+    assert!(Filter::all(vec![]).matches(graphs.query_edges_in_graphs(&filter).unwrap()[0]));
+}
+
+#[test]
+fn should_fail_querying_edges_without_matches() {
+    let graphs = prepare_filter_test();
+    let filter = Filter::relation("enemy of");
+    assert_eq!(
+        graphs
+            .query_edges(&filter, Some("middle-earth"))
+            .unwrap_err(),
+        GruPHstError::EdgeNotFound
+    );
+    assert_eq!(
+        graphs.query_edges(&filter, Some("foobar")).unwrap_err(),
+        GruPHstError::VaultNotExists(String::from("foobar"))
+    );
+    assert_eq!(
+        graphs
+            .query_edges_in_graphs(&Filter::any(vec![]))
+            .unwrap_err(),
+        GruPHstError::EdgeNotFound
+    );
+}
@@ -14,20 +14,30 @@ pub fn prepare_graphs_test() -> Graphs {
     let code: Vec<u8> = vec![3, 1, 3, 3, 7];
     fred.set_attr_vec_u8("code", &code);
 
-    graphs.add_edge(&Edge::create(&alice, "friend of", &bob), None);
-    graphs.add_edge(&Edge::create(&bob, "friend of", &alice), None);
-    graphs.add_edge(&Edge::create(&fred, "relative of", &alice), None);
-    graphs.add_edge(&Edge::create(&fred, "friend of", &bob), None);
+    graphs
+        .add_edge(&Edge::create(&alice, "friend of", &bob), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&bob, "friend of", &alice), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&fred, "relative of", &alice), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&fred, "friend of", &bob), None)
+        .unwrap();
 
     graphs
 }
 
 pub fn prepare_insert_graph_test(graphs: &mut Graphs) {
-    graphs.insert("middle-earth");
-    graphs.add_edge(
-        &Edge::create(&Vertex::new("Gandalf"), "enemy of", &Vertex::new("Saruman")),
-        Some("middle-earth"),
-    );
+    graphs.insert("middle-earth").unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Gandalf"), "enemy of", &Vertex::new("Saruman")),
+            Some("middle-earth"),
+        )
+        .unwrap();
 }
 
 pub fn prepare_vector_edges() -> Vec<Edge> {
@@ -79,7 +89,7 @@ fn should_insert_a_graph_into_the_vault() {
     let mut graphs = prepare_graphs_test();
     assert_eq!(graphs.len_graphs(), 1);
     assert_eq!(graphs.len(), 4);
-    graphs.insert("middle-earth");
+    graphs.insert("middle-earth").unwrap();
     assert_eq!(graphs.len_graphs(), 2);
     assert_eq!(graphs.len(), 4);
     prepare_insert_graph_test(&mut graphs);
@@ -90,7 +100,7 @@ fn should_insert_a_graph_into_the_vault() {
 fn should_remove_graph_from_the_vault() {
     let mut graphs = prepare_graphs_test();
     assert_eq!(graphs.len_graphs(), 1);
-    graphs.insert("middle-earth");
+    graphs.insert("middle-earth").unwrap();
     assert_eq!(graphs.len_graphs(), 2);
     graphs.delete_vault("middle-earth").unwrap();
     assert_eq!(graphs.len_graphs(), 1);
@@ -112,10 +122,12 @@ fn should_insert_a_graph_into_the_vault_without_init() {
     let mut graphs = prepare_graphs_test();
     assert_eq!(graphs.len_graphs(), 1);
     assert_eq!(graphs.len(), 4);
-    graphs.add_edge(
-        &Edge::create(&Vertex::new("Earth"), "has satellite", &Vertex::new("Moon")),
-        Some("solar-system"),
-    );
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Earth"), "has satellite", &Vertex::new("Moon")),
+            Some("solar-system"),
+        )
+        .unwrap();
     assert_eq!(graphs.len_graphs(), 2);
     assert_eq!(graphs.len(), 5);
 }
@@ -126,10 +138,12 @@ fn insert_lot_of_edges_into_the_vault() {
     let mut graphs = prepare_graphs_test();
     graphs.update_label("big-big-big");
     for _i in 1..5500 {
-        graphs.add_edge(
-            &Edge::create(&Vertex::new("Earth"), "has satellite", &Vertex::new("Moon")),
-            None,
-        );
+        graphs
+            .add_edge(
+                &Edge::create(&Vertex::new("Earth"), "has satellite", &Vertex::new("Moon")),
+                None,
+            )
+            .unwrap();
     }
 }
 
@@ -143,14 +157,14 @@ fn is_empty_graphs() {
 
 #[test]
 fn should_find_edges_by_relation() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let vertices_found = graphs.find_edges_by_relation("friend of", None).unwrap();
     assert_eq!(vertices_found.len(), 3);
 }
 
 #[test]
 fn should_not_find_edges_by_relation_since_vault_does_not_exists() {
-    let mut graphs = Graphs::init("empty");
+    let graphs = Graphs::init("empty");
     let e = graphs.find_edges_by_relation("friend of", Some("!exists"));
     assert_eq!(
         e,
@@ -160,7 +174,7 @@ fn should_not_find_edges_by_relation_since_vault_does_not_exists() {
 
 #[test]
 fn should_not_find_edges_by_relation_since_vault_is_empty() {
-    let mut graphs = Graphs::init("empty");
+    let graphs = Graphs::init("empty");
     let e = graphs.find_edges_by_relation("friend of", Some("empty"));
     assert_eq!(e, Err(GruPHstError::VaultEmpty));
 }
@@ -179,7 +193,7 @@ fn should_edges_find_by_relation_in_graphs() {
 
 #[test]
 fn should_find_by_relations_name() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let relations = vec!["friend of", "relative of"];
     let vertices_found = graphs.find_edges_by_relations(relations, None).unwrap();
     assert_eq!(vertices_found.len(), 4);
@@ -187,14 +201,14 @@ fn should_find_by_relations_name() {
 
 #[test]
 fn should_not_find_by_relations_name() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let relations = vec!["foo", "bar"];
     assert!(graphs.find_edges_by_relations(relations, None).is_err());
 }
 
 #[test]
 fn should_not_find_by_relations_name_vault_does_not_exists() {
-    let mut graphs = Graphs::init("void");
+    let graphs = Graphs::init("void");
     let relations = vec!["foo", "bar"];
     let e = graphs.find_edges_by_relations(relations, Some("!exists"));
     assert_eq!(
@@ -205,7 +219,7 @@ fn should_not_find_by_relations_name_vault_does_not_exists() {
 
 #[test]
 fn should_not_find_by_relation() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     assert!(graphs.find_edges_by_relation("lol", None).is_err());
 }
 
@@ -226,7 +240,7 @@ fn should_return_the_unique_relations_labels_for_whole_graphs() {
 
 #[test]
 fn should_find_edges_with_attribute() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let edges_found = graphs
         .find_edges_with_vertex_attr_str_key("age", None)
         .unwrap();
@@ -236,7 +250,7 @@ fn should_find_edges_with_attribute() {
 
 #[test]
 fn should_not_find_edges_with_attribute() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     assert!(graphs
         .find_edges_with_vertex_attr_str_key("foo", None)
         .is_err());
@@ -244,7 +258,7 @@ fn should_not_find_edges_with_attribute() {
 
 #[test]
 fn should_not_find_edges_with_attribute_since_vault_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_str_key("foo", Some("!exists"));
     assert_eq!(
         e,
@@ -254,7 +268,7 @@ fn should_not_find_edges_with_attribute_since_vault_does_not_exists() {
 
 #[test]
 fn should_find_graphs_with_attribute_like() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let found_graphs = graphs
         .find_edges_with_vertex_attr_str_key_like("Ag", None)
         .unwrap();
@@ -264,7 +278,7 @@ fn should_find_graphs_with_attribute_like() {
 
 #[test]
 fn should_not_find_graphs_with_attribute_like() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     assert!(graphs
         .find_edges_with_vertex_attr_str_key_like("fo", None)
         .is_err());
@@ -272,7 +286,7 @@ fn should_not_find_graphs_with_attribute_like() {
 
 #[test]
 fn should_not_find_graphs_with_attribute_like_since_vault_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_str_key_like("Ag", Some("!exists"));
     assert_eq!(
         e,
@@ -412,7 +426,7 @@ fn should_create_new_vault_and_add_an_edge() {
     let mut graphs = prepare_graphs_test();
     assert_eq!(graphs.len_graphs(), 1);
     let edge = Edge::create(&Vertex::new("foo"), "before a", &Vertex::new("bar"));
-    graphs.insert_with("other", &edge);
+    graphs.insert_with("other", &edge).unwrap();
     assert_eq!(graphs.len_graphs(), 2);
 }
 
@@ -421,10 +435,12 @@ fn should_find_in_graph_by_id() {
     let mut graphs = prepare_graphs_test();
     let from_vertex = Vertex::new("Earth");
     let from_vertex_id = from_vertex.get_id();
-    graphs.add_edge(
-        &Edge::create(&from_vertex, "has satellite", &Vertex::new("Moon")),
-        Some("solar-system"),
-    );
+    graphs
+        .add_edge(
+            &Edge::create(&from_vertex, "has satellite", &Vertex::new("Moon")),
+            Some("solar-system"),
+        )
+        .unwrap();
     let mut found_graph = graphs.find_edge_by_id(&from_vertex_id, None).unwrap();
     assert_eq!(found_graph.get_label(), "has satellite");
     assert_eq!(found_graph.get_from_vertex().get_label(), "Earth");
@@ -440,10 +456,12 @@ fn should_find_in_graphs_by_id() {
     let mut graphs = prepare_graphs_test();
     let from_vertex = Vertex::new("Earth");
     let from_vertex_id = from_vertex.get_id();
-    graphs.add_edge(
-        &Edge::create(&from_vertex, "has satellite", &Vertex::new("Moon")),
-        Some("solar-system"),
-    );
+    graphs
+        .add_edge(
+            &Edge::create(&from_vertex, "has satellite", &Vertex::new("Moon")),
+            Some("solar-system"),
+        )
+        .unwrap();
     let default_graph_id = graphs.get_edges(Some("my graphs")).unwrap()[0].get_id();
     let mut found_graph = graphs.find_edge_by_id_in_graphs(&default_graph_id).unwrap();
     assert_eq!(found_graph.get_label(), "friend of");
@@ -509,11 +527,11 @@ fn should_update_graph() {
     let alice_edge = Vertex::new("Alice");
     let bob_edge = Vertex::new("Bob");
     let alice_bob_graph = Edge::create(&alice_edge, "best friends", &bob_edge);
-    my_graphs.add_edge(&alice_bob_graph, None);
+    my_graphs.add_edge(&alice_bob_graph, None).unwrap();
 
     let fred_edge = Vertex::new("Fred");
     let mut alice_fred_graph = Edge::create(&alice_edge, "super friends", &fred_edge);
-    my_graphs.add_edge(&alice_fred_graph, None);
+    my_graphs.add_edge(&alice_fred_graph, None).unwrap();
 
     assert_eq!(my_graphs.len(), 2);
 
@@ -551,7 +569,7 @@ fn should_fail_on_updating_graph() {
     let alice = Vertex::new("Alice");
     let bob = Vertex::new("Bob");
     let alice_bob = Edge::create(&alice, "friend of", &bob);
-    grphs.add_edge(&alice_bob, None);
+    grphs.add_edge(&alice_bob, None).unwrap();
 
     let bob_alice = Edge::create(&bob, "friend of", &alice);
     assert!(grphs.update_graph(&bob_alice, None).is_err());
@@ -638,7 +656,7 @@ fn should_fail_returning_unique_vertices_on_graphs_vault_is_empty() {
 fn should_return_stats_for_graphs() {
     let mut graphs = prepare_graphs_test();
     let graphs_stats = graphs.get_stats();
-    assert_eq!(graphs_stats.get_mem(), 1268);
+    assert_eq!(graphs_stats.get_mem(), 1210);
     assert_eq!(graphs_stats.get_total_edges(), 4);
     assert_eq!(graphs_stats.get_total_graphs(), 1);
     assert_eq!(graphs_stats.get_total_attr(), 11);
@@ -650,7 +668,7 @@ fn should_return_stats_for_graphs() {
 fn should_retrieve_memory_used_by_graphs() {
     let graphs = prepare_graphs_test();
     let mem_usage = graphs.get_mem().unwrap();
-    assert_eq!(mem_usage, 1268);
+    assert_eq!(mem_usage, 1210);
 }
 
 #[test]
@@ -671,7 +689,7 @@ fn should_update_label_for_graphs() {
 
 #[test]
 fn should_find_vertex_by_id() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let a_vertex = graphs.get_edges(None).unwrap()[0].get_from_vertex();
     assert_eq!(a_vertex.get_label(), "Alice".to_string());
     let found_vertex = graphs
@@ -682,14 +700,14 @@ fn should_find_vertex_by_id() {
 
 #[test]
 fn should_not_find_vertex_by_id_that_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_vertex_by_id("foobar", None);
     assert_eq!(e, Err(GruPHstError::VertexNotFound));
 }
 
 #[test]
 fn should_not_find_vertex_by_id_vault_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_vertex_by_id("foobar", Some("!Exists"));
     assert_eq!(
         e,
@@ -729,7 +747,7 @@ fn should_add_a_collection_of_edges() {
 
     let mut edges = prepare_vector_edges();
 
-    graphs.add_edges(&mut edges, None);
+    graphs.add_edges(&mut edges, None).unwrap();
 
     let stats = graphs.get_stats();
     assert_eq!(stats.get_total_edges(), 4);
@@ -741,7 +759,7 @@ fn should_create_new_vault_and_add_a_colection_of_edges() {
     let mut graphs = prepare_graphs_test();
     assert_eq!(graphs.len_graphs(), 1);
     let mut edges = prepare_vector_edges();
-    graphs.add_edges(&mut edges, Some("new-vault"));
+    graphs.add_edges(&mut edges, Some("new-vault")).unwrap();
     assert_eq!(graphs.len_graphs(), 2);
     let stats = graphs.get_stats();
     assert_eq!(stats.get_total_edges(), 8);
@@ -750,7 +768,7 @@ fn should_create_new_vault_and_add_a_colection_of_edges() {
 
 #[test]
 fn should_find_edges_with_vertex_that_has_any_attr() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let mut found_edges = graphs
         .find_edges_with_vertex_attr_key("phone", None)
         .unwrap();
@@ -765,7 +783,7 @@ fn should_find_edges_with_vertex_that_has_any_attr() {
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_any_attr_that_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let mut e = graphs.find_edges_with_vertex_attr_key("foo", None);
     assert_eq!(e, Err(GruPHstError::EdgeNotFound));
     e = graphs.find_edges_with_vertex_attr_key("bar", None);
@@ -774,7 +792,7 @@ fn should_not_find_edges_with_vertex_that_has_any_attr_that_does_not_exists() {
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_any_attr_since_vault_doest_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_key("phone", Some("!Exists"));
     assert_eq!(
         e,
@@ -784,7 +802,7 @@ fn should_not_find_edges_with_vertex_that_has_any_attr_since_vault_doest_not_exi
 
 #[test]
 fn should_find_edges_with_vertex_that_has_vec_u8_attr() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let found_edges = graphs
         .find_edges_with_vertex_attr_vec_u8_key("code", None)
         .unwrap();
@@ -794,14 +812,14 @@ fn should_find_edges_with_vertex_that_has_vec_u8_attr() {
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_that_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_vec_u8_key("bar", None);
     assert_eq!(e, Err(GruPHstError::EdgeNotFound));
 }
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_since_vault_doest_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_vec_u8_key("phone", Some("!Exists"));
     assert_eq!(
         e,
@@ -811,7 +829,7 @@ fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_since_vault_doest_not_
 
 #[test]
 fn should_find_edges_with_vertex_that_has_vec_u8_attr_like() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let found_edges = graphs
         .find_edges_with_vertex_attr_vec_u8_key_like("oDe", None)
         .unwrap();
@@ -821,14 +839,14 @@ fn should_find_edges_with_vertex_that_has_vec_u8_attr_like() {
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_like_that_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_vec_u8_key_like("bAr", None);
     assert_eq!(e, Err(GruPHstError::EdgeNotFound));
 }
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_like_since_vault_doest_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_vec_u8_key_like("phone", Some("!Exists"));
     assert_eq!(
         e,
@@ -838,7 +856,7 @@ fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_like_since_vault_doest
 
 #[test]
 fn should_find_edges_with_vertex_that_has_any_attr_like() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let mut found_edges = graphs
         .find_edges_with_vertex_attr_key_like("Hon", None)
         .unwrap();
@@ -853,7 +871,7 @@ fn should_find_edges_with_vertex_that_has_any_attr_like() {
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_any_attr_like_that_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let mut e = graphs.find_edges_with_vertex_attr_key_like("fOO", None);
     assert_eq!(e, Err(GruPHstError::EdgeNotFound));
     e = graphs.find_edges_with_vertex_attr_key_like("baR", None);
@@ -862,7 +880,7 @@ fn should_not_find_edges_with_vertex_that_has_any_attr_like_that_does_not_exists
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_any_attr_like_since_vault_doest_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let e = graphs.find_edges_with_vertex_attr_key_like("phone", Some("!Exists"));
     assert_eq!(
         e,
@@ -872,7 +890,7 @@ fn should_not_find_edges_with_vertex_that_has_any_attr_like_since_vault_doest_no
 
 #[test]
 fn should_find_edges_with_vertex_that_has_vec_u8_attr_equals_to() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let vec_u8_attr: Vec<u8> = vec![3, 1, 3, 3, 7];
     let res = graphs
         .find_edges_with_vertex_attr_vec_u8_equals_to("code", &vec_u8_attr, None)
@@ -883,7 +901,7 @@ fn should_find_edges_with_vertex_that_has_vec_u8_attr_equals_to() {
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_key_does_not_exists_equals_to() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let vec_u8_attr: Vec<u8> = vec![3, 1, 3, 3, 7];
     let e = graphs.find_edges_with_vertex_attr_vec_u8_equals_to("edoc", &vec_u8_attr, None);
     assert_eq!(e, Err(GruPHstError::EdgeNotFound));
@@ -891,7 +909,7 @@ fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_key_does_not_exists_eq
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_value_not_equals_to() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let vec_u8_attr_nok: Vec<u8> = vec![1, 3, 3, 7];
     let e = graphs.find_edges_with_vertex_attr_vec_u8_equals_to("code", &vec_u8_attr_nok, None);
     assert_eq!(e, Err(GruPHstError::EdgeNotFound));
@@ -899,7 +917,7 @@ fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_value_not_equals_to()
 
 #[test]
 fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_equals_to_vault_does_not_exists() {
-    let mut graphs = prepare_graphs_test();
+    let graphs = prepare_graphs_test();
     let vec_u8_attr_nok: Vec<u8> = vec![1, 3, 3, 7];
     let e = graphs.find_edges_with_vertex_attr_vec_u8_equals_to(
         "code",
@@ -911,3 +929,89 @@ fn should_not_find_edges_with_vertex_that_has_vec_u8_attr_equals_to_vault_does_n
         Err(GruPHstError::VaultNotExists(String::from("!Exists")))
     );
 }
+
+#[test]
+fn should_add_a_vertex_without_edges() {
+    let mut graphs = Graphs::init("middle-earth");
+    let tom = Vertex::new("Tom Bombadil");
+    graphs.add_vertex(&tom, None).unwrap();
+    let vertices = graphs.get_vertices(None).unwrap();
+    assert_eq!(vertices.len(), 1);
+    assert_eq!(vertices[0].get_label(), "Tom Bombadil");
+    assert!(graphs.get_edges(None).is_err());
+    let found = graphs.find_vertex_by_id(&tom.get_id(), None).unwrap();
+    assert_eq!(found.get_id(), tom.get_id());
+}
+
+#[test]
+fn should_add_a_vertex_creating_a_new_vault() {
+    let mut graphs = Graphs::init("middle-earth");
+    graphs
+        .add_vertex(&Vertex::new("Goldberry"), Some("old forest"))
+        .unwrap();
+    assert_eq!(graphs.len_graphs(), 2);
+    assert_eq!(graphs.get_vertices(Some("old forest")).unwrap().len(), 1);
+}
+
+#[test]
+fn should_register_vertices_when_adding_edges() {
+    let graphs = prepare_graphs_test();
+    let vertices = graphs.get_vertices(None).unwrap();
+    assert_eq!(vertices.len(), 3);
+}
+
+#[test]
+fn should_share_the_registered_vertex_with_edges() {
+    let mut graphs = Graphs::init("middle-earth");
+    let frodo = Vertex::new("Frodo");
+    graphs.add_vertex(&frodo, None).unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "lives at", &Vertex::new("The Shire")),
+            None,
+        )
+        .unwrap();
+    assert_eq!(graphs.get_vertices(None).unwrap().len(), 2);
+    let mut registered = graphs.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    registered.set_attr("surname", "Baggins");
+    let edges = graphs.get_edges(None).unwrap();
+    assert_eq!(
+        edges[0].get_from_vertex().get_attr("surname").unwrap(),
+        "Baggins"
+    );
+}
+
+#[test]
+fn should_remove_a_vertex_without_edges() {
+    let mut graphs = prepare_graphs_test();
+    let tom = Vertex::new("Tom Bombadil");
+    graphs.add_vertex(&tom, None).unwrap();
+    assert_eq!(graphs.get_vertices(None).unwrap().len(), 4);
+    graphs.remove_vertex(&tom.get_id(), None).unwrap();
+    assert_eq!(graphs.get_vertices(None).unwrap().len(), 3);
+    assert_eq!(
+        graphs.find_vertex_by_id(&tom.get_id(), None),
+        Err(GruPHstError::VertexNotFound)
+    );
+}
+
+#[test]
+fn should_not_remove_a_vertex_with_edges() {
+    let mut graphs = prepare_graphs_test();
+    let alice = graphs.get_edges(None).unwrap()[0].get_from_vertex();
+    let e = graphs.remove_vertex(&alice.get_id(), None);
+    assert_eq!(e, Err(GruPHstError::VertexHasEdges(alice.get_id())));
+}
+
+#[test]
+fn should_not_remove_a_vertex_that_does_not_exists() {
+    let mut graphs = prepare_graphs_test();
+    assert_eq!(
+        graphs.remove_vertex("foobar", None),
+        Err(GruPHstError::VertexNotFound)
+    );
+    assert_eq!(
+        graphs.remove_vertex("foobar", Some("!exists")),
+        Err(GruPHstError::VaultNotExists(String::from("!exists")))
+    );
+}
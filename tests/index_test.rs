@@ -0,0 +1,336 @@
+use std::fs;
+
+use gruphst::{
+    attributes::AttrValue,
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{Graphs, IndexKind, IndexTarget, Schema},
+    vertex::Vertex,
+};
+
+fn prepare_index_test() -> (Graphs, Vertex, Vertex) {
+    let mut graphs = Graphs::init("fellowship");
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("years old", 50);
+    frodo.set_attr("race", "hobbit");
+    let mut sam = Vertex::new("Sam");
+    sam.set_attr("years old", 38.0);
+    sam.set_attr("race", "hobbit");
+    let mut gandalf = Vertex::new("Gandalf");
+    gandalf.set_attr("years old", 24000);
+    gandalf.set_attr("race", "maia");
+    let mut friends = Edge::create(&frodo, "friend of", &sam);
+    friends.set_attr("since", 2968);
+    graphs.add_edge(&friends, None).unwrap();
+    let mut guides = Edge::create(&gandalf, "guide of", &frodo);
+    guides.set_attr("since", 3001.5);
+    graphs.add_edge(&guides, None).unwrap();
+    let mut legolas = Vertex::new("Legolas");
+    legolas.set_attr(
+        "race",
+        vec![AttrValue::from("elf"), AttrValue::from("sindar")],
+    );
+    graphs.add_vertex(&legolas, None).unwrap();
+    (graphs, frodo, sam)
+}
+
+fn labels(vertices: Vec<Vertex>) -> Vec<String> {
+    let mut labels: Vec<String> = vertices.iter().map(|v| v.get_label()).collect();
+    labels.sort();
+    labels
+}
+
+#[test]
+fn should_find_the_same_with_or_without_index() {
+    for kind in [IndexKind::Hash, IndexKind::BTree] {
+        let (scanned, ..) = prepare_index_test();
+        let (mut indexed, ..) = prepare_index_test();
+        for (attr_k, target) in [
+            ("years old", IndexTarget::Vertex),
+            ("race", IndexTarget::Vertex),
+            ("since", IndexTarget::Edge),
+        ] {
+            indexed.create_index(attr_k, target, kind, None).unwrap();
+        }
+        assert_eq!(indexed.get_indexes(None).unwrap().len(), 3);
+
+        let find = |graphs: &Graphs, value: i64| {
+            graphs
+                .find_vertices_with_attr_equals_to("years old", value, None)
+                .map(labels)
+        };
+        assert_eq!(find(&indexed, 38), Ok(vec![String::from("Sam")]));
+        assert_eq!(find(&indexed, 38), find(&scanned, 38));
+        assert_eq!(find(&indexed, 39), Err(GruPHstError::VertexNotFound));
+        let in_range = |graphs: &Graphs| {
+            graphs
+                .find_vertices_with_attr_in_range("years old", 30..=24000, None)
+                .map(labels)
+        };
+        assert_eq!(in_range(&indexed).unwrap().len(), 3);
+        assert_eq!(in_range(&indexed), in_range(&scanned));
+        let hobbits = indexed
+            .find_vertices_with_attr_in_range("race", "h".."i", None)
+            .map(labels);
+        assert_eq!(hobbits.unwrap(), vec!["Frodo", "Sam"]);
+
+        let edges = indexed
+            .find_edges_with_attr_in_range("since", 3000.., None)
+            .unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].get_relation(), "guide of");
+        let edges = indexed
+            .find_edges_with_attr_equals_to("since", 2968.0, None)
+            .unwrap();
+        assert_eq!(edges[0].get_relation(), "friend of");
+        let edges = indexed
+            .find_edges_with_vertex_attr_in_range("years old", ..40, None)
+            .unwrap();
+        assert_eq!(edges.len(), 1);
+        let edges = indexed
+            .find_edges_with_vertex_attr_str_equals_to("race", "hobbit", None)
+            .unwrap();
+        let relations = |edges: Vec<Edge>| -> Vec<String> {
+            edges.iter().map(|edge| edge.get_relation()).collect()
+        };
+        assert_eq!(relations(edges), vec!["friend of", "guide of"]);
+        let edges = scanned
+            .find_edges_with_vertex_attr_str_equals_to("race", "hobbit", None)
+            .unwrap();
+        assert_eq!(relations(edges), vec!["friend of", "guide of"]);
+        // the values not indexed, like a list, are still found
+        let elves = indexed
+            .find_vertices_with_attr_equals_to(
+                "race",
+                vec![AttrValue::from("elf"), AttrValue::from("sindar")],
+                None,
+            )
+            .map(labels);
+        assert_eq!(elves.unwrap(), vec!["Legolas"]);
+        assert_eq!(indexed.get_mem_estimate(), indexed.get_mem().unwrap());
+    }
+}
+
+#[test]
+fn should_keep_the_indexes_in_sync_on_every_mutation() {
+    let (mut graphs, frodo, sam) = prepare_index_test();
+    graphs
+        .create_index("years old", IndexTarget::Vertex, IndexKind::BTree, None)
+        .unwrap();
+    graphs
+        .create_index("since", IndexTarget::Edge, IndexKind::Hash, None)
+        .unwrap();
+    let find_aged = |graphs: &Graphs, years: i64| {
+        graphs
+            .find_vertices_with_attr_equals_to("years old", years, None)
+            .map(labels)
+    };
+
+    graphs
+        .set_vertex_attr(&frodo.get_id(), "years old", 51, None)
+        .unwrap();
+    assert_eq!(find_aged(&graphs, 50), Err(GruPHstError::VertexNotFound));
+    assert_eq!(find_aged(&graphs, 51), Ok(vec![String::from("Frodo")]));
+    graphs
+        .delete_vertex_attr(&sam.get_id(), "years old", None)
+        .unwrap();
+    assert_eq!(find_aged(&graphs, 38), Err(GruPHstError::VertexNotFound));
+
+    let mut bilbo = Vertex::new("Bilbo");
+    bilbo.set_attr("years old", 111);
+    graphs.add_vertex(&bilbo, None).unwrap();
+    assert_eq!(find_aged(&graphs, 111), Ok(vec![String::from("Bilbo")]));
+    graphs.remove_vertex(&bilbo.get_id(), None).unwrap();
+    assert_eq!(find_aged(&graphs, 111), Err(GruPHstError::VertexNotFound));
+
+    // the positions of the edges follow the removals
+    let first = graphs.get_edges(None).unwrap()[0].clone();
+    graphs.delete_edge_by_id(first.get_id(), None).unwrap();
+    let mut rangers = Edge::create(&Vertex::new("Aragorn"), "ranger with", &bilbo);
+    rangers.set_attr("since", 2956);
+    graphs.add_edge(&rangers, None).unwrap();
+    assert_eq!(find_aged(&graphs, 111), Ok(vec![String::from("Bilbo")]));
+    let edges = graphs.find_edges_with_attr_equals_to("since", 2956, None);
+    assert_eq!(edges.unwrap()[0].get_relation(), "ranger with");
+    let mut guides = graphs.get_edges(None).unwrap()[0].clone();
+    guides.set_attr("since", 3018);
+    graphs.update_graph(&guides, None).unwrap();
+    let edges = graphs
+        .find_edges_with_attr_in_range("since", 3000.., None)
+        .unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].get_attr_i64("since").unwrap(), 3018);
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+
+    // the vertices shared with other vaults are moved on their indexes too
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+            Some("shire"),
+        )
+        .unwrap();
+    graphs
+        .create_index(
+            "years old",
+            IndexTarget::Vertex,
+            IndexKind::Hash,
+            Some("shire"),
+        )
+        .unwrap();
+    graphs
+        .set_vertex_attr(&frodo.get_id(), "years old", 52, Some("fellowship"))
+        .unwrap();
+    let found = graphs.find_vertices_with_attr_equals_to("years old", 52, Some("shire"));
+    assert_eq!(found.map(labels), Ok(vec![String::from("Frodo")]));
+
+    // a failed transaction rolls back the indexes
+    let result = graphs.transaction(|tx| {
+        tx.set_vertex_attr(&frodo.get_id(), "years old", 53, Some("shire"))?;
+        tx.drop_index("years old", IndexTarget::Vertex, Some("shire"))?;
+        tx.drop_index("years old", IndexTarget::Vertex, Some("shire"))
+    });
+    assert_eq!(
+        result,
+        Err(GruPHstError::IndexNotFound(String::from("years old")))
+    );
+    assert_eq!(graphs.get_indexes(Some("shire")).unwrap().len(), 1);
+    let found = graphs.find_vertices_with_attr_equals_to("years old", 52, Some("shire"));
+    assert_eq!(found.map(labels), Ok(vec![String::from("Frodo")]));
+}
+
+#[test]
+fn should_find_the_vertices_changed_out_of_the_graphs() {
+    for kind in [IndexKind::Hash, IndexKind::BTree] {
+        let (mut graphs, mut frodo, mut sam) = prepare_index_test();
+        graphs
+            .create_index("years old", IndexTarget::Vertex, kind, None)
+            .unwrap();
+        graphs
+            .create_index("race", IndexTarget::Vertex, kind, None)
+            .unwrap();
+        let find_aged = |graphs: &Graphs, years: i64| {
+            graphs
+                .find_vertices_with_attr_equals_to("years old", years, None)
+                .map(labels)
+        };
+
+        frodo.set_attr("years old", 51);
+        sam.del_attr("race").unwrap();
+        assert_eq!(find_aged(&graphs, 50), Err(GruPHstError::VertexNotFound));
+        assert_eq!(find_aged(&graphs, 51), Ok(vec![String::from("Frodo")]));
+        let in_range = graphs.find_vertices_with_attr_in_range("years old", 51..52, None);
+        assert_eq!(in_range.map(labels), Ok(vec![String::from("Frodo")]));
+        let edges = graphs.find_edges_with_vertex_attr_str_equals_to("years old", 51, None);
+        assert_eq!(edges.unwrap().len(), 2);
+        let hobbits = graphs.find_vertices_with_attr_equals_to("race", "hobbit", None);
+        assert_eq!(hobbits.map(labels), Ok(vec![String::from("Frodo")]));
+
+        // merged with the changed vertex
+        let key = [("years old", AttrValue::from(51))];
+        let merged = graphs.merge_vertex("Frodo", &key, None).unwrap();
+        assert_eq!(merged.get_id(), frodo.get_id());
+
+        // indexed again on the next mutation through the Graphs
+        graphs.add_vertex(&Vertex::new("Bilbo"), None).unwrap();
+        frodo.update_attr("years old", 52).unwrap();
+        assert_eq!(find_aged(&graphs, 51), Err(GruPHstError::VertexNotFound));
+        assert_eq!(find_aged(&graphs, 52), Ok(vec![String::from("Frodo")]));
+
+        // and kept unique
+        let mut schema = Schema::new();
+        schema.add_unique_key("Frodo", &["years old"]);
+        graphs.set_schema(schema, None).unwrap();
+        let mut other = Vertex::new("Frodo");
+        other.set_attr("years old", 52);
+        let result = graphs.add_vertex(&other, None);
+        assert!(matches!(result, Err(GruPHstError::SchemaViolation(_))));
+    }
+}
+
+#[test]
+fn should_persist_the_indexes_and_build_them_on_load() {
+    let (mut graphs, ..) = prepare_index_test();
+    graphs
+        .create_index("years old", IndexTarget::Vertex, IndexKind::BTree, None)
+        .unwrap();
+    graphs
+        .create_index("since", IndexTarget::Edge, IndexKind::Hash, None)
+        .unwrap();
+    graphs.save(Some("./tests/data/indexed-")).unwrap();
+    let file_name = "./tests/data/indexed-fellowship.grphst";
+    let loaded = Graphs::load(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+    assert_eq!(
+        loaded.get_indexes(None).unwrap(),
+        vec![
+            (String::from("since"), IndexTarget::Edge, IndexKind::Hash),
+            (
+                String::from("years old"),
+                IndexTarget::Vertex,
+                IndexKind::BTree
+            ),
+        ]
+    );
+    let found = loaded.find_vertices_with_attr_in_range("years old", 1000.., None);
+    assert_eq!(found.map(labels), Ok(vec![String::from("Gandalf")]));
+    assert_eq!(loaded.get_mem_estimate(), loaded.get_mem().unwrap());
+
+    // replayed from the write-ahead log
+    let dir = "tests/data/wal-indexes";
+    let _ = fs::remove_dir_all(dir);
+    {
+        let mut opened = Graphs::open(dir).unwrap();
+        opened
+            .add_edges(&mut graphs.get_edges(None).unwrap(), None)
+            .unwrap();
+        opened
+            .create_index("years old", IndexTarget::Vertex, IndexKind::Hash, None)
+            .unwrap();
+        opened
+            .create_index("since", IndexTarget::Edge, IndexKind::Hash, None)
+            .unwrap();
+        opened.drop_index("since", IndexTarget::Edge, None).unwrap();
+    }
+    let opened = Graphs::open(dir).unwrap();
+    assert_eq!(
+        opened.get_indexes(None).unwrap(),
+        vec![(
+            String::from("years old"),
+            IndexTarget::Vertex,
+            IndexKind::Hash
+        )]
+    );
+    drop(opened);
+    fs::remove_dir_all(dir).unwrap();
+
+    // built when a vault of a store is loaded
+    let dir = "./tests/data/store-indexes";
+    graphs.save_store(dir).unwrap();
+    let stored = Graphs::open_store(dir).unwrap();
+    let found = stored.find_vertices_with_attr_equals_to("years old", 24000, None);
+    assert_eq!(found.map(labels), Ok(vec![String::from("Gandalf")]));
+    assert_eq!(stored.get_indexes(None).unwrap().len(), 2);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_fail_with_indexes_of_missing_vaults_or_attributes() {
+    let (mut graphs, ..) = prepare_index_test();
+    assert_eq!(
+        graphs.create_index(
+            "years old",
+            IndexTarget::Vertex,
+            IndexKind::Hash,
+            Some("mordor")
+        ),
+        Err(GruPHstError::VaultNotExists(String::from("mordor")))
+    );
+    assert_eq!(
+        graphs.drop_index("years old", IndexTarget::Edge, None),
+        Err(GruPHstError::IndexNotFound(String::from("years old")))
+    );
+    assert_eq!(
+        graphs.get_indexes(Some("mordor")),
+        Err(GruPHstError::VaultNotExists(String::from("mordor")))
+    );
+}
@@ -0,0 +1,201 @@
+use std::fs;
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{Graphs, MemoryPolicy},
+    vertex::Vertex,
+};
+
+fn edge() -> Edge {
+    Edge::create(
+        &Vertex::new("Elrond"),
+        "lives at",
+        &Vertex::new("Rivendell"),
+    )
+}
+
+/// Adds edges until the memory usage reaches the provided percentage
+fn fill_vault(graphs: &mut Graphs, vault: &str, percentage: usize) -> usize {
+    let max_mem = graphs.get_graphs_stats().get_max_mem();
+    let mut added = 0;
+    while graphs.get_mem().unwrap() * 100 < max_mem * percentage {
+        graphs.add_edge(&edge(), Some(vault)).unwrap();
+        added += 1;
+    }
+    added
+}
+
+#[test]
+fn should_reject_the_writes_over_the_memory_limit() {
+    let mut graphs = Graphs::init("rejected");
+    graphs.set_memory_policy(MemoryPolicy::Reject);
+    assert_eq!(graphs.get_memory_policy(), MemoryPolicy::Reject);
+    let added = fill_vault(&mut graphs, "rejected", 98);
+    let result = loop {
+        if let Err(err) = graphs.add_edge(&edge(), None) {
+            break err;
+        }
+    };
+    assert_eq!(result, GruPHstError::MemoryLimitExceeded);
+    let len = graphs.len();
+    assert!(len > added);
+
+    // nothing is added by a rejected write
+    let mut edges = vec![edge(), edge()];
+    assert_eq!(
+        graphs.add_edges(&mut edges, None),
+        Err(GruPHstError::MemoryLimitExceeded)
+    );
+    assert_eq!(graphs.len(), len);
+    assert_eq!(graphs.len_graphs(), 1);
+    let max_mem = graphs.get_graphs_stats().get_max_mem();
+    assert!(graphs.get_mem().unwrap() < max_mem);
+
+    // deletions are not rejected
+    let id = graphs.get_edges(None).unwrap()[0].get_id();
+    graphs.delete_edge_by_id(id, None).unwrap();
+    assert_eq!(graphs.len(), len - 1);
+}
+
+#[test]
+fn should_evict_the_least_recently_used_vaults() {
+    let mut graphs = Graphs::init("rivendell");
+    graphs.set_memory_policy(MemoryPolicy::Evict);
+    graphs.insert("lothlorien").unwrap();
+    let rivendell = fill_vault(&mut graphs, "rivendell", 20);
+    let lothlorien = fill_vault(&mut graphs, "lothlorien", 40);
+    graphs.insert("mordor").unwrap();
+    // the last used vault is kept
+    graphs.get_edges(Some("rivendell")).unwrap();
+    while graphs.get_evicted_vaults().is_empty() {
+        graphs.add_edge(&edge(), None).unwrap();
+    }
+    assert_eq!(graphs.get_evicted_vaults(), vec!["lothlorien"]);
+    assert!(graphs.is_evicted("lothlorien"));
+    assert_eq!(graphs.len_graphs(), 3);
+    while !graphs.is_evicted("rivendell") {
+        graphs.add_edge(&edge(), None).unwrap();
+    }
+    // nothing else to evict, the current vault is kept
+    let result = loop {
+        if let Err(err) = graphs.add_edge(&edge(), None) {
+            break err;
+        }
+    };
+    assert_eq!(result, GruPHstError::MemoryLimitExceeded);
+    assert!(!graphs.is_evicted("mordor"));
+
+    // a saved Graphs includes the evicted vaults
+    graphs.delete_vault("mordor").unwrap();
+    graphs.save(Some("./tests/data/")).unwrap();
+    let loaded = Graphs::load("./tests/data/mordor.grphst").unwrap();
+    assert_eq!(loaded.len_graphs(), 2);
+    assert_eq!(
+        loaded.get_edges(Some("rivendell")).unwrap().len(),
+        rivendell
+    );
+    assert!(loaded.get_evicted_vaults().is_empty());
+    fs::remove_file("./tests/data/mordor.grphst").unwrap();
+
+    // reloaded by a write on it, a read, or on demand
+    graphs.add_edge(&edge(), Some("rivendell")).unwrap();
+    assert!(!graphs.is_evicted("rivendell"));
+    assert_eq!(
+        graphs.get_edges(Some("rivendell")).unwrap().len(),
+        rivendell + 1
+    );
+    assert_eq!(
+        graphs.get_edges(Some("lothlorien")).unwrap().len(),
+        lothlorien
+    );
+    assert!(!graphs.is_evicted("lothlorien"));
+    graphs.reload_vault("lothlorien").unwrap();
+    assert_eq!(graphs.len(), rivendell + 1 + lothlorien);
+    assert!(graphs.get_evicted_vaults().is_empty());
+    assert!(graphs.reload_vault("lothlorien").is_err());
+}
+
+#[test]
+fn should_share_the_vertices_of_a_reloaded_vault() {
+    let mut graphs = Graphs::init("shire");
+    graphs.set_memory_policy(MemoryPolicy::Evict);
+    let frodo = Vertex::new("Frodo");
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+            None,
+        )
+        .unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "friend of", &Vertex::new("Sam")),
+            Some("fellowship"),
+        )
+        .unwrap();
+    graphs.set_label("shire");
+    fill_vault(&mut graphs, "shire", 50);
+    while !graphs.is_evicted("fellowship") {
+        graphs.add_edge(&edge(), None).unwrap();
+    }
+    graphs.reload_vault("fellowship").unwrap();
+    assert!(!graphs.is_evicted("fellowship"));
+    let mut fellowship = graphs
+        .find_vertex_by_id(&frodo.get_id(), Some("fellowship"))
+        .unwrap();
+    fellowship.set_attr("years old", 50);
+    let shire = graphs.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    assert_eq!(shire.get_attr_i64("years old").unwrap(), 50);
+
+    graphs.delete_vault("fellowship").unwrap();
+    assert_eq!(graphs.len_graphs(), 1);
+}
+
+#[test]
+fn should_keep_a_running_estimate_of_the_memory() {
+    let mut graphs = Graphs::init("middle-earth");
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("years old", 50);
+    let sam = Vertex::new("Sam");
+    let mut friends = Edge::create(&frodo, "friend of", &sam);
+    friends.set_attr("since", 2968);
+    graphs.add_edge(&friends, None).unwrap();
+    let mut edges = vec![
+        Edge::create(&sam, "lives at", &Vertex::new("The Shire")),
+        Edge::create(&Vertex::new("Gandalf"), "friend of", &frodo),
+    ];
+    graphs.add_edges(&mut edges, None).unwrap();
+    let tom = Vertex::new("Tom Bombadil");
+    graphs.add_vertex(&tom, None).unwrap();
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+
+    graphs
+        .set_vertex_attr(&sam.get_id(), "gardener", true, None)
+        .unwrap();
+    graphs
+        .delete_vertex_attr(&frodo.get_id(), "years old", None)
+        .unwrap();
+    friends.set_attr("since", "the Third Age");
+    graphs.update_graph(&friends, None).unwrap();
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+
+    graphs.delete_edge_by_id(friends.get_id(), None).unwrap();
+    graphs.remove_vertex(&tom.get_id(), None).unwrap();
+    graphs.insert("mordor").unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Sauron"), "created", &Vertex::new("One Ring")),
+            None,
+        )
+        .unwrap();
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+    graphs.delete_vault("mordor").unwrap();
+    graphs.set_label("middle-earth");
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+
+    graphs.save(Some("./tests/data/")).unwrap();
+    let loaded = Graphs::load("./tests/data/middle-earth.grphst").unwrap();
+    assert_eq!(loaded.get_mem_estimate(), loaded.get_mem().unwrap());
+    fs::remove_file("./tests/data/middle-earth.grphst").unwrap();
+}
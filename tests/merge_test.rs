@@ -0,0 +1,114 @@
+use gruphst::{
+    attributes::AttrValue,
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{Graphs, IndexKind, IndexTarget, Schema},
+    vertex::Vertex,
+};
+
+#[test]
+fn should_merge_the_vertices_by_their_key() {
+    for indexed in [false, true] {
+        let mut graphs = Graphs::init("shire");
+        if indexed {
+            graphs
+                .create_index("name", IndexTarget::Vertex, IndexKind::Hash, None)
+                .unwrap();
+        }
+        let mut frodo = Vertex::new("Hobbit");
+        frodo.set_attr("name", "Frodo");
+        frodo.set_attr("years old", 50);
+        graphs
+            .add_edge(
+                &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+                None,
+            )
+            .unwrap();
+
+        let key = [
+            ("name", AttrValue::from("Frodo")),
+            ("years old", AttrValue::from(50.0)),
+        ];
+        let merged = graphs.merge_vertex("Hobbit", &key, None).unwrap();
+        assert_eq!(merged.get_id(), frodo.get_id());
+        // the vertex is shared with the vault
+        graphs
+            .set_vertex_attr(&frodo.get_id(), "family", "Baggins", None)
+            .unwrap();
+        assert_eq!(merged.get_attr("family").unwrap(), "Baggins");
+
+        let elf = graphs.merge_vertex("Elf", &key[..1], None).unwrap();
+        assert_ne!(elf.get_id(), frodo.get_id());
+        assert_eq!(elf.get_attr("name").unwrap(), "Frodo");
+        let older = [
+            ("name", AttrValue::from("Frodo")),
+            ("years old", AttrValue::from(51)),
+        ];
+        let other = graphs.merge_vertex("Hobbit", &older, None).unwrap();
+        assert_ne!(other.get_id(), frodo.get_id());
+        assert_eq!(graphs.get_vertices(None).unwrap().len(), 4);
+
+        let again = graphs.merge_vertex("Elf", &key[..1], None).unwrap();
+        assert_eq!(again.get_id(), elf.get_id());
+        assert_eq!(graphs.get_vertices(None).unwrap().len(), 4);
+        assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+
+        // each vault has its own vertices
+        let rivendell = graphs.merge_vertex("Elf", &key[..1], Some("rivendell"));
+        assert_ne!(rivendell.unwrap().get_id(), elf.get_id());
+    }
+}
+
+#[test]
+fn should_merge_the_edges_by_their_vertices_and_relation() {
+    let mut graphs = Graphs::init("shire");
+    let frodo = Vertex::new("Frodo");
+    let sam = Vertex::new("Sam");
+    let friends = Edge::create(&frodo, "friend of", &sam);
+    graphs.add_edge(&friends, None).unwrap();
+
+    let merged = graphs.merge_edge(&frodo, "friend of", &sam, None).unwrap();
+    assert_eq!(merged.get_id(), friends.get_id());
+    let back = graphs.merge_edge(&sam, "friend of", &frodo, None).unwrap();
+    assert_ne!(back.get_id(), friends.get_id());
+    let gardener = graphs
+        .merge_edge(&sam, "gardener of", &frodo, None)
+        .unwrap();
+    assert_eq!(graphs.len(), 3);
+    for _ in 0..2 {
+        graphs.merge_edge(&sam, "friend of", &frodo, None).unwrap();
+        graphs
+            .merge_edge(&sam, "gardener of", &frodo, None)
+            .unwrap();
+    }
+    assert_eq!(graphs.len(), 3);
+    graphs.delete_edge_by_id(gardener.get_id(), None).unwrap();
+    let hired = graphs
+        .merge_edge(&sam, "gardener of", &frodo, None)
+        .unwrap();
+    assert_ne!(hired.get_id(), gardener.get_id());
+    assert_eq!(graphs.len(), 3);
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+}
+
+#[test]
+fn should_merge_following_the_schema() {
+    let mut graphs = Graphs::init("shire");
+    let mut schema = Schema::new();
+    schema.require_vertex_attr("Hobbit", "name");
+    schema.allow_relation("friend of", &["Hobbit"], &["Hobbit"]);
+    graphs.set_schema(schema, None).unwrap();
+
+    let result = graphs.merge_vertex("Hobbit", &[], None);
+    assert!(matches!(result, Err(GruPHstError::SchemaViolation(_))));
+    let frodo = graphs
+        .merge_vertex("Hobbit", &[("name", AttrValue::from("Frodo"))], None)
+        .unwrap();
+    let sam = graphs
+        .merge_vertex("Hobbit", &[("name", AttrValue::from("Sam"))], None)
+        .unwrap();
+    let result = graphs.merge_edge(&frodo, "enemy of", &sam, None);
+    assert!(matches!(result, Err(GruPHstError::SchemaViolation(_))));
+    graphs.merge_edge(&frodo, "friend of", &sam, None).unwrap();
+    assert_eq!(graphs.len(), 1);
+}
@@ -0,0 +1,161 @@
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{paths::PathOptions, traversal::Direction, Graphs},
+    vertex::Vertex,
+};
+
+fn road(graphs: &mut Graphs, from: &Vertex, to: &Vertex, km: &str) {
+    let mut edge = Edge::create(from, "road to", to);
+    edge.set_attr("km", km);
+    graphs.add_edge(&edge, None).unwrap();
+}
+
+fn prepare_paths_test() -> (Graphs, Vec<Vertex>) {
+    let mut graphs = Graphs::init("middle-earth");
+    let mut places = Vec::new();
+    for (label, x) in [
+        ("The Shire", 0),
+        ("Bree", 200),
+        ("Weathertop", 300),
+        ("Rivendell", 500),
+        ("Moria", 600),
+    ] {
+        let mut place = Vertex::new(label);
+        place.set_attr("x", x);
+        places.push(place);
+    }
+    road(&mut graphs, &places[0], &places[1], "200");
+    road(&mut graphs, &places[1], &places[2], "100");
+    road(&mut graphs, &places[2], &places[3], "200");
+    road(&mut graphs, &places[0], &places[3], "900");
+    road(&mut graphs, &places[3], &places[4], "150.5");
+    (graphs, places)
+}
+
+fn labels(edges: Vec<Edge>) -> Vec<String> {
+    edges
+        .iter()
+        .map(|e| e.get_to_vertex().get_label())
+        .collect()
+}
+
+#[test]
+fn should_find_unweighted_shortest_path() {
+    let (graphs, places) = prepare_paths_test();
+    let path = graphs
+        .shortest_path(
+            &places[0].get_id(),
+            &places[4].get_id(),
+            &PathOptions::unweighted(),
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(path.get_edges()), vec!["Rivendell", "Moria"]);
+    assert_eq!(path.get_cost(), 2.0);
+}
+
+#[test]
+fn should_find_dijkstra_shortest_path() {
+    let (graphs, places) = prepare_paths_test();
+    let path = graphs
+        .shortest_path(
+            &places[0].get_id(),
+            &places[4].get_id(),
+            &PathOptions::dijkstra("km"),
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        labels(path.get_edges()),
+        vec!["Bree", "Weathertop", "Rivendell", "Moria"]
+    );
+    assert_eq!(path.get_cost(), 650.5);
+}
+
+#[test]
+fn should_find_astar_shortest_path() {
+    let (graphs, places) = prepare_paths_test();
+    let x = |vertex: &Vertex| vertex.get_attr("x").unwrap().parse::<f64>().unwrap();
+    let options = PathOptions::astar("km", move |current, target| (x(target) - x(current)).abs());
+    let path = graphs
+        .shortest_path(&places[0].get_id(), &places[3].get_id(), &options, None)
+        .unwrap();
+    assert_eq!(
+        labels(path.get_edges()),
+        vec!["Bree", "Weathertop", "Rivendell"]
+    );
+    assert_eq!(path.get_cost(), 500.0);
+}
+
+#[test]
+fn should_find_path_following_direction() {
+    let (graphs, places) = prepare_paths_test();
+    let options = PathOptions::dijkstra("km");
+    assert_eq!(
+        graphs
+            .shortest_path(&places[4].get_id(), &places[0].get_id(), &options, None)
+            .unwrap_err(),
+        GruPHstError::NoPathFound(places[4].get_id(), places[0].get_id())
+    );
+    let path = graphs
+        .shortest_path(
+            &places[4].get_id(),
+            &places[0].get_id(),
+            &options.direction(Direction::Both),
+            None,
+        )
+        .unwrap();
+    assert_eq!(path.get_edges().len(), 4);
+    assert_eq!(path.get_edges()[0].get_to_vertex().get_label(), "Moria");
+    assert_eq!(path.get_cost(), 650.5);
+
+    let path = graphs
+        .shortest_path(
+            &places[0].get_id(),
+            &places[0].get_id(),
+            &PathOptions::unweighted(),
+            None,
+        )
+        .unwrap();
+    assert!(path.get_edges().is_empty());
+    assert_eq!(path.get_cost(), 0.0);
+}
+
+#[test]
+fn should_fail_with_not_valid_weights() {
+    let (mut graphs, places) = prepare_paths_test();
+    let from = places[0].get_id();
+    let to = places[4].get_id();
+    assert_eq!(
+        graphs
+            .shortest_path(&from, &to, &PathOptions::dijkstra("hours"), None)
+            .unwrap_err(),
+        GruPHstError::AttributeNotFound
+    );
+    road(&mut graphs, &places[1], &places[4], "far away");
+    assert_eq!(
+        graphs
+            .shortest_path(&from, &to, &PathOptions::dijkstra("km"), None)
+            .unwrap_err(),
+        GruPHstError::EdgeWeightNotValid(String::from("far away"))
+    );
+}
+
+#[test]
+fn should_fail_finding_path_for_unknown_vertices() {
+    let (graphs, places) = prepare_paths_test();
+    let options = PathOptions::unweighted();
+    assert_eq!(
+        graphs
+            .shortest_path(&places[0].get_id(), "foobar", &options, None)
+            .unwrap_err(),
+        GruPHstError::VertexNotFound
+    );
+    assert_eq!(
+        graphs
+            .shortest_path("foo", "bar", &options, Some("foobar"))
+            .unwrap_err(),
+        GruPHstError::VaultNotExists(String::from("foobar"))
+    );
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::cypher::{Binding, QueryValue},
+    graphs::Graphs,
+    vertex::Vertex,
+};
+
+fn prepare_pattern_test() -> Graphs {
+    let mut graphs = Graphs::init("middle-earth");
+    let gandalf = Vertex::new("Gandalf");
+    let saruman = Vertex::new("Saruman");
+    let radagast = Vertex::new("Radagast");
+    let frodo = Vertex::new("Frodo");
+    let mut orthanc = Vertex::new("Orthanc");
+    orthanc.set_attr("type", "tower");
+    let mut bag_end = Vertex::new("Bag End");
+    bag_end.set_attr("type", "hole");
+
+    graphs
+        .add_edge(&Edge::create(&gandalf, "knows", &saruman), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&saruman, "knows", &radagast), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&radagast, "knows", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&gandalf, "knows", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&saruman, "lives at", &orthanc), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&frodo, "lives at", &bag_end), None)
+        .unwrap();
+
+    graphs
+}
+
+fn label(bindings: &HashMap<String, Binding>, var: &str) -> String {
+    bindings[var].as_vertex().unwrap().get_label()
+}
+
+#[test]
+fn should_match_multi_hop_pattern_with_variable_length() {
+    let graphs = prepare_pattern_test();
+    let matches = graphs
+        .match_pattern(
+            r#"(a)-[:knows*1..3]->(b)-[:lives at]->(c {type: "tower"})"#,
+            None,
+        )
+        .unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(label(&matches[0], "a"), "Gandalf");
+    assert_eq!(label(&matches[0], "b"), "Saruman");
+    assert_eq!(label(&matches[0], "c"), "Orthanc");
+    assert_eq!(matches[0].len(), 3);
+
+    let mut homes: Vec<String> = graphs
+        .match_pattern("(a:Gandalf)-[k:knows*1..3]->(b)-[l:lives at]->(c)", None)
+        .unwrap()
+        .iter()
+        .map(|m| {
+            assert_eq!(m["l"].as_edge().unwrap().get_relation(), "lives at");
+            format!(
+                "{} {} {}",
+                m["k"].as_edges().unwrap().len(),
+                label(m, "b"),
+                label(m, "c")
+            )
+        })
+        .collect();
+    homes.sort();
+    assert_eq!(
+        homes,
+        vec!["1 Frodo Bag End", "1 Saruman Orthanc", "3 Frodo Bag End"]
+    );
+}
+
+#[test]
+fn should_match_variable_length_bounds() {
+    let graphs = prepare_pattern_test();
+    let lengths = |pattern: &str| {
+        let mut lengths: Vec<usize> = graphs
+            .match_pattern(pattern, None)
+            .unwrap()
+            .iter()
+            .map(|m| m["k"].as_edges().unwrap().len())
+            .collect();
+        lengths.sort();
+        lengths
+    };
+    assert_eq!(lengths("(:Gandalf)-[k:knows*]->(:Frodo)"), vec![1, 3]);
+    assert_eq!(lengths("(:Gandalf)-[k:knows*2..]->(:Frodo)"), vec![3]);
+    assert_eq!(lengths("(:Gandalf)-[k:knows*..2]->(:Frodo)"), vec![1]);
+    assert_eq!(lengths("(:Gandalf)-[k:knows*3]->(b)"), vec![3]);
+    assert_eq!(lengths("(:Gandalf)-[k*0..1]->(:Gandalf)"), vec![0]);
+    assert_eq!(lengths("(:Frodo)<-[k:knows*2]-(b)"), vec![2]);
+
+    let matches = graphs
+        .match_pattern("(:Frodo)-[k:knows*]-(:Frodo)", None)
+        .unwrap();
+    // around the cycle, in both directions
+    assert_eq!(matches.len(), 2);
+    assert!(matches
+        .iter()
+        .all(|m| m["k"].as_edges().unwrap().len() == 4));
+}
+
+#[test]
+fn should_query_variable_length_relationships() {
+    let graphs = prepare_pattern_test();
+    let result = graphs
+        .query(
+            "MATCH (a:Gandalf)-[path:knows*2..3]->(b) RETURN b, path ORDER",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        result,
+        GruPHstError::QuerySyntax(56, "expected end of query, found 'ORDER'".to_string())
+    );
+
+    let result = graphs
+        .query(
+            "MATCH (a:Gandalf)-[path:knows*2..3]->(b) RETURN label(b), path",
+            None,
+        )
+        .unwrap();
+    assert_eq!(result.len(), 2);
+    for row in result.get_rows() {
+        let QueryValue::Edges(edges) = &row[1] else {
+            panic!("expected the edges of the path");
+        };
+        assert_eq!(edges[0].get_from_vertex().get_label(), "Gandalf");
+        assert_eq!(
+            edges.last().unwrap().get_to_vertex().get_label(),
+            row[0].as_value().unwrap().to_string()
+        );
+    }
+}
+
+#[test]
+fn should_fail_on_not_valid_patterns() {
+    let graphs = prepare_pattern_test();
+    assert_eq!(
+        graphs.match_pattern("(a)-[:knows*3..1]->(b)", None),
+        Err(GruPHstError::QuerySyntax(
+            12,
+            "maximum length lower than the minimum".to_string()
+        ))
+    );
+    assert_eq!(
+        graphs.match_pattern("(a)-[:knows]->(b) RETURN b", None),
+        Err(GruPHstError::QuerySyntax(
+            18,
+            "expected end of pattern, found 'RETURN'".to_string()
+        ))
+    );
+    assert_eq!(
+        graphs.match_pattern("(a)-[:knows]->(b)", Some("moria")),
+        Err(GruPHstError::VaultNotExists("moria".to_string()))
+    );
+    assert!(graphs
+        .match_pattern("(a:Sauron)-[:knows]->(b)", None)
+        .unwrap()
+        .is_empty());
+}
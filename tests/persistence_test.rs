@@ -1,5 +1,9 @@
+use std::fs;
+
+use gruphst::attributes::AttrValue;
 use gruphst::edge::Edge;
-use gruphst::graphs::Graphs;
+use gruphst::errors::GruPHstError;
+use gruphst::graphs::{Compression, EncryptionKey, Graphs};
 use gruphst::vertex::Vertex;
 
 fn prepare_persistence_test() -> (Graphs, Edge, Edge) {
@@ -11,14 +15,14 @@ fn prepare_persistence_test() -> (Graphs, Edge, Edge) {
     let vertex2 = Vertex::new("b edge");
 
     let edge1 = Edge::create(&vertex1, "relation a-b", &vertex2);
-    gru.add_edge(&edge1, None);
+    gru.add_edge(&edge1, None).unwrap();
 
     let vertex3 = Vertex::new("c edge");
 
     let vertex4 = Vertex::new("d edge");
 
     let edge2 = Edge::create(&vertex3, "relation c-d", &vertex4);
-    gru.add_edge(&edge2, None);
+    gru.add_edge(&edge2, None).unwrap();
 
     (gru, edge1, edge2)
 }
@@ -34,6 +38,7 @@ fn assertion_persisted_graphs(grphs: Graphs, name: String, edge1: Edge, edge2: E
 }
 
 #[test]
+#[allow(deprecated)]
 fn should_persists_deprecated_method() {
     let (gru, edge1, edge2) = prepare_persistence_test();
 
@@ -92,10 +97,423 @@ fn load_persisted_should_fail_fail_does_not_exists() {
 
 #[test]
 fn load_persisted_should_fail_wrong_file_format() {
-    assert!(Graphs::load("tests/data/wrong-persisted-file.grphst").is_err());
+    let err = Graphs::load("tests/data/wrong-persisted-file.grphst").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<GruPHstError>(),
+        Some(&GruPHstError::SnapshotBadMagic)
+    );
+}
+
+fn load_error(file_name: &str, bytes: &[u8]) -> GruPHstError {
+    fs::write(file_name, bytes).unwrap();
+    let err = Graphs::load(file_name).unwrap_err();
+    fs::remove_file(file_name).unwrap();
+    err.downcast::<GruPHstError>().map(|err| *err).unwrap()
+}
+
+#[test]
+fn should_save_with_a_header_and_without_leftovers() {
+    let mut gru = Graphs::init("saved-with-header");
+    gru.add_edge(
+        &Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam")),
+        None,
+    )
+    .unwrap();
+    let path = "./tests/data/";
+    gru.save(Some(path)).unwrap();
+    // saving again replaces the previous file
+    gru.save(Some(path)).unwrap();
+    let file_name = "./tests/data/saved-with-header.grphst";
+    let bytes = fs::read(file_name).unwrap();
+    assert!(bytes.starts_with(b"GRUPHST\0"));
+    assert_eq!(u16::from_le_bytes(bytes[8..10].try_into().unwrap()), 5);
+    assert!(fs::metadata(format!("{}.tmp", file_name)).is_err());
+    assert_eq!(Graphs::load(file_name).unwrap().len(), 1);
+    fs::remove_file(file_name).unwrap();
+}
+
+#[test]
+fn load_persisted_should_fail_corrupted_or_unsupported_file() {
+    let (gru, _, _) = prepare_persistence_test();
+    gru.save(Some("./tests/data/corrupted-")).unwrap();
+    let file_name = "./tests/data/corrupted-graphs-a.grphst";
+    let bytes = fs::read(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let mut tampered = bytes.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 0xff;
+    assert_eq!(
+        load_error("./tests/data/tampered.grphst", &tampered),
+        GruPHstError::SnapshotChecksumMismatch
+    );
+    assert_eq!(
+        load_error("./tests/data/truncated.grphst", &bytes[..12]),
+        GruPHstError::SnapshotChecksumMismatch
+    );
+    let mut future = bytes.clone();
+    future[8..10].copy_from_slice(&99u16.to_le_bytes());
+    assert_eq!(
+        load_error("./tests/data/future.grphst", &future),
+        GruPHstError::SnapshotUnsupportedVersion(99)
+    );
+    let mut unknown_codec = bytes.clone();
+    unknown_codec[10..12].copy_from_slice(&7u16.to_le_bytes());
+    assert_eq!(
+        load_error("./tests/data/unknown-codec.grphst", &unknown_codec),
+        GruPHstError::SnapshotUnsupportedCompression(7)
+    );
+}
+
+#[test]
+fn should_load_files_saved_without_header() {
+    let (gru, edge1, edge2) = prepare_persistence_test();
+    let file_name = "./tests/data/without-header.grphst";
+    fs::write(file_name, bincode::serialize(&gru).unwrap()).unwrap();
+    let grphs = Graphs::load(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+    assertion_persisted_graphs(grphs, gru.get_label(), edge1, edge2);
 }
 
 #[test]
 fn load_persisted_fail_file_size_bigger_than_max_configured_memory() {
     assert!(Graphs::load("tests/data/big-big-big.grphst").is_err());
 }
+
+#[test]
+fn should_keep_shared_vertices_after_save_and_load() {
+    let mut gru = Graphs::init("shared-vertices");
+    let mut gandalf = Vertex::new("Gandalf");
+    gandalf.set_attr("known as", "The Gray");
+    let hobbits = ["Frodo", "Sam", "Merry", "Pippin"];
+    for hobbit in hobbits.iter() {
+        gru.add_edge(
+            &Edge::create(&gandalf, "friend of", &Vertex::new(hobbit)),
+            None,
+        )
+        .unwrap();
+        gru.add_edge(
+            &Edge::create(&Vertex::new(hobbit), "friend of", &gandalf),
+            None,
+        )
+        .unwrap();
+    }
+    gru.add_vertex(&Vertex::new("Tom Bombadil"), None).unwrap();
+
+    let path = "./tests/data/";
+    gru.save(Some(path)).unwrap();
+    let file_name = format!("{}{}.grphst", path, gru.get_label());
+    let loaded = Graphs::load(&file_name).unwrap();
+
+    assert_eq!(loaded.len(), 8);
+    assert_eq!(loaded.get_vertices(None).unwrap().len(), 10);
+    let mut loaded_gandalf = loaded.find_vertex_by_id(&gandalf.get_id(), None).unwrap();
+    loaded_gandalf.set_attr("known as", "The White");
+    for edge in loaded.get_edges(None).unwrap() {
+        let vertex = edge.find_vertex_by_id(&gandalf.get_id()).unwrap();
+        assert_eq!(vertex.get_attr("known as").unwrap(), "The White");
+    }
+}
+
+#[test]
+fn should_keep_attribute_types_after_save_and_load() {
+    let mut gru = Graphs::init("typed-attributes");
+    let mut gandalf = Vertex::new("Gandalf");
+    gandalf.set_attr("years old", 24000);
+    gandalf.set_attr("wizard", true);
+    gandalf.set_attr("height", 1.68);
+    gandalf.set_attr_vec_u8("code", &[3, 1, 3, 3, 7]);
+    let mut edge = Edge::create(&gandalf, "friend of", &Vertex::new("Frodo"));
+    edge.set_attr("since", AttrValue::Timestamp(-1_000));
+    gru.add_edge(&edge, None).unwrap();
+
+    let path = "./tests/data/";
+    gru.save(Some(path)).unwrap();
+    let file_name = format!("{}{}.grphst", path, gru.get_label());
+    let loaded = Graphs::load(&file_name).unwrap();
+
+    let loaded_gandalf = loaded.find_vertex_by_id(&gandalf.get_id(), None).unwrap();
+    assert_eq!(loaded_gandalf.get_attr_i64("years old").unwrap(), 24000);
+    assert!(loaded_gandalf.get_attr_bool("wizard").unwrap());
+    assert_eq!(loaded_gandalf.get_attr_f64("height").unwrap(), 1.68);
+    assert_eq!(
+        loaded_gandalf.get_attr_vec_u8("code").unwrap(),
+        vec![3, 1, 3, 3, 7]
+    );
+    let loaded_edge = &loaded.get_edges(None).unwrap()[0];
+    assert_eq!(loaded_edge.get_attr_timestamp("since").unwrap(), -1_000);
+}
+
+#[test]
+fn should_migrate_stringly_typed_attributes_on_load() {
+    let loaded = Graphs::load("tests/data/legacy-string-attrs.grphst").unwrap();
+    assert_eq!(loaded.get_label(), "legacy-string-attrs");
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.get_vertices(None).unwrap().len(), 4);
+
+    let edges = loaded.find_edges_by_relation("friend of", None).unwrap();
+    let edge = edges[0];
+    assert_eq!(edge.get_attr("duration in years").unwrap(), "42");
+    assert_eq!(
+        edge.get_attr_value("duration in years").unwrap(),
+        AttrValue::Str(String::from("42"))
+    );
+    let gandalf = edge.get_from_vertex();
+    assert_eq!(gandalf.get_attr("years old").unwrap(), "24000");
+    assert_eq!(gandalf.get_attr("known as").unwrap(), "The Gray");
+    assert_eq!(
+        gandalf.get_attr_vec_u8("code").unwrap(),
+        vec![3, 1, 3, 3, 7]
+    );
+    assert_eq!(gandalf.get_attr_vec_u8_keys(), vec!["code"]);
+}
+
+#[test]
+fn should_migrate_files_persisted_by_gruphst_0_12() {
+    let loaded = Graphs::load("tests/data/gruphst-0.12.grphst").unwrap();
+    assert_eq!(loaded.get_label(), "my graphs");
+    assert_eq!(loaded.len(), 4);
+    assert_eq!(loaded.get_vertices(None).unwrap().len(), 3);
+
+    let edges = loaded.get_edges(None).unwrap();
+    let alice = edges[0].get_from_vertex();
+    assert_eq!(alice.get_label(), "Alice");
+    assert_eq!(alice.get_attr("address").unwrap(), "Elm street");
+    assert!(alice.get_attr_vec_u8_keys().is_empty());
+    // the copies of a vertex are shared again
+    let mut alice_copy = edges[1].get_to_vertex();
+    alice_copy.set_attr("age", 30);
+    assert_eq!(alice.get_attr_i64("age").unwrap(), 30);
+    assert_eq!(edges[2].get_relation(), "relative of");
+}
+
+#[test]
+fn should_migrate_files_persisted_by_gruphst_0_16() {
+    let loaded = Graphs::load("tests/data/gruphst-0.16.grphst").unwrap();
+    assert_eq!(loaded.get_label(), "middle-earth");
+    assert_eq!(loaded.len_graphs(), 2);
+    assert_eq!(loaded.get_vertices(Some("middle-earth")).unwrap().len(), 3);
+    assert_eq!(loaded.get_vertices(Some("enemies")).unwrap().len(), 2);
+
+    let friends = loaded
+        .find_edges_by_relation("friend of", Some("middle-earth"))
+        .unwrap()[0]
+        .clone();
+    assert_eq!(friends.get_attr("duration in years").unwrap(), "42");
+    let gandalf = friends.get_from_vertex();
+    assert_eq!(gandalf.get_attr("known as").unwrap(), "The Gray");
+    assert_eq!(
+        gandalf.get_attr_vec_u8("code").unwrap(),
+        vec![3, 1, 3, 3, 7]
+    );
+    // the same vertex on both vaults is shared
+    let mut gandalf_enemy = loaded.get_edges(Some("enemies")).unwrap()[0].get_to_vertex();
+    gandalf_enemy.set_attr("years old", 24000);
+    assert_eq!(gandalf.get_attr_i64("years old").unwrap(), 24000);
+
+    // saved again with the current format
+    let path = "./tests/data/migrated-";
+    loaded.save(Some(path)).unwrap();
+    let file_name = "./tests/data/migrated-middle-earth.grphst";
+    assert!(fs::read(file_name).unwrap().starts_with(b"GRUPHST\0"));
+    let reloaded = Graphs::load(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+    assert_eq!(reloaded.len(), 3);
+}
+
+#[test]
+fn should_migrate_files_persisted_with_the_format_2() {
+    let file_name = "tests/data/format-v2.grphst";
+    let loaded = Graphs::load(file_name).unwrap();
+    assert_eq!(loaded.get_label(), "format-v2");
+    assert_eq!(loaded.len_graphs(), 2);
+    assert!(loaded.get_indexes(None).unwrap().is_empty());
+    let frodo = loaded
+        .find_vertices_with_attr_equals_to("email", "frodo@shire.me", None)
+        .unwrap();
+    assert_eq!(frodo[0].get_attr_i64("years old").unwrap(), 50);
+    let edges = loaded.get_edges(Some("fellowship")).unwrap();
+    assert_eq!(edges[0].get_from_vertex(), frodo[0]);
+
+    // also persisted without the header
+    let headerless = "./tests/data/format-v2-headerless.grphst";
+    fs::write(headerless, &fs::read(file_name).unwrap()[16..]).unwrap();
+    let loaded = Graphs::load(headerless).unwrap();
+    fs::remove_file(headerless).unwrap();
+    assert_eq!(loaded.len(), 2);
+}
+
+#[test]
+fn should_migrate_files_persisted_with_the_format_3() {
+    let loaded = Graphs::load("tests/data/format-v3.grphst").unwrap();
+    assert_eq!(loaded.get_label(), "format-v3");
+    assert_eq!(loaded.len_graphs(), 2);
+    assert_eq!(loaded.get_schema(Some("format-v3")), None);
+    assert_eq!(loaded.get_indexes(Some("format-v3")).unwrap().len(), 1);
+    let frodo = loaded
+        .find_vertices_with_attr_equals_to("email", "frodo@shire.me", Some("format-v3"))
+        .unwrap();
+    assert_eq!(frodo[0].get_attr("name").unwrap(), "Frodo");
+    let lives_at = loaded.get_edges(Some("shire")).unwrap();
+    assert_eq!(lives_at[0].get_from_vertex(), frodo[0]);
+}
+
+#[test]
+fn should_migrate_files_persisted_with_the_format_4() {
+    let file_name = "tests/data/format-v4.grphst";
+    let loaded = Graphs::load(file_name).unwrap();
+    assert_eq!(loaded.get_label(), "format-v4");
+    assert_eq!(loaded.len_graphs(), 2);
+    assert!(loaded.get_schema(None).is_some());
+    assert_eq!(loaded.get_indexes(None).unwrap().len(), 1);
+    let frodo = loaded
+        .find_vertices_with_attr_equals_to("email", "frodo@shire.me", None)
+        .unwrap();
+    assert_eq!(frodo[0].get_attr("name").unwrap(), "Frodo");
+    let lives_at = loaded.get_edges(Some("shire")).unwrap();
+    assert_eq!(lives_at[0].get_from_vertex(), frodo[0]);
+
+    // also persisted without the header
+    let headerless = "./tests/data/format-v4-headerless.grphst";
+    fs::write(headerless, &fs::read(file_name).unwrap()[16..]).unwrap();
+    let loaded = Graphs::load(headerless).unwrap();
+    fs::remove_file(headerless).unwrap();
+    assert_eq!(loaded.len(), 2);
+}
+
+fn prepare_repetitive_graphs(name: &str) -> Graphs {
+    let mut graphs = Graphs::init(name);
+    let gandalf = Vertex::new("Gandalf");
+    for i in 0..100 {
+        let mut hobbit = Vertex::new("hobbit");
+        hobbit.set_attr("lives at", "The Shire");
+        let mut edge = Edge::create(&gandalf, "friend of", &hobbit);
+        edge.set_attr("met on", i);
+        graphs.add_edge(&edge, None).unwrap();
+    }
+    graphs
+}
+
+#[test]
+fn should_save_without_compression() {
+    let graphs = prepare_repetitive_graphs("not-compressed");
+    graphs
+        .save_compressed(Some("./tests/data/"), Compression::None)
+        .unwrap();
+    let file_name = "./tests/data/not-compressed.grphst";
+    let bytes = fs::read(file_name).unwrap();
+    assert_eq!(u16::from_le_bytes(bytes[10..12].try_into().unwrap()), 0);
+    let loaded = Graphs::load(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+    assert_eq!(loaded.len(), 100);
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn should_save_compressed_with_lz4() {
+    let graphs = prepare_repetitive_graphs("compressed-lz4");
+    let path = "./tests/data/plain-";
+    graphs
+        .save_compressed(Some(path), Compression::None)
+        .unwrap();
+    let plain_file_name = "./tests/data/plain-compressed-lz4.grphst";
+    let plain_len = fs::metadata(plain_file_name).unwrap().len();
+    fs::remove_file(plain_file_name).unwrap();
+
+    graphs
+        .save_compressed(Some("./tests/data/"), Compression::Lz4)
+        .unwrap();
+    let file_name = "./tests/data/compressed-lz4.grphst";
+    let bytes = fs::read(file_name).unwrap();
+    assert!(bytes.starts_with(b"GRUPHST\0"));
+    assert_eq!(u16::from_le_bytes(bytes[10..12].try_into().unwrap()), 1);
+    assert!((bytes.len() as u64) < plain_len / 2);
+
+    // the codec on the header decompresses it on load
+    let loaded = Graphs::load(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+    assert_eq!(loaded.len(), 100);
+    let edge = &loaded.get_edges(None).unwrap()[42];
+    assert_eq!(
+        edge.get_to_vertex().get_attr("lives at").unwrap(),
+        "The Shire"
+    );
+    assert_eq!(loaded.get_vertices(None).unwrap().len(), 101);
+}
+
+fn load_encrypted_error(file_name: &str, key: &EncryptionKey) -> GruPHstError {
+    let err = Graphs::load_encrypted(file_name, key).unwrap_err();
+    err.downcast::<GruPHstError>().map(|err| *err).unwrap()
+}
+
+#[test]
+fn should_save_encrypted_with_a_key() {
+    let mut graphs = Graphs::init("encrypted-with-key");
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("carries", "The One Ring");
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "friend of", &Vertex::new("Sam")),
+            None,
+        )
+        .unwrap();
+    let key = EncryptionKey::new([42; 32]);
+    graphs.save_encrypted(Some("./tests/data/"), &key).unwrap();
+    let file_name = "./tests/data/encrypted-with-key.grphst";
+
+    let bytes = fs::read(file_name).unwrap();
+    assert!(bytes.starts_with(b"GRUPHST\0"));
+    assert_eq!(bytes[11], 1);
+    let plain = b"The One Ring";
+    assert!(!bytes.windows(plain.len()).any(|window| window == plain));
+
+    let loaded = Graphs::load_encrypted(file_name, &key).unwrap();
+    let frodo = loaded.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    assert_eq!(frodo.get_attr("carries").unwrap(), "The One Ring");
+
+    let err = Graphs::load(file_name).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<GruPHstError>(),
+        Some(&GruPHstError::SnapshotEncrypted)
+    );
+    assert_eq!(
+        load_encrypted_error(file_name, &EncryptionKey::new([7; 32])),
+        GruPHstError::SnapshotWrongKey
+    );
+
+    let mut unknown_cipher = bytes.clone();
+    unknown_cipher[11] = 9;
+    assert_eq!(
+        load_error("./tests/data/unknown-cipher.grphst", &unknown_cipher),
+        GruPHstError::SnapshotUnsupportedCipher(9)
+    );
+    fs::remove_file(file_name).unwrap();
+}
+
+#[test]
+fn should_save_encrypted_with_a_passphrase() {
+    let (mut gru, edge1, edge2) = prepare_persistence_test();
+    let key = EncryptionKey::from_passphrase("speak friend and enter");
+    gru.save_encrypted(Some("./tests/data/passphrase-"), &key)
+        .unwrap();
+    let file_name = "./tests/data/passphrase-graphs-a.grphst";
+
+    let loaded = Graphs::load_encrypted(
+        file_name,
+        &EncryptionKey::from_passphrase("speak friend and enter"),
+    )
+    .unwrap();
+    assertion_persisted_graphs(loaded, gru.get_label(), edge1, edge2);
+    assert_eq!(
+        load_encrypted_error(file_name, &EncryptionKey::from_passphrase("mellon")),
+        GruPHstError::SnapshotWrongKey
+    );
+    assert!(!format!("{:?}", key).contains("speak friend"));
+    fs::remove_file(file_name).unwrap();
+
+    // files saved without encryption are loaded with a key too
+    gru.save(Some("./tests/data/plain-")).unwrap();
+    let file_name = "./tests/data/plain-graphs-a.grphst";
+    assert_eq!(Graphs::load_encrypted(file_name, &key).unwrap().len(), 2);
+    fs::remove_file(file_name).unwrap();
+}
@@ -0,0 +1,142 @@
+use std::ops::Bound;
+
+use gruphst::{
+    attributes::AttrValue, edge::Edge, errors::GruPHstError, graphs::Graphs, vertex::Vertex,
+};
+
+fn prepare_range_queries_test() -> Graphs {
+    let mut graphs = Graphs::init("middle-earth");
+    let mut gandalf = Vertex::new("Gandalf");
+    gandalf.set_attr("years old", 24000);
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("years old", 50);
+    frodo.set_attr("height", 1.22);
+    let mut sam = Vertex::new("Sam");
+    sam.set_attr("years old", 38.5);
+    let mut aragorn = Vertex::new("Aragorn");
+    aragorn.set_attr("years old", "87");
+
+    let mut edge = Edge::create(&gandalf, "friend of", &frodo);
+    edge.set_attr("duration in years", 42);
+    graphs.add_edge(&edge, None).unwrap();
+    let mut edge = Edge::create(&sam, "friend of", &frodo);
+    edge.set_attr("duration in years", 33.3);
+    graphs.add_edge(&edge, None).unwrap();
+    let mut edge = Edge::create(&aragorn, "friend of", &frodo);
+    edge.set_attr("duration in years", 1);
+    edge.set_attr("since", AttrValue::Timestamp(1_000));
+    graphs.add_edge(&edge, None).unwrap();
+
+    let mut sauron = Vertex::new("Sauron");
+    sauron.set_attr("years old", 50000);
+    let mut edge = Edge::create(&sauron, "enemy of", &gandalf);
+    edge.set_attr("duration in years", 3000);
+    graphs.add_edge(&edge, Some("enemies")).unwrap();
+
+    graphs
+}
+
+fn labels(vertices: Vec<Vertex>) -> Vec<String> {
+    let mut labels: Vec<String> = vertices.iter().map(|v| v.get_label()).collect();
+    labels.sort();
+    labels
+}
+
+#[test]
+fn should_find_vertices_with_attribute_in_range() {
+    let graphs = prepare_range_queries_test();
+    let found = graphs
+        .find_vertices_with_attr_in_range("years old", 1000.., Some("middle-earth"))
+        .unwrap();
+    assert_eq!(labels(found), vec!["Gandalf"]);
+    let found = graphs
+        .find_vertices_with_attr_in_range("years old", 30.0..=50.0, Some("middle-earth"))
+        .unwrap();
+    assert_eq!(labels(found), vec!["Frodo", "Sam"]);
+    let found = graphs
+        .find_vertices_with_attr_in_range(
+            "years old",
+            (Bound::Excluded(50), Bound::Unbounded),
+            Some("middle-earth"),
+        )
+        .unwrap();
+    assert_eq!(labels(found), vec!["Gandalf"]);
+    assert_eq!(
+        graphs
+            .find_vertices_with_attr_in_range("years old", ..10, Some("middle-earth"))
+            .unwrap_err(),
+        GruPHstError::VertexNotFound
+    );
+}
+
+#[test]
+fn should_find_vertices_with_attribute_in_range_in_graphs() {
+    let graphs = prepare_range_queries_test();
+    let found = graphs
+        .find_vertices_with_attr_in_range_in_graphs("years old", 1000..)
+        .unwrap();
+    assert_eq!(labels(found), vec!["Gandalf", "Sauron"]);
+    let found = graphs
+        .find_vertices_with_attr_in_range_in_graphs("years old", "80".."90")
+        .unwrap();
+    assert_eq!(labels(found), vec!["Aragorn"]);
+}
+
+#[test]
+fn should_find_edges_with_attribute_in_range() {
+    let graphs = prepare_range_queries_test();
+    let found = graphs
+        .find_edges_with_attr_in_range("duration in years", 10..=50, Some("middle-earth"))
+        .unwrap();
+    assert_eq!(found.len(), 2);
+    let found = graphs
+        .find_edges_with_attr_in_range("since", AttrValue::Timestamp(0).., Some("middle-earth"))
+        .unwrap();
+    assert_eq!(found[0].get_from_vertex().get_label(), "Aragorn");
+    let found = graphs
+        .find_edges_with_attr_in_range_in_graphs("duration in years", 40..)
+        .unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(
+        graphs
+            .find_edges_with_attr_in_range("duration in years", 100.., Some("middle-earth"))
+            .unwrap_err(),
+        GruPHstError::EdgeNotFound
+    );
+}
+
+#[test]
+fn should_find_edges_with_vertex_attribute_in_range() {
+    let graphs = prepare_range_queries_test();
+    let found = graphs
+        .find_edges_with_vertex_attr_in_range("height", 1..2, Some("middle-earth"))
+        .unwrap();
+    assert_eq!(found.len(), 3);
+    let found = graphs
+        .find_edges_with_vertex_attr_in_range_in_graphs("years old", 40000..)
+        .unwrap();
+    assert_eq!(found[0].get_relation(), "enemy of");
+    assert_eq!(
+        graphs
+            .find_edges_with_vertex_attr_in_range("years old", 1..2, Some("foobar"))
+            .unwrap_err(),
+        GruPHstError::VaultNotExists(String::from("foobar"))
+    );
+}
+
+#[test]
+fn should_check_attribute_in_range_on_vertex_and_edge() {
+    let mut vertex = Vertex::new("Frodo");
+    vertex.set_attr("years old", 50);
+    vertex.set_attr("surname", "Baggins");
+    assert!(vertex.has_attr_in_range("years old", 50..=50));
+    assert!(!vertex.has_attr_in_range("years old", 50..50));
+    assert!(!vertex.has_attr_in_range("surname", 0..));
+    assert!(vertex.has_attr_in_range("surname", "B".."C"));
+
+    let mut edge = Edge::create(&vertex, "lives at", &Vertex::new("The Shire"));
+    edge.set_attr("km", 10.5);
+    assert!(edge.has_attr_in_range("km", 10..11));
+    assert!(!edge.has_attr_in_range("foo", 10..11));
+    assert!(edge.has_vertex_with_attr_in_range("years old", 18..));
+}
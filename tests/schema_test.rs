@@ -0,0 +1,254 @@
+use std::fs;
+
+use gruphst::{
+    attributes::AttrType,
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{Graphs, IndexKind, IndexTarget, Schema},
+    vertex::Vertex,
+};
+
+fn hobbit(name: &str) -> Vertex {
+    let mut vertex = Vertex::new("Hobbit");
+    vertex.set_attr("name", name);
+    vertex
+}
+
+fn prepare_schema() -> Schema {
+    let mut schema = Schema::new();
+    schema.allow_relation("friend of", &["Hobbit"], &["Hobbit"]);
+    schema.allow_relation("lives at", &["Hobbit"], &[]);
+    schema.require_vertex_attr("Hobbit", "name");
+    schema.set_vertex_attr_type("Hobbit", "years old", AttrType::Int);
+    schema.require_edge_attr("lives at", "since");
+    schema.set_edge_attr_type("lives at", "since", AttrType::Int);
+    schema.add_unique_key("Hobbit", &["name"]);
+    schema
+}
+
+fn is_violation<T>(result: Result<T, GruPHstError>) -> bool {
+    matches!(result, Err(GruPHstError::SchemaViolation(_)))
+}
+
+#[test]
+fn should_reject_the_edges_not_following_the_schema() {
+    let mut graphs = Graphs::init("shire");
+    graphs.set_schema(prepare_schema(), None).unwrap();
+    let frodo = hobbit("Frodo");
+    let sam = hobbit("Sam");
+    graphs
+        .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+        .unwrap();
+
+    let gandalf = Vertex::new("Wizard");
+    let result = graphs.add_edge(&Edge::create(&frodo, "enemy of", &sam), None);
+    assert_eq!(
+        result,
+        Err(GruPHstError::SchemaViolation(String::from(
+            "relation \"enemy of\" is not allowed"
+        )))
+    );
+    let result = graphs.add_edge(&Edge::create(&frodo, "friend of", &gandalf), None);
+    assert_eq!(
+        result,
+        Err(GruPHstError::SchemaViolation(String::from(
+            "relation \"friend of\" does not permit the label \"Wizard\" on its to vertex"
+        )))
+    );
+    let result = graphs.add_edge(
+        &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+        None,
+    );
+    assert!(is_violation(result));
+    let mut lives = Edge::create(&frodo, "lives at", &Vertex::new("Bag End"));
+    lives.set_attr("since", "2968");
+    assert!(is_violation(graphs.add_edge(&lives, None)));
+    lives.set_attr("since", 2968);
+    graphs.add_edge(&lives, None).unwrap();
+
+    let mut merry = Vertex::new("Hobbit");
+    merry.set_attr("years old", 36);
+    let result = graphs.add_edge(&Edge::create(&merry, "friend of", &sam), None);
+    assert!(is_violation(result));
+    merry.set_attr("name", "Merry");
+    merry.set_attr("years old", 36.5);
+    let result = graphs.add_edge(&Edge::create(&merry, "friend of", &sam), None);
+    assert!(is_violation(result));
+
+    // a batch is rejected as a whole
+    let mut edges = vec![
+        Edge::create(&hobbit("Pippin"), "friend of", &sam),
+        Edge::create(&frodo, "enemy of", &sam),
+    ];
+    assert!(is_violation(graphs.add_edges(&mut edges, None)));
+    assert_eq!(edges.len(), 2);
+    assert_eq!(graphs.len(), 2);
+
+    // updated edges are checked as well
+    lives.set_attr("since", 2968.5);
+    assert!(is_violation(graphs.update_graph(&lives, None)));
+    lives.set_attr("since", 2980);
+    graphs.update_graph(&lives, None).unwrap();
+    assert_eq!(graphs.len(), 2);
+
+    // other vaults are not restricted
+    let result = graphs.add_edge(&Edge::create(&frodo, "enemy of", &gandalf), Some("mordor"));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn should_keep_the_unique_keys() {
+    for indexed in [false, true] {
+        let mut graphs = Graphs::init("shire");
+        if indexed {
+            graphs
+                .create_index("name", IndexTarget::Vertex, IndexKind::Hash, None)
+                .unwrap();
+        }
+        let mut schema = prepare_schema();
+        schema.add_unique_key("Hobbit", &["family", "years old"]);
+        graphs.set_schema(schema, None).unwrap();
+        let mut frodo = hobbit("Frodo");
+        frodo.set_attr("family", "Baggins");
+        frodo.set_attr("years old", 50);
+        let sam = hobbit("Sam");
+        graphs
+            .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+            .unwrap();
+
+        // the same vertex can be added again
+        graphs
+            .add_edge(&Edge::create(&sam, "friend of", &frodo), None)
+            .unwrap();
+        let result = graphs.add_vertex(&hobbit("Frodo"), None);
+        assert!(is_violation(result));
+
+        let mut bilbo = hobbit("Bilbo");
+        bilbo.set_attr("family", "Baggins");
+        bilbo.set_attr("years old", 50);
+        assert!(is_violation(graphs.add_vertex(&bilbo, None)));
+        bilbo.set_attr("years old", 111);
+        graphs.add_vertex(&bilbo, None).unwrap();
+
+        // the vertices added together are unique too
+        let mut edges = vec![
+            Edge::create(&hobbit("Merry"), "friend of", &sam),
+            Edge::create(&hobbit("Merry"), "friend of", &frodo),
+        ];
+        assert!(is_violation(graphs.add_edges(&mut edges, None)));
+        assert_eq!(graphs.get_vertices(None).unwrap().len(), 3);
+    }
+}
+
+#[test]
+fn should_set_a_schema_only_on_a_vault_following_it() {
+    let mut graphs = Graphs::init("shire");
+    let twins = Edge::create(&hobbit("Frodo"), "friend of", &hobbit("Frodo"));
+    graphs.add_edge(&twins, None).unwrap();
+    assert!(is_violation(graphs.set_schema(prepare_schema(), None)));
+    assert_eq!(graphs.get_schema(None), None);
+    assert_eq!(
+        graphs.set_schema(Schema::new(), Some("mordor")),
+        Err(GruPHstError::VaultNotExists(String::from("mordor")))
+    );
+
+    graphs.insert("rivendell").unwrap();
+    let rivendell = Some("rivendell");
+    graphs.set_schema(prepare_schema(), rivendell).unwrap();
+    assert_eq!(graphs.get_schema(rivendell), Some(&prepare_schema()));
+    let enemies = Edge::create(&hobbit("Sam"), "enemy of", &hobbit("Gollum"));
+    let result: Result<(), GruPHstError> = graphs.transaction(|tx| {
+        tx.remove_schema(rivendell)?;
+        tx.add_edge(&enemies, rivendell)?;
+        Err(GruPHstError::VaultEmpty)
+    });
+    assert_eq!(result, Err(GruPHstError::VaultEmpty));
+    assert_eq!(graphs.get_schema(rivendell), Some(&prepare_schema()));
+    assert!(is_violation(graphs.add_edge(&enemies, rivendell)));
+
+    assert_eq!(graphs.remove_schema(rivendell), Ok(Some(prepare_schema())));
+    graphs.add_edge(&enemies, rivendell).unwrap();
+    assert_eq!(graphs.get_edges(rivendell).unwrap().len(), 1);
+    graphs.set_schema(Schema::new(), rivendell).unwrap();
+    graphs.delete_vault("rivendell").unwrap();
+    assert_eq!(graphs.get_schema(rivendell), None);
+}
+
+#[test]
+fn should_check_the_attributes_set_through_the_graphs() {
+    let mut graphs = Graphs::init("shire");
+    graphs.set_schema(prepare_schema(), None).unwrap();
+    let frodo = hobbit("Frodo");
+    let sam = hobbit("Sam");
+    graphs
+        .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+        .unwrap();
+
+    let id = frodo.get_id();
+    let result = graphs.set_vertex_attr(&id, "years old", "fifty", None);
+    assert!(is_violation(result));
+    assert!(frodo.get_attr("years old").is_err());
+    graphs.set_vertex_attr(&id, "years old", 50, None).unwrap();
+    assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+
+    assert!(is_violation(
+        graphs.set_vertex_attr(&id, "name", "Sam", None)
+    ));
+    assert!(is_violation(graphs.delete_vertex_attr(&id, "name", None)));
+    assert_eq!(frodo.get_attr("name").unwrap(), "Frodo");
+    graphs.delete_vertex_attr(&id, "years old", None).unwrap();
+}
+
+#[test]
+fn should_persist_the_schemas() {
+    let mut graphs = Graphs::init("shire");
+    graphs.set_schema(prepare_schema(), None).unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&hobbit("Frodo"), "friend of", &hobbit("Sam")),
+            None,
+        )
+        .unwrap();
+    graphs.insert("mordor").unwrap();
+    graphs.set_schema(Schema::new(), Some("mordor")).unwrap();
+    graphs.set_label("shire");
+    assert_eq!(graphs.get_mem_estimate(), graphs.get_mem().unwrap());
+
+    graphs.save(Some("tests/data/")).unwrap();
+    let file_name = "tests/data/shire.grphst";
+    let loaded = Graphs::load(file_name).unwrap();
+    fs::remove_file(file_name).unwrap();
+    assert_eq!(loaded.get_schema(None), Some(&prepare_schema()));
+    assert_eq!(loaded.get_schema(Some("mordor")), Some(&Schema::new()));
+
+    // on the files of a store, also of an unloaded vault
+    let dir = "tests/data/schema-store";
+    graphs.save_store(dir).unwrap();
+    graphs.unload_vault("shire").unwrap();
+    assert_eq!(graphs.get_schema(None), Some(&prepare_schema()));
+    assert!(is_violation(graphs.add_vertex(&hobbit("Frodo"), None)));
+    let mut opened = Graphs::open_store(dir).unwrap();
+    assert_eq!(opened.get_schema(None), Some(&prepare_schema()));
+    assert!(is_violation(opened.add_vertex(&hobbit("Sam"), None)));
+    assert_eq!(
+        opened.remove_schema(Some("mordor")),
+        Ok(Some(Schema::new()))
+    );
+    fs::remove_dir_all(dir).unwrap();
+
+    // on the write-ahead log
+    let dir = "tests/data/schema-wal";
+    let _ = fs::remove_dir_all(dir);
+    {
+        let mut graphs = Graphs::open(dir).unwrap();
+        graphs.insert("shire").unwrap();
+        graphs.set_schema(prepare_schema(), Some("shire")).unwrap();
+        graphs.insert("mordor").unwrap();
+        graphs.set_schema(Schema::new(), Some("mordor")).unwrap();
+        graphs.remove_schema(Some("mordor")).unwrap();
+    }
+    let graphs = Graphs::open(dir).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+    assert_eq!(graphs.get_schema(Some("shire")), Some(&prepare_schema()));
+    assert_eq!(graphs.get_schema(Some("mordor")), None);
+}
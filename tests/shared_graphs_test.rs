@@ -0,0 +1,189 @@
+#![cfg(feature = "sync")]
+
+use std::{thread, time::Duration};
+
+use gruphst::{
+    edge::Edge,
+    graphs::{paths::PathOptions, AutoPersist, Graphs, SharedGraphs},
+    vertex::Vertex,
+};
+
+const THREADS: usize = 8;
+const EDGES_PER_THREAD: usize = 20;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn graphs_vertex_and_edge_are_send_and_sync() {
+    assert_send_sync::<Graphs>();
+    assert_send_sync::<SharedGraphs>();
+    assert_send_sync::<Vertex>();
+    assert_send_sync::<Edge>();
+    assert_send_sync::<PathOptions>();
+}
+
+#[test]
+fn should_find_paths_with_astar_from_other_threads() {
+    let graphs = SharedGraphs::init("middle-earth");
+    let the_shire = Vertex::new("The Shire");
+    let rivendell = Vertex::new("Rivendell");
+    let mut road = Edge::create(&the_shire, "road to", &rivendell);
+    road.set_attr("km", 500);
+    graphs.write().add_edge(&road, None).unwrap();
+
+    let options = PathOptions::astar("km", |_current, _target| 0.0);
+    let handle = thread::spawn(move || {
+        graphs
+            .read()
+            .shortest_path(&the_shire.get_id(), &rivendell.get_id(), &options, None)
+            .unwrap()
+            .get_cost()
+    });
+    assert_eq!(handle.join().unwrap(), 500.0);
+}
+
+#[test]
+fn should_add_edges_from_many_threads() {
+    let graphs = SharedGraphs::init("middle-earth");
+    let the_shire = Vertex::new("The Shire");
+    graphs.write().add_vertex(&the_shire, None).unwrap();
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let graphs = graphs.clone();
+            let the_shire = the_shire.clone();
+            thread::spawn(move || {
+                for i in 0..EDGES_PER_THREAD {
+                    let hobbit = Vertex::new(&format!("hobbit {}-{}", t, i));
+                    let edge = Edge::create(&hobbit, "lives at", &the_shire);
+                    graphs.write().add_edge(&edge, None).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let graphs = graphs.read();
+    assert_eq!(graphs.len(), THREADS * EDGES_PER_THREAD);
+    assert_eq!(
+        graphs.get_vertices(None).unwrap().len(),
+        THREADS * EDGES_PER_THREAD + 1
+    );
+}
+
+#[test]
+fn should_read_while_other_threads_write() {
+    let graphs = SharedGraphs::init("middle-earth");
+    let gandalf = Vertex::new("Gandalf");
+    graphs
+        .write()
+        .add_edge(
+            &Edge::create(&gandalf, "friend of", &Vertex::new("Frodo")),
+            None,
+        )
+        .unwrap();
+
+    let writers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let graphs = graphs.clone();
+            let gandalf = gandalf.clone();
+            thread::spawn(move || {
+                for i in 0..EDGES_PER_THREAD {
+                    let friend = Vertex::new(&format!("friend {}-{}", t, i));
+                    graphs
+                        .with_write(|g| {
+                            g.add_edge(&Edge::create(&gandalf, "friend of", &friend), None)
+                        })
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    let readers: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let graphs = graphs.clone();
+            let gandalf_id = gandalf.get_id();
+            thread::spawn(move || {
+                for _ in 0..EDGES_PER_THREAD {
+                    let found = graphs.with_read(|g| {
+                        g.find_vertex_by_id(&gandalf_id, None).unwrap();
+                        g.find_edges_by_relation("friend of", None).unwrap().len()
+                    });
+                    assert!(found >= 1);
+                }
+            })
+        })
+        .collect();
+    for handle in writers.into_iter().chain(readers) {
+        handle.join().unwrap();
+    }
+
+    let found = graphs
+        .read()
+        .find_edges_by_relation("friend of", None)
+        .unwrap()
+        .len();
+    assert_eq!(found, THREADS * EDGES_PER_THREAD + 1);
+}
+
+#[test]
+fn should_share_vertex_attributes_between_threads() {
+    let graphs = SharedGraphs::init("middle-earth");
+    let gandalf = Vertex::new("Gandalf");
+    graphs
+        .write()
+        .add_edge(
+            &Edge::create(&gandalf, "friend of", &Vertex::new("Frodo")),
+            None,
+        )
+        .unwrap();
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let graphs = graphs.clone();
+            let gandalf_id = gandalf.get_id();
+            thread::spawn(move || {
+                let mut vertex = graphs.read().find_vertex_by_id(&gandalf_id, None).unwrap();
+                vertex.set_attr(&format!("visited by {}", t), "yes");
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let edges = graphs.read().get_edges(None).unwrap();
+    assert_eq!(edges[0].get_from_vertex().attrs_len(), THREADS);
+}
+
+#[test]
+fn should_auto_persist_on_a_background_thread() {
+    let file_name = "./tests/data/auto-persist-shared.grphst";
+    let _ = std::fs::remove_file(file_name);
+    let graphs = SharedGraphs::init("auto-persist-shared");
+    graphs.set_auto_persist(
+        AutoPersist::Interval(Duration::from_millis(20)),
+        Some("./tests/data/"),
+    );
+    let edge = Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam"));
+    graphs.write().add_edge(&edge, None).unwrap();
+
+    // saved without any other mutation
+    let mut saved = None;
+    for _ in 0..50 {
+        thread::sleep(Duration::from_millis(20));
+        if let Ok(loaded) = Graphs::load(file_name) {
+            saved = Some(loaded);
+            break;
+        }
+    }
+    assert_eq!(saved.unwrap().len(), 1);
+
+    graphs.write().add_edge(&edge, None).unwrap();
+    drop(graphs);
+    // flushed when the last handle is dropped
+    assert_eq!(Graphs::load(file_name).unwrap().len(), 2);
+    std::fs::remove_file(file_name).unwrap();
+}
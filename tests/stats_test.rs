@@ -25,22 +25,22 @@ fn prepare_stats_test() -> Graphs {
 
     let mut edge = Edge::create(&alice, relation_friend_of, &bob);
     edge.set_attr("foo", "bar 0");
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
 
     edge = Edge::create(&alice, relation_relative_of, &fred);
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
 
     edge = Edge::create(&alice, "enemy of", &john);
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
 
     edge = Edge::create(&peter, relation_relative_of, &john);
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
 
-    graphs.insert("only relatives");
+    graphs.insert("only relatives").unwrap();
     edge.set_attr("foo one", "bar 1");
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
     edge = Edge::create(&bob, "brother of", &john);
-    graphs.add_edge(&edge, None);
+    graphs.add_edge(&edge, None).unwrap();
 
     graphs
 }
@@ -52,7 +52,7 @@ fn graphs_stats() {
     let stats = graphs.get_stats();
     assert_eq!(stats.get_total_edges(), 6);
     assert_eq!(stats.get_total_attr(), 19);
-    assert_eq!(stats.get_mem(), 2017);
+    assert_eq!(stats.get_mem(), 2114);
     assert_eq!(stats.get_uniq_rel(), 4);
     assert_eq!(stats.get_total_graphs(), 2);
     assert_eq!(stats.get_total_vertices(), 12);
@@ -0,0 +1,185 @@
+use std::fs;
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{AutoPersist, Graphs, IndexKind, IndexTarget},
+    vertex::Vertex,
+};
+
+fn prepare_transaction_test() -> (Graphs, Vertex, Edge) {
+    let mut graphs = Graphs::init("fellowship");
+    let mut frodo = Vertex::new("Frodo");
+    frodo.set_attr("years old", 50);
+    let friends = Edge::create(&frodo, "friend of", &Vertex::new("Sam"));
+    graphs.add_edge(&friends, None).unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Gandalf"), "friend of", &frodo),
+            None,
+        )
+        .unwrap();
+    (graphs, frodo, friends)
+}
+
+#[test]
+fn should_commit_every_change_of_a_transaction() {
+    let (mut graphs, frodo, friends) = prepare_transaction_test();
+    let added = graphs
+        .transaction(|tx| {
+            let edge = Edge::create(&frodo, "lives at", &Vertex::new("Bag End"));
+            tx.add_edge(&edge, None)?;
+            tx.add_edge(&edge, Some("shire"))?;
+            let mut updated = friends.clone();
+            updated.set_attr("since", 2968);
+            tx.update_graph(&updated, Some("fellowship"))?;
+            tx.set_vertex_attr(&frodo.get_id(), "ring bearer", true, Some("shire"))?;
+            Ok(edge.get_id())
+        })
+        .unwrap();
+    assert_eq!(graphs.len(), 4);
+    assert_eq!(graphs.len_graphs(), 2);
+    assert_eq!(graphs.get_label(), "shire");
+    assert!(frodo.get_attr_bool("ring bearer").unwrap());
+    let edge = graphs.find_edge_by_id(&added, None).unwrap();
+    assert_eq!(edge.get_relation(), "lives at");
+    let edge = graphs
+        .find_edge_by_id(&friends.get_id(), Some("fellowship"))
+        .unwrap();
+    assert_eq!(edge.get_attr_i64("since").unwrap(), 2968);
+}
+
+#[test]
+fn should_roll_back_every_change_of_a_failed_transaction() {
+    let (mut graphs, mut frodo, friends) = prepare_transaction_test();
+    let mem = graphs.get_mem().unwrap();
+    let mut sam = friends.get_to_vertex();
+    let result = graphs.transaction(|tx| {
+        tx.add_edge(
+            &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+            Some("shire"),
+        )?;
+        tx.set_label("fellowship");
+        let mut updated = friends.clone();
+        updated.set_attr("since", 2968);
+        tx.update_graph(&updated, None)?;
+        tx.delete_edge_by_id(friends.get_id(), None)?;
+        tx.set_vertex_attr(&frodo.get_id(), "years old", 51, None)?;
+        tx.delete_vertex_attr(&frodo.get_id(), "years old", None)?;
+        // changes on the shared vertices are rolled back too
+        sam.set_attr("gardener", true);
+        frodo.set_label("Mr. Underhill");
+        tx.update_graph(&Edge::create(&frodo, "not", &sam), None)?;
+        Ok(())
+    });
+    assert_eq!(result, Err(GruPHstError::EdgeNotFound));
+    assert_eq!(graphs.len(), 2);
+    assert_eq!(graphs.len_graphs(), 1);
+    assert_eq!(graphs.get_label(), "fellowship");
+    assert!(graphs.get_edges(None).unwrap().contains(&friends));
+    assert_eq!(frodo.get_label(), "Frodo");
+    assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+    assert!(sam.get_attr_bool("gardener").is_err());
+    let found = graphs.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    assert_eq!(found.get_attr_i64("years old").unwrap(), 50);
+    assert_eq!(graphs.get_mem().unwrap(), mem);
+    assert_eq!(graphs.get_mem_estimate(), mem);
+}
+
+#[test]
+fn should_roll_back_a_transaction_inside_another_one() {
+    let (mut graphs, frodo, _) = prepare_transaction_test();
+    graphs
+        .transaction(|tx| {
+            tx.add_vertex(&Vertex::new("Aragorn"), None)?;
+            let inner = tx.transaction(|tx| {
+                tx.set_vertex_attr(&frodo.get_id(), "years old", 51, None)?;
+                tx.remove_vertex("not a vertex", None)
+            });
+            assert_eq!(inner, Err(GruPHstError::VertexNotFound));
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(graphs.get_vertices(None).unwrap().len(), 4);
+    assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+}
+
+#[test]
+fn should_roll_back_the_vertices_changed_before_their_vault() {
+    let (mut graphs, mut frodo, _) = prepare_transaction_test();
+    graphs
+        .create_index("years old", IndexTarget::Vertex, IndexKind::BTree, None)
+        .unwrap();
+    let result = graphs.transaction(|tx| {
+        frodo.set_attr("years old", 33);
+        tx.transaction(|tx| {
+            tx.set_vertex_attr(&frodo.get_id(), "years old", 51, None)?;
+            tx.add_vertex(&Vertex::new("Bilbo"), Some("rivendell"))
+        })?;
+        tx.remove_vertex("not a vertex", None)
+    });
+    assert_eq!(result, Err(GruPHstError::VertexNotFound));
+    assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+    assert_eq!(graphs.len_graphs(), 1);
+    let found = graphs
+        .find_vertices_with_attr_equals_to("years old", 50, None)
+        .unwrap();
+    assert_eq!(found, vec![frodo.clone()]);
+    assert!(graphs
+        .find_vertices_with_attr_equals_to("years old", 51, None)
+        .is_err());
+}
+
+#[test]
+fn should_log_and_persist_a_committed_transaction_once() {
+    let dir = "tests/data/wal-transaction";
+    let _ = fs::remove_dir_all(dir);
+    let frodo = Vertex::new("Frodo");
+    {
+        let mut graphs = Graphs::open(dir).unwrap();
+        graphs
+            .transaction(|tx| {
+                tx.add_edge(
+                    &Edge::create(&frodo, "friend of", &Vertex::new("Sam")),
+                    None,
+                )?;
+                tx.set_vertex_attr(&frodo.get_id(), "years old", 50, None)
+            })
+            .unwrap();
+        let result = graphs.transaction(|tx| {
+            tx.add_edge(
+                &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+                None,
+            )?;
+            tx.delete_vault("mordor")
+        });
+        assert!(result.is_err());
+    }
+    let graphs = Graphs::open(dir).unwrap();
+    assert_eq!(graphs.len(), 1);
+    let frodo = graphs.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+    drop(graphs);
+    fs::remove_dir_all(dir).unwrap();
+
+    let file = "./tests/data/transaction-persisted.grphst";
+    let _ = fs::remove_file(file);
+    let mut graphs = Graphs::init("transaction-persisted");
+    graphs.set_auto_persist(AutoPersist::OnWrite, Some("./tests/data/"));
+    graphs
+        .transaction(|tx| {
+            tx.add_edge(
+                &Edge::create(&frodo, "friend of", &Vertex::new("Sam")),
+                None,
+            )?;
+            assert!(fs::metadata(file).is_err());
+            tx.add_edge(
+                &Edge::create(&frodo, "lives at", &Vertex::new("Bag End")),
+                None,
+            )
+        })
+        .unwrap();
+    assert_eq!(Graphs::load(file).unwrap().len(), 2);
+    drop(graphs);
+    fs::remove_file(file).unwrap();
+}
@@ -0,0 +1,170 @@
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::traversal::{Direction, TraversalOptions},
+    graphs::Graphs,
+    vertex::Vertex,
+};
+
+fn prepare_traversal_test() -> (Graphs, Vertex, Vertex, Vertex, Vertex, Vertex) {
+    let mut graphs = Graphs::init("middle-earth");
+    let frodo = Vertex::new("Frodo");
+    let sam = Vertex::new("Sam");
+    let gandalf = Vertex::new("Gandalf");
+    let the_shire = Vertex::new("The Shire");
+    let mordor = Vertex::new("Mordor");
+
+    graphs
+        .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&sam, "friend of", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&gandalf, "friend of", &frodo), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&frodo, "lives at", &the_shire), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&sam, "lives at", &the_shire), None)
+        .unwrap();
+    graphs
+        .add_edge(&Edge::create(&the_shire, "far from", &mordor), None)
+        .unwrap();
+
+    (graphs, frodo, sam, gandalf, the_shire, mordor)
+}
+
+fn labels(steps: &[(usize, Vertex, Edge)]) -> Vec<String> {
+    steps.iter().map(|(_, v, _)| v.get_label()).collect()
+}
+
+#[test]
+fn should_traverse_breadth_first() {
+    let (graphs, frodo, _sam, _gandalf, _the_shire, _mordor) = prepare_traversal_test();
+    let steps = graphs
+        .traverse(&frodo.get_id(), &TraversalOptions::bfs(), None)
+        .unwrap();
+    assert_eq!(labels(&steps), vec!["Sam", "The Shire", "Mordor"]);
+    let depths: Vec<usize> = steps.iter().map(|(d, _, _)| *d).collect();
+    assert_eq!(depths, vec![1, 1, 2]);
+    assert_eq!(steps[2].2.get_relation(), "far from");
+}
+
+#[test]
+fn should_traverse_depth_first() {
+    let (graphs, frodo, _sam, _gandalf, _the_shire, _mordor) = prepare_traversal_test();
+    let steps = graphs
+        .traverse(
+            &frodo.get_id(),
+            &TraversalOptions::dfs().direction(Direction::Both),
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        labels(&steps),
+        vec!["Sam", "The Shire", "Mordor", "Gandalf"]
+    );
+    let depths: Vec<usize> = steps.iter().map(|(d, _, _)| *d).collect();
+    assert_eq!(depths, vec![1, 2, 3, 1]);
+}
+
+#[test]
+fn should_traverse_following_direction_and_relations() {
+    let (graphs, frodo, _sam, _gandalf, the_shire, _mordor) = prepare_traversal_test();
+    let steps = graphs
+        .traverse(
+            &the_shire.get_id(),
+            &TraversalOptions::bfs().direction(Direction::In),
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(&steps), vec!["Frodo", "Sam", "Gandalf"]);
+
+    let steps = graphs
+        .traverse(
+            &frodo.get_id(),
+            &TraversalOptions::bfs()
+                .direction(Direction::Both)
+                .relation("friend of"),
+            None,
+        )
+        .unwrap();
+    assert_eq!(labels(&steps), vec!["Sam", "Gandalf"]);
+}
+
+#[test]
+fn should_traverse_until_max_depth() {
+    let (graphs, frodo, _sam, _gandalf, _the_shire, _mordor) = prepare_traversal_test();
+    let steps = graphs
+        .traverse(&frodo.get_id(), &TraversalOptions::dfs().max_depth(1), None)
+        .unwrap();
+    assert_eq!(labels(&steps), vec!["Sam", "The Shire"]);
+    let steps = graphs
+        .traverse(&frodo.get_id(), &TraversalOptions::bfs().max_depth(0), None)
+        .unwrap();
+    assert!(steps.is_empty());
+}
+
+#[test]
+fn should_stop_traversal_from_visitor() {
+    let (graphs, frodo, _sam, _gandalf, _the_shire, _mordor) = prepare_traversal_test();
+    let mut visited = Vec::new();
+    graphs
+        .traverse_with(
+            &frodo.get_id(),
+            &TraversalOptions::bfs(),
+            None,
+            |depth, vertex, _edge| {
+                visited.push(vertex.get_label());
+                depth < 1 || vertex.get_label() != "Sam"
+            },
+        )
+        .unwrap();
+    assert_eq!(visited, vec!["Sam"]);
+}
+
+#[test]
+fn should_traverse_across_vaults() {
+    let (mut graphs, frodo, _sam, _gandalf, _the_shire, mordor) = prepare_traversal_test();
+    graphs
+        .add_edge(
+            &Edge::create(&mordor, "ruled by", &Vertex::new("Sauron")),
+            Some("shadows"),
+        )
+        .unwrap();
+    let steps = graphs
+        .traverse(
+            &frodo.get_id(),
+            &TraversalOptions::bfs(),
+            Some("middle-earth"),
+        )
+        .unwrap();
+    assert_eq!(steps.len(), 3);
+    let steps = graphs
+        .traverse_in_graphs(&frodo.get_id(), &TraversalOptions::bfs())
+        .unwrap();
+    assert_eq!(labels(&steps), vec!["Sam", "The Shire", "Mordor", "Sauron"]);
+    assert_eq!(steps[3].0, 3);
+}
+
+#[test]
+fn should_fail_traversing_from_unknown_vertex() {
+    let (graphs, _frodo, _sam, _gandalf, _the_shire, _mordor) = prepare_traversal_test();
+    let options = TraversalOptions::bfs();
+    assert_eq!(
+        graphs.traverse("foobar", &options, None).unwrap_err(),
+        GruPHstError::VertexNotFound
+    );
+    assert_eq!(
+        graphs.traverse_in_graphs("foobar", &options).unwrap_err(),
+        GruPHstError::VertexNotFound
+    );
+    assert_eq!(
+        graphs
+            .traverse("foobar", &options, Some("foobar"))
+            .unwrap_err(),
+        GruPHstError::VaultNotExists(String::from("foobar"))
+    );
+}
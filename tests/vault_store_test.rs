@@ -0,0 +1,265 @@
+use std::fs;
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{filter::Filter, traversal::TraversalOptions, EncryptionKey, Graphs, MemoryPolicy},
+    vertex::Vertex,
+};
+
+fn middle_earth() -> Graphs {
+    let mut graphs = Graphs::init("shire");
+    let frodo = Vertex::new("Frodo");
+    let sam = Vertex::new("Sam");
+    graphs
+        .add_edge(&Edge::create(&frodo, "friend of", &sam), None)
+        .unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&sam, "lives at", &Vertex::new("Bag Shot Row")),
+            None,
+        )
+        .unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "member of", &Vertex::new("Fellowship")),
+            Some("fellowship"),
+        )
+        .unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Sauron"), "created", &Vertex::new("One Ring")),
+            Some("mordor"),
+        )
+        .unwrap();
+    graphs.set_label("shire");
+    graphs
+}
+
+fn stored_files(dir: &str) -> usize {
+    fs::read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn should_load_every_vault_on_the_first_access() {
+    let dir = "./tests/data/store-lazy";
+    let mut graphs = middle_earth();
+    graphs.save_store(dir).unwrap();
+    // the manifest and a file per vault
+    assert_eq!(stored_files(dir), 4);
+
+    let mut opened = Graphs::open_store(dir).unwrap();
+    assert_eq!(opened.get_label(), "shire");
+    assert_eq!(opened.len_graphs(), 3);
+    assert_eq!(opened.get_evicted_vaults().len(), 3);
+    assert!(opened.get_mem_estimate() < graphs.get_mem_estimate());
+
+    assert_eq!(opened.get_edges(None).unwrap().len(), 2);
+    assert!(!opened.is_evicted("shire"));
+    assert!(opened.is_evicted("fellowship"));
+    assert_eq!(opened.get_mem_estimate(), opened.get_mem().unwrap());
+    let frodo = opened.find_vertex_by_id_in_graphs(
+        &graphs.get_edges(None).unwrap()[0]
+            .get_from_vertex()
+            .get_id(),
+    );
+    assert_eq!(frodo.unwrap().get_label(), "Frodo");
+
+    // the vertices shared by the vaults are linked once loaded
+    let frodo_id = opened.get_edges(Some("fellowship")).unwrap()[0]
+        .get_from_vertex()
+        .get_id();
+    opened
+        .set_vertex_attr(&frodo_id, "ring bearer", true, Some("fellowship"))
+        .unwrap();
+    let shire = opened.find_vertex_by_id(&frodo_id, None).unwrap();
+    assert!(shire.get_attr_bool("ring bearer").unwrap());
+    assert!(!opened.is_evicted("fellowship"));
+    assert_eq!(opened.len(), 4);
+    assert!(opened.get_evicted_vaults().is_empty());
+
+    // a saved Graphs includes the vaults not loaded yet
+    opened.save(Some("./tests/data/")).unwrap();
+    let loaded = Graphs::load("./tests/data/shire.grphst").unwrap();
+    assert_eq!(loaded.len(), 4);
+    fs::remove_file("./tests/data/shire.grphst").unwrap();
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_query_every_vault_of_a_store_not_loaded_yet() {
+    let dir = "./tests/data/store-queries";
+    let mut graphs = middle_earth();
+    let mut sauron = Vertex::new("Sauron");
+    sauron.set_attr("years old", 7000);
+    let mut forged = Edge::create(&sauron, "forged", &Vertex::new("One Ring"));
+    forged.set_attr("year", 1600);
+    graphs.add_edge(&forged, Some("mordor")).unwrap();
+    graphs.set_label("shire");
+    graphs.save_store(dir).unwrap();
+    let sauron_id = sauron.get_id();
+
+    // every query runs on a store just opened, with its vaults on disk
+    let opened = || Graphs::open_store(dir).unwrap();
+    let found = opened().find_vertex_by_id_in_graphs(&sauron_id).unwrap();
+    assert_eq!(found.get_label(), "Sauron");
+    let aged = opened()
+        .find_vertices_with_attr_in_range_in_graphs("years old", 5000..)
+        .unwrap();
+    assert_eq!(aged[0].get_label(), "Sauron");
+    let graphs = opened();
+    let edges = graphs
+        .find_edges_with_attr_in_range_in_graphs("year", 1000..)
+        .unwrap();
+    assert_eq!(edges[0].get_relation(), "forged");
+    let graphs = opened();
+    let edges = graphs
+        .find_edges_with_vertex_attr_in_range_in_graphs("years old", ..=7000)
+        .unwrap();
+    assert_eq!(edges[0].get_relation(), "forged");
+    let edge = opened()
+        .find_edge_by_id_in_graphs(&forged.get_id())
+        .unwrap()
+        .clone();
+    assert_eq!(edge.get_relation(), "forged");
+    let graphs = opened();
+    let edges = graphs
+        .query_edges_in_graphs(&Filter::relation("forged"))
+        .unwrap();
+    assert_eq!(edges.len(), 1);
+    let steps = opened()
+        .traverse_in_graphs(&sauron_id, &TraversalOptions::default())
+        .unwrap();
+    assert_eq!(steps[0].1.get_label(), "One Ring");
+    assert_eq!(opened().get_vaults().unwrap().len(), 3);
+    assert_eq!(opened().get_uniq_vertices_on_graphs().unwrap().len(), 8);
+    assert_eq!(opened().uniq_relations().len(), 5);
+    assert_eq!(opened().len(), 5);
+    assert_eq!(opened().get_stats().get_total_edges(), 5);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_save_and_unload_a_vault_on_its_own() {
+    let dir = "./tests/data/store-vaults";
+    let mut graphs = middle_earth();
+    assert_eq!(
+        graphs
+            .save_vault("mordor")
+            .unwrap_err()
+            .downcast_ref::<GruPHstError>(),
+        Some(&GruPHstError::NoVaultStore)
+    );
+    graphs.save_store(dir).unwrap();
+
+    let mut opened = Graphs::open_store(dir).unwrap();
+    opened
+        .add_edge(
+            &Edge::create(&Vertex::new("Nazgûl"), "serves", &Vertex::new("Sauron")),
+            Some("mordor"),
+        )
+        .unwrap();
+    opened.save_vault("mordor").unwrap();
+    assert!(opened.save_vault("isengard").is_err());
+    let reopened = Graphs::open_store(dir).unwrap();
+    assert_eq!(reopened.get_edges(Some("mordor")).unwrap().len(), 2);
+    assert_eq!(reopened.get_edges(Some("shire")).unwrap().len(), 2);
+
+    opened.unload_vault("mordor").unwrap();
+    assert!(opened.is_evicted("mordor"));
+    assert_eq!(opened.len(), 5);
+    assert_eq!(opened.get_edges(Some("mordor")).unwrap().len(), 2);
+    opened.unload_vault("mordor").unwrap();
+    assert!(opened.is_evicted("mordor"));
+
+    // the file of a deleted vault is removed on the next save
+    opened.delete_vault("fellowship").unwrap();
+    assert_eq!(stored_files(dir), 4);
+    opened.save_store(dir).unwrap();
+    assert_eq!(stored_files(dir), 3);
+    let reopened = Graphs::open_store(dir).unwrap();
+    assert_eq!(reopened.len_graphs(), 2);
+    assert_eq!(
+        reopened.get_edges(Some("fellowship")),
+        Err(GruPHstError::VaultNotExists(String::from("fellowship")))
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_evict_the_vaults_to_the_store() {
+    let dir = "./tests/data/store-evicted";
+    let mut graphs = Graphs::init("rivendell");
+    graphs.set_memory_policy(MemoryPolicy::Evict);
+    graphs.insert("lothlorien").unwrap();
+    graphs.save_store(dir).unwrap();
+    let edge = Edge::create(
+        &Vertex::new("Elrond"),
+        "lives at",
+        &Vertex::new("Rivendell"),
+    );
+    while graphs.get_mem().unwrap() * 100 < graphs.get_graphs_stats().get_max_mem() * 50 {
+        graphs.add_edge(&edge, None).unwrap();
+    }
+    let lothlorien = graphs.len();
+    graphs.set_label("rivendell");
+    while graphs.get_evicted_vaults().is_empty() {
+        graphs.add_edge(&edge, None).unwrap();
+    }
+    assert_eq!(graphs.get_evicted_vaults(), vec!["lothlorien"]);
+
+    let opened = Graphs::open_store(dir).unwrap();
+    assert_eq!(
+        opened.get_edges(Some("lothlorien")).unwrap().len(),
+        lothlorien
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_check_the_header_of_the_manifest() {
+    let dir = "./tests/data/store-manifest";
+    middle_earth().save_store(dir).unwrap();
+    let manifest = format!("{}/vaults.grphst", dir);
+    let bytes = fs::read(&manifest).unwrap();
+    assert!(bytes.starts_with(b"GRPHSTV\0"));
+    assert_eq!(u16::from_le_bytes([bytes[8], bytes[9]]), 1);
+
+    let open_error = |bytes: &[u8]| {
+        fs::write(&manifest, bytes).unwrap();
+        let err = Graphs::open_store(dir).unwrap_err();
+        err.downcast::<GruPHstError>().map(|err| *err).unwrap()
+    };
+    let mut corrupted = bytes.clone();
+    *corrupted.last_mut().unwrap() ^= 0xff;
+    assert_eq!(
+        open_error(&corrupted),
+        GruPHstError::SnapshotChecksumMismatch
+    );
+    let mut unknown_version = bytes.clone();
+    unknown_version[8] = 9;
+    assert_eq!(
+        open_error(&unknown_version),
+        GruPHstError::SnapshotUnsupportedVersion(9)
+    );
+    assert_eq!(
+        open_error(b"not a manifest"),
+        GruPHstError::SnapshotBadMagic
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_not_save_a_store_with_a_key() {
+    let dir = "./tests/data/store-encrypted";
+    let mut graphs = middle_earth();
+    let key = EncryptionKey::new([42; 32]);
+    graphs.save_encrypted(Some("./tests/data/"), &key).unwrap();
+    assert_eq!(
+        graphs.save_store(dir).unwrap_err().to_string(),
+        GruPHstError::SnapshotEncryptionFailed.to_string()
+    );
+    assert_eq!(stored_files(dir), 0);
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_file("./tests/data/shire.grphst").unwrap();
+}
@@ -1,7 +1,9 @@
+#![allow(clippy::assertions_on_constants, clippy::cmp_owned)]
+
 use std::collections::HashMap;
 
 use graphs_test::{prepare_graphs_test, prepare_insert_graph_test};
-use gruphst::{edge::Edge, errors::GruPHstError, vertex::Vertex};
+use gruphst::{attributes::AttrValue, edge::Edge, errors::GruPHstError, vertex::Vertex};
 
 #[path = "./graphs_test.rs"]
 mod graphs_test;
@@ -146,10 +148,12 @@ fn get_vertex_relation_out() {
     assert_eq!(find_results.len(), 1);
     assert_eq!(find_results[0].get_label(), "Fred");
     let vertex = find_results[0].clone();
-    graphs.add_edge(
-        &Edge::create(&vertex, "relative of", &Vertex::new("Peter")),
-        Some("my graphs"),
-    );
+    graphs
+        .add_edge(
+            &Edge::create(&vertex, "relative of", &Vertex::new("Peter")),
+            Some("my graphs"),
+        )
+        .unwrap();
     let relations_out: HashMap<String, Vec<Vertex>> = vertex
         .get_relations_out_on_edges(graphs.get_edges(Some("my graphs")).unwrap())
         .unwrap();
@@ -199,10 +203,12 @@ fn get_vertex_relation_in() {
         }
     }
 
-    graphs.add_edge(
-        &Edge::create(&Vertex::new("Peter"), "friend of", &vertex),
-        Some("my graphs"),
-    );
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Peter"), "friend of", &vertex),
+            Some("my graphs"),
+        )
+        .unwrap();
 
     let relations_in: HashMap<String, Vec<Vertex>> = vertex
         .get_relations_in_on_edges(graphs.get_edges(Some("my graphs")).unwrap())
@@ -301,3 +307,50 @@ fn should_store_and_validate_a_cryptographic_hashed_value() {
         .unwrap());
     assert!(vertex.is_hash_valid("foo bar attr", plain_text).is_err());
 }
+
+#[test]
+fn should_get_typed_vertex_attributes() {
+    let (mut vertex, _id) = prepare_vertex_test();
+    vertex.set_attr("height", 1.22);
+    vertex.set_attr("ring bearer", true);
+    vertex.set_attr("born", AttrValue::Timestamp(-3_000));
+    vertex.set_attr("friends", vec![AttrValue::from("Sam"), AttrValue::from(4)]);
+
+    assert_eq!(vertex.get_attr_i64("age").unwrap(), 42);
+    assert_eq!(vertex.get_attr_f64("age").unwrap(), 42.0);
+    assert_eq!(vertex.get_attr_f64("height").unwrap(), 1.22);
+    assert!(vertex.get_attr_bool("ring bearer").unwrap());
+    assert_eq!(vertex.get_attr_timestamp("born").unwrap(), -3_000);
+    assert_eq!(
+        vertex.get_attr_list("friends").unwrap()[1],
+        AttrValue::Int(4)
+    );
+    assert_eq!(vertex.get_attr("friends").unwrap(), "[Sam, 4]");
+    assert_eq!(
+        vertex.get_attr_value("name").unwrap(),
+        AttrValue::Str(String::from("Alice"))
+    );
+}
+
+#[test]
+fn should_fail_getting_vertex_attribute_with_other_type() {
+    let (vertex, _id) = prepare_vertex_test();
+    assert_eq!(
+        vertex.get_attr_i64("name"),
+        Err(GruPHstError::AttributeTypeMismatch(
+            String::from("name"),
+            String::from("integer")
+        ))
+    );
+    assert_eq!(
+        vertex.get_attr("code"),
+        Err(GruPHstError::AttributeTypeMismatch(
+            String::from("code"),
+            String::from("string")
+        ))
+    );
+    assert_eq!(
+        vertex.get_attr_bool("foobar"),
+        Err(GruPHstError::AttributeNotFound)
+    );
+}
@@ -0,0 +1,209 @@
+use std::{fs, io::Write, path::Path};
+
+use gruphst::{
+    edge::Edge,
+    errors::GruPHstError,
+    graphs::{EncryptionKey, Graphs},
+    vertex::Vertex,
+};
+
+fn prepare_wal_test(dir: &str) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn files(dir: &str) -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn should_replay_the_log_after_a_crash() {
+    let dir = "tests/data/wal-replay";
+    prepare_wal_test(dir);
+    let frodo = Vertex::new("Frodo");
+    let sam = Vertex::new("Sam");
+    let gandalf = Vertex::new("Gandalf");
+    let friends = Edge::create(&frodo, "friend of", &sam);
+    {
+        let mut graphs = Graphs::open(dir).unwrap();
+        assert_eq!(graphs.get_label(), "wal-replay");
+        graphs.add_edge(&friends, None).unwrap();
+        let mut edges = vec![
+            Edge::create(&gandalf, "friend of", &frodo),
+            Edge::create(&sam, "lives at", &Vertex::new("The Shire")),
+        ];
+        graphs.add_edges(&mut edges, None).unwrap();
+        graphs
+            .set_vertex_attr(&frodo.get_id(), "years old", 50, None)
+            .unwrap();
+        graphs
+            .set_vertex_attr(&sam.get_id(), "gardener", true, None)
+            .unwrap();
+        graphs
+            .delete_vertex_attr(&sam.get_id(), "gardener", None)
+            .unwrap();
+        let mut updated = friends.clone();
+        updated.set_attr("since", 2980);
+        graphs.update_graph(&updated, None).unwrap();
+        graphs
+            .add_vertex(&Vertex::new("Tom Bombadil"), None)
+            .unwrap();
+
+        graphs.insert("enemies").unwrap();
+        graphs
+            .add_edge(
+                &Edge::create(&Vertex::new("Sauron"), "enemy of", &gandalf),
+                None,
+            )
+            .unwrap();
+        graphs.insert("to delete").unwrap();
+        graphs.set_label("enemies");
+        graphs.delete_vault("to delete").unwrap();
+        // the process dies without saving
+    }
+
+    let graphs = Graphs::open(dir).unwrap();
+    assert_eq!(graphs.get_label(), "enemies");
+    assert_eq!(graphs.len_graphs(), 2);
+    assert_eq!(graphs.get_edges(Some("wal-replay")).unwrap().len(), 3);
+    assert_eq!(graphs.get_vertices(Some("wal-replay")).unwrap().len(), 5);
+    assert_eq!(
+        graphs.get_edges(None).unwrap()[0].get_relation(),
+        "enemy of"
+    );
+    let frodo = graphs
+        .find_vertex_by_id(&frodo.get_id(), Some("wal-replay"))
+        .unwrap();
+    assert_eq!(frodo.get_attr_i64("years old").unwrap(), 50);
+    let sam = graphs
+        .find_vertex_by_id(&sam.get_id(), Some("wal-replay"))
+        .unwrap();
+    assert_eq!(
+        sam.get_attr_value("gardener"),
+        Err(GruPHstError::AttributeNotFound)
+    );
+    let friends = graphs
+        .get_edges(Some("wal-replay"))
+        .unwrap()
+        .into_iter()
+        .find(|edge| edge.get_id() == friends.get_id())
+        .unwrap();
+    assert_eq!(friends.get_attr_i64("since").unwrap(), 2980);
+    // the vertex is shared again by its edges
+    assert_eq!(
+        friends.get_from_vertex().get_attr_i64("years old").unwrap(),
+        50
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_recover_from_a_partial_record() {
+    let dir = "tests/data/wal-partial";
+    prepare_wal_test(dir);
+    let mut graphs = Graphs::open(dir).unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam")),
+            None,
+        )
+        .unwrap();
+    let edge = Edge::create(&Vertex::new("Sam"), "lives at", &Vertex::new("The Shire"));
+    graphs.add_edge(&edge, None).unwrap();
+    drop(graphs);
+
+    // a record torn by a crash while writing
+    let log = Path::new(dir).join("wal-0.log");
+    let len = fs::metadata(&log).unwrap().len();
+    let mut file = fs::OpenOptions::new().append(true).open(&log).unwrap();
+    file.write_all(&[200, 0, 0, 0, 1, 2, 3]).unwrap();
+    drop(file);
+
+    let mut graphs = Graphs::open(dir).unwrap();
+    assert_eq!(graphs.get_edges(None).unwrap().len(), 2);
+    assert_eq!(fs::metadata(&log).unwrap().len(), len);
+    graphs.delete_edge_by_id(edge.get_id(), None).unwrap();
+    drop(graphs);
+
+    let graphs = Graphs::open(dir).unwrap();
+    assert_eq!(graphs.get_edges(None).unwrap().len(), 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_compact_the_log_into_a_snapshot() {
+    let dir = "tests/data/wal-compact";
+    prepare_wal_test(dir);
+    let mut graphs = Graphs::open(dir).unwrap();
+    let frodo = Vertex::new("Frodo");
+    graphs
+        .add_edge(
+            &Edge::create(&frodo, "friend of", &Vertex::new("Sam")),
+            None,
+        )
+        .unwrap();
+    graphs.compact().unwrap();
+    assert_eq!(files(dir), vec!["snapshot-1.grphst", "wal-1.log"]);
+    assert_eq!(
+        fs::metadata(Path::new(dir).join("wal-1.log"))
+            .unwrap()
+            .len(),
+        0
+    );
+
+    // GRUPHST_WAL_COMPACT_AFTER is 10 for tests
+    for i in 0..12 {
+        graphs
+            .set_vertex_attr(&frodo.get_id(), "counter", i, None)
+            .unwrap();
+    }
+    assert_eq!(files(dir), vec!["snapshot-2.grphst", "wal-2.log"]);
+    drop(graphs);
+
+    // a compaction that did not finish is discarded
+    fs::write(Path::new(dir).join("snapshot-3.tmp"), b"partial").unwrap();
+    fs::write(Path::new(dir).join("wal-3.log"), b"").unwrap();
+    let graphs = Graphs::open(dir).unwrap();
+    assert_eq!(files(dir), vec!["snapshot-2.grphst", "wal-2.log"]);
+    let frodo = graphs.find_vertex_by_id(&frodo.get_id(), None).unwrap();
+    assert_eq!(frodo.get_attr_i64("counter").unwrap(), 11);
+
+    let mut not_opened = Graphs::init("middle-earth");
+    assert_eq!(
+        not_opened.compact().unwrap_err().to_string(),
+        GruPHstError::NoWriteAheadLog.to_string()
+    );
+    // a clone is not written on the log
+    let mut cloned = graphs.clone();
+    assert!(cloned.compact().is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn should_not_compact_the_log_with_a_key() {
+    let dir = "tests/data/wal-encrypted";
+    prepare_wal_test(dir);
+    let mut graphs = Graphs::open(dir).unwrap();
+    graphs
+        .add_edge(
+            &Edge::create(&Vertex::new("Frodo"), "friend of", &Vertex::new("Sam")),
+            None,
+        )
+        .unwrap();
+    let key = EncryptionKey::new([42; 32]);
+    graphs
+        .save_encrypted(Some("tests/data/wal-"), &key)
+        .unwrap();
+    assert_eq!(
+        graphs.compact().unwrap_err().to_string(),
+        GruPHstError::SnapshotEncryptionFailed.to_string()
+    );
+    assert_eq!(files(dir), vec!["wal-0.log"]);
+    drop(graphs);
+    fs::remove_file("tests/data/wal-wal-encrypted.grphst").unwrap();
+    fs::remove_dir_all(dir).unwrap();
+}